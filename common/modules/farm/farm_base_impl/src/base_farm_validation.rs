@@ -7,6 +7,23 @@ use pausable::State;
 pub trait BaseFarmValidationModule {
     fn validate_contract_state(&self, current_state: State, farm_token_id: &TokenIdentifier) {
         require!(current_state == State::Active, ERROR_NOT_ACTIVE);
+        self.validate_farm_token_id(farm_token_id);
+    }
+
+    /// Exits are also allowed in the `WithdrawOnly` state
+    fn validate_contract_state_for_exit(
+        &self,
+        current_state: State,
+        farm_token_id: &TokenIdentifier,
+    ) {
+        require!(
+            current_state == State::Active || current_state == State::WithdrawOnly,
+            ERROR_NOT_ACTIVE
+        );
+        self.validate_farm_token_id(farm_token_id);
+    }
+
+    fn validate_farm_token_id(&self, farm_token_id: &TokenIdentifier) {
         require!(
             farm_token_id.is_valid_esdt_identifier(),
             ERROR_NO_FARM_TOKEN
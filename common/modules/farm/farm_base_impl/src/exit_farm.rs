@@ -36,7 +36,10 @@ pub trait BaseExitFarmModule:
         payment: EsdtTokenPayment<Self::Api>,
     ) -> InternalExitFarmResult<Self, FC::AttributesType> {
         let mut storage_cache = StorageCache::new(self);
-        self.validate_contract_state(storage_cache.contract_state, &storage_cache.farm_token_id);
+        self.validate_contract_state_for_exit(
+            storage_cache.contract_state,
+            &storage_cache.farm_token_id,
+        );
 
         let exit_farm_context = ExitFarmContext::<Self::Api, FC::AttributesType>::new(
             payment.clone(),
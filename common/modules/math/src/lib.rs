@@ -55,3 +55,23 @@ where
         T::default()
     }
 }
+
+/// Integer n-th root, rounded down
+pub fn nth_root<M: ManagedTypeApi>(value: &BigUint<M>, n: u32) -> BigUint<M> {
+    if n == 1 || value == &0u64 {
+        return value.clone();
+    }
+
+    // start from a power of two above the root,
+    // from there Newton's method decreases monotonically
+    let value_bits = value.log2_floor().unwrap_or_default() + 1;
+    let mut root = BigUint::from(2u64).pow(value_bits.div_ceil(n));
+    loop {
+        let next_root = (&root * (n - 1) as u64 + value / &root.pow(n - 1)) / n as u64;
+        if next_root >= root {
+            return root;
+        }
+
+        root = next_root;
+    }
+}
@@ -10,6 +10,7 @@ pub enum State {
     Inactive,
     Active,
     PartialActive,
+    WithdrawOnly,
 }
 
 #[multiversx_sc::module]
@@ -42,6 +43,13 @@ pub trait PausableModule: permissions_module::PermissionsModule {
         self.state().set(State::Active);
     }
 
+    /// Only lets users take their funds out of the contract
+    #[endpoint(setWithdrawOnly)]
+    fn set_withdraw_only(&self) {
+        self.require_caller_has_pause_permissions();
+        self.state().set(State::WithdrawOnly);
+    }
+
     #[view(getState)]
     #[storage_mapper("state")]
     fn state(&self) -> SingleValueMapper<State>;
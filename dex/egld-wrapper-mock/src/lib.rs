@@ -0,0 +1,43 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+/// Wraps and unwraps EGLD one to one, from its own EGLD and wrapped EGLD balances
+#[multiversx_sc::contract]
+pub trait EgldWrapperMock {
+    #[init]
+    fn init(&self, wrapped_egld_token_id: TokenIdentifier) {
+        self.wrapped_egld_token_id().set(wrapped_egld_token_id);
+    }
+
+    #[payable("EGLD")]
+    #[endpoint(wrapEgld)]
+    fn wrap_egld(&self) -> EsdtTokenPayment {
+        let amount = self.call_value().egld_value().clone_value();
+        require!(amount > 0u64, "Payment must be more than 0");
+
+        let caller = self.blockchain().get_caller();
+        let payment = EsdtTokenPayment::new(self.wrapped_egld_token_id().get(), 0, amount);
+        self.send().direct_non_zero_esdt_payment(&caller, &payment);
+
+        payment
+    }
+
+    #[payable("*")]
+    #[endpoint(unwrapEgld)]
+    fn unwrap_egld(&self) {
+        let (token_id, amount) = self.call_value().single_fungible_esdt();
+        require!(
+            token_id == self.wrapped_egld_token_id().get(),
+            "Wrong esdt token"
+        );
+        require!(amount > 0u64, "Must pay more than 0 tokens!");
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_egld(&caller, &amount);
+    }
+
+    #[view(getWrappedEgldTokenId)]
+    #[storage_mapper("wrappedEgldTokenId")]
+    fn wrapped_egld_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
+}
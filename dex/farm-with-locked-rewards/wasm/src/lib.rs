@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           69
+// Endpoints:                           70
 // Async Callback:                       1
-// Total number of exported functions:  72
+// Total number of exported functions:  73
 
 #![no_std]
 
@@ -51,6 +51,7 @@ multiversx_sc_wasm_adapter::endpoints! {
         removeFromPauseWhitelist => remove_from_pause_whitelist
         pause => pause
         resume => resume
+        setWithdrawOnly => set_withdraw_only
         getState => state
         addAdmin => add_admin_endpoint
         removeAdmin => remove_admin_endpoint
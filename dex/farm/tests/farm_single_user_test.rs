@@ -3,12 +3,14 @@
 mod farm_setup;
 
 use config::ConfigModule;
+use farm::Farm;
 use farm_setup::single_user_farm_setup::*;
-use multiversx_sc::types::EsdtLocalRole;
+use multiversx_sc::{codec::multi_types::OptionalValue, types::EsdtLocalRole};
 use multiversx_sc_scenario::{
     managed_address, managed_biguint, managed_token_id, rust_biguint,
     whitebox_legacy::TxTokenTransfer, DebugApi,
 };
+use pausable::PausableModule;
 use sc_whitelist_module::SCWhitelistModule;
 
 #[test]
@@ -51,6 +53,70 @@ fn test_exit_farm() {
     farm_setup.check_farm_token_supply(0);
 }
 
+#[test]
+fn test_exit_farm_withdraw_only() {
+    let mut farm_setup = SingleUserFarmSetup::new(farm::contract_obj, pair::contract_obj);
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 1;
+    farm_setup.enter_farm(farm_in_amount, &[], expected_farm_token_nonce, 0, 0, 0);
+
+    farm_setup.set_block_epoch(5);
+    farm_setup.set_block_nonce(10);
+
+    farm_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &farm_setup.owner_address,
+            &farm_setup.farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_withdraw_only();
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.farm_wrapper,
+            FARM_TOKEN_ID,
+            expected_farm_token_nonce,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.claim_rewards_endpoint(OptionalValue::None);
+            },
+        )
+        .assert_user_error("Not active");
+
+    farm_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &farm_setup.user_address,
+            &farm_setup.farm_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(farm_in_amount),
+            |sc| {
+                let _ = sc.enter_farm_endpoint(OptionalValue::None);
+            },
+        )
+        .assert_user_error("Not active");
+
+    let expected_mex_out = 10 * PER_BLOCK_REWARD_AMOUNT;
+    let expected_lp_token_balance = rust_biguint!(USER_TOTAL_LP_TOKENS);
+    farm_setup.exit_farm(
+        farm_in_amount,
+        expected_farm_token_nonce,
+        expected_mex_out,
+        farm_in_amount,
+        &rust_biguint!(expected_mex_out),
+        &expected_lp_token_balance,
+    );
+    farm_setup.check_farm_token_supply(0);
+}
+
 #[test]
 fn test_exit_farm_with_penalty() {
     let mut farm_setup = SingleUserFarmSetup::new(farm::contract_obj, pair::contract_obj);
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           66
+// Endpoints:                           67
 // Async Callback:                       1
-// Total number of exported functions:  69
+// Total number of exported functions:  70
 
 #![no_std]
 
@@ -48,6 +48,7 @@ multiversx_sc_wasm_adapter::endpoints! {
         removeFromPauseWhitelist => remove_from_pause_whitelist
         pause => pause
         resume => resume
+        setWithdrawOnly => set_withdraw_only
         getState => state
         addAdmin => add_admin_endpoint
         removeAdmin => remove_admin_endpoint
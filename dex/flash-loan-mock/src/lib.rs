@@ -0,0 +1,43 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+/// Borrows from a pair through a flash loan and pays back the loan
+/// plus a given extra amount from its own balance
+#[multiversx_sc::contract]
+pub trait FlashLoanMock {
+    #[init]
+    fn init(&self) {}
+
+    #[endpoint(borrow)]
+    fn borrow(
+        &self,
+        pair_address: ManagedAddress,
+        token_id: TokenIdentifier,
+        amount: BigUint,
+        repay_extra_amount: BigUint,
+    ) {
+        self.tx()
+            .to(&pair_address)
+            .raw_call("flashLoan")
+            .argument(&token_id)
+            .argument(&amount)
+            .argument(&ManagedBuffer::from(b"onFlashLoan"))
+            .argument(&repay_extra_amount)
+            .sync_call();
+    }
+
+    #[payable("*")]
+    #[endpoint(onFlashLoan)]
+    fn on_flash_loan(&self, repay_extra_amount: BigUint) {
+        let pair_address = self.blockchain().get_caller();
+        let payment = self.call_value().single_esdt();
+        let repay_amount = payment.amount + repay_extra_amount;
+
+        self.tx()
+            .to(&pair_address)
+            .raw_call("repayFlashLoan")
+            .single_esdt(&payment.token_identifier, 0, &repay_amount)
+            .sync_call();
+    }
+}
@@ -50,6 +50,21 @@ pub trait Config {
         self.try_change_price_providers(price_providers);
     }
 
+    /// Once set, LP tokens are valued with the fair LP price from the given safe price view
+    /// contract, instead of the safe price endpoint of the price provider
+    #[endpoint(changeFairLpPriceView)]
+    fn change_fair_lp_price_view(&self, opt_view_address: OptionalValue<ManagedAddress>) {
+        self.require_caller_self();
+
+        match opt_view_address {
+            OptionalValue::Some(view_address) => {
+                require!(!view_address.is_zero(), ERROR_ZERO_VALUE);
+                self.fair_lp_price_view().set(view_address);
+            }
+            OptionalValue::None => self.fair_lp_price_view().clear(),
+        }
+    }
+
     fn require_caller_self(&self) {
         let caller = self.blockchain().get_caller();
         let sc_address = self.blockchain().get_sc_address();
@@ -154,6 +169,10 @@ pub trait Config {
     #[storage_mapper("mexTokenId")]
     fn mex_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
 
+    #[view(getFairLpPriceView)]
+    #[storage_mapper("fairLpPriceView")]
+    fn fair_lp_price_view(&self) -> SingleValueMapper<ManagedAddress>;
+
     #[storage_mapper("price_providers")]
     fn price_providers(&self) -> MapMapper<TokenIdentifier, ManagedAddress>;
 }
@@ -12,6 +12,13 @@ mod price_provider_proxy {
             &self,
             liquidity: BigUint,
         ) -> MultiValue2<EsdtTokenPayment<Self::Api>, EsdtTokenPayment<Self::Api>>;
+
+        #[endpoint(getLpTokensFairPriceByDefaultOffset)]
+        fn get_lp_tokens_fair_price_by_default_offset(
+            &self,
+            pair_address: ManagedAddress,
+            liquidity: BigUint,
+        ) -> MultiValue2<EsdtTokenPayment<Self::Api>, EsdtTokenPayment<Self::Api>>;
     }
 }
 
@@ -25,10 +32,20 @@ pub trait Lib: config::Config {
         }
 
         if let Some(provider) = self.price_providers().get(&payment.token_identifier) {
+            let fair_lp_price_view_mapper = self.fair_lp_price_view();
             let call_result: MultiValue2<EsdtTokenPayment<Self::Api>, EsdtTokenPayment<Self::Api>> =
-                self.price_provider_proxy(provider)
-                    .get_tokens_for_given_position_with_safe_price(payment.amount.clone())
-                    .execute_on_dest_context();
+                if fair_lp_price_view_mapper.is_empty() {
+                    self.price_provider_proxy(provider)
+                        .get_tokens_for_given_position_with_safe_price(payment.amount.clone())
+                        .execute_on_dest_context()
+                } else {
+                    self.price_provider_proxy(fair_lp_price_view_mapper.get())
+                        .get_lp_tokens_fair_price_by_default_offset(
+                            provider,
+                            payment.amount.clone(),
+                        )
+                        .execute_on_dest_context()
+                };
             let (token1, token2) = call_result.into_tuple();
 
             if token1.token_identifier == mex_token_id {
@@ -678,3 +678,83 @@ fn test_basic_propose_with_lpmex() {
         })
         .assert_ok();
 }
+
+#[test]
+fn test_propose_with_lpmex_fair_price() {
+    let mut gov_setup = setup_gov(governance::contract_obj, pair_mock::contract_obj);
+
+    // The pair mock also acts as the safe price view contract
+    let gov_address = gov_setup.gov_wrapper.address_ref().clone();
+    let pair_address = gov_setup.pair_wrapper.address_ref().clone();
+    gov_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &gov_address,
+            &gov_setup.gov_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.change_fair_lp_price_view(OptionalValue::Some(managed_address!(&pair_address)));
+            },
+        )
+        .assert_ok();
+
+    // The fair price of the mock values the LP tokens at half of the safe price
+    gov_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &gov_setup.owner_address,
+            &gov_setup.gov_wrapper,
+            LPMEX_TOKEN_ID,
+            0,
+            &rust_biguint!(MIN_WEIGHT_FOR_PROPOSAL * 2),
+            |sc| {
+                sc.propose(ProposalCreationArgs {
+                    description: managed_buffer!(&b""[..]),
+                    actions: ManagedVec::from(Vec::<Action<DebugApi>>::new()),
+                });
+            },
+        )
+        .assert_user_error(core::str::from_utf8(NOT_ENOUGH_FUNDS_TO_PROPOSE).unwrap());
+
+    gov_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &gov_setup.owner_address,
+            &gov_setup.gov_wrapper,
+            LPMEX_TOKEN_ID,
+            0,
+            &rust_biguint!(MIN_WEIGHT_FOR_PROPOSAL * 4),
+            |sc| {
+                sc.propose(ProposalCreationArgs {
+                    description: managed_buffer!(&b""[..]),
+                    actions: ManagedVec::from(Vec::<Action<DebugApi>>::new()),
+                });
+            },
+        )
+        .assert_ok();
+
+    gov_setup
+        .blockchain_wrapper
+        .execute_query(&gov_setup.gov_wrapper, |sc| {
+            let proposal = sc.proposal(0).get();
+            assert_eq!(
+                managed_biguint!(MIN_WEIGHT_FOR_PROPOSAL),
+                proposal.num_upvotes,
+            );
+        })
+        .assert_ok();
+
+    // Clearing the view goes back to the safe price of the provider
+    gov_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &gov_address,
+            &gov_setup.gov_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.change_fair_lp_price_view(OptionalValue::None);
+                assert!(sc.fair_lp_price_view().is_empty());
+            },
+        )
+        .assert_ok();
+}
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           21
+// Endpoints:                           23
 // Async Callback (empty):               1
-// Total number of exported functions:  24
+// Total number of exported functions:  26
 
 #![no_std]
 
@@ -31,6 +31,7 @@ multiversx_sc_wasm_adapter::endpoints! {
         changeVotingPeriodInBlocks => change_voting_period_in_blocks
         changeGovernanceTokenIds => change_governance_token_ids
         changePriceProviders => change_price_providers
+        changeFairLpPriceView => change_fair_lp_price_view
         getGovernanceTokenId => governance_token_ids
         getQuorum => quorum
         getMinWeightForProposal => min_weight_for_proposal
@@ -40,6 +41,7 @@ multiversx_sc_wasm_adapter::endpoints! {
         getProposalIdCounter => proposal_id_counter
         getVoteNFTId => vote_nft_id
         getMexTokenId => mex_token_id
+        getFairLpPriceView => fair_lp_price_view
         getProposalStatus => get_proposal_status_view
     )
 }
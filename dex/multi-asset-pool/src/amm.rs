@@ -0,0 +1,85 @@
+multiversx_sc::imports!();
+
+use crate::config::{self, MAX_PERCENTAGE};
+
+/// All the pool tokens have equal weights, so the invariant is the product of all
+/// the reserves. A swap only changes two of them, which makes the swap formulas
+/// the same as the ones of a constant product pair.
+#[multiversx_sc::module]
+pub trait AmmModule:
+    config::ConfigModule + permissions_module::PermissionsModule + pausable::PausableModule
+{
+    fn get_amount_out(
+        &self,
+        amount_in: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+    ) -> BigUint {
+        let total_fee_percent = self.total_fee_percent().get();
+        let amount_in_with_fee = amount_in * (MAX_PERCENTAGE - total_fee_percent);
+        let numerator = &amount_in_with_fee * reserve_out;
+        let denominator = (reserve_in * MAX_PERCENTAGE) + amount_in_with_fee;
+
+        numerator / denominator
+    }
+
+    fn get_amount_in(
+        &self,
+        amount_out: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+    ) -> BigUint {
+        let total_fee_percent = self.total_fee_percent().get();
+        let numerator = reserve_in * amount_out * MAX_PERCENTAGE;
+        let denominator = (reserve_out - amount_out) * (MAX_PERCENTAGE - total_fee_percent);
+
+        (numerator / denominator) + 1u64
+    }
+
+    /// A single token deposit is a proportional deposit plus a swap of the other tokens' share,
+    /// so the swap fee is only charged on that share.
+    ///
+    /// new_supply = supply * ((reserve + amount) / reserve) ^ (1 / n)
+    fn get_single_token_deposit_liquidity(
+        &self,
+        amount: &BigUint,
+        reserve: &BigUint,
+        lp_supply: &BigUint,
+        tokens_count: usize,
+    ) -> BigUint {
+        let amount_after_fee = amount - &self.get_non_proportional_fee(amount, tokens_count);
+        let new_supply_pow =
+            lp_supply.pow(tokens_count as u32) * &(reserve + &amount_after_fee) / reserve;
+        let new_supply = math::nth_root(&new_supply_pow, tokens_count as u32);
+        if new_supply > *lp_supply {
+            new_supply - lp_supply
+        } else {
+            BigUint::zero()
+        }
+    }
+
+    /// new_reserve = reserve * ((supply - liquidity) / supply) ^ n, rounded up in favour of the pool
+    fn get_single_token_withdraw_amount(
+        &self,
+        liquidity: &BigUint,
+        reserve: &BigUint,
+        lp_supply: &BigUint,
+        tokens_count: usize,
+    ) -> BigUint {
+        let tokens_count_u32 = tokens_count as u32;
+        let numerator = reserve * &(lp_supply - liquidity).pow(tokens_count_u32);
+        let denominator = lp_supply.pow(tokens_count_u32);
+        let new_reserve = (numerator + &denominator - 1u64) / denominator;
+
+        let amount_no_fee = reserve - &new_reserve;
+        let fee_amount = self.get_non_proportional_fee(&amount_no_fee, tokens_count);
+
+        amount_no_fee - fee_amount
+    }
+
+    fn get_non_proportional_fee(&self, amount: &BigUint, tokens_count: usize) -> BigUint {
+        let tokens_count = tokens_count as u64;
+        amount * (tokens_count - 1) * self.total_fee_percent().get()
+            / (tokens_count * MAX_PERCENTAGE)
+    }
+}
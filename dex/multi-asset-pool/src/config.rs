@@ -0,0 +1,77 @@
+multiversx_sc::imports!();
+
+use pausable::State;
+
+use crate::errors::*;
+
+pub const MAX_PERCENTAGE: u64 = 100_000;
+pub const MAX_FEE_PERCENTAGE: u64 = 5_000;
+
+#[multiversx_sc::module]
+pub trait ConfigModule: permissions_module::PermissionsModule + pausable::PausableModule {
+    #[endpoint(setStateActiveNoSwaps)]
+    fn set_state_active_no_swaps(&self) {
+        self.require_caller_has_owner_permissions();
+        self.state().set(State::PartialActive);
+    }
+
+    #[endpoint(setFeePercent)]
+    fn set_fee_percent_endpoint(&self, total_fee_percent: u64) {
+        self.require_caller_has_owner_permissions();
+        self.set_fee_percent(total_fee_percent);
+    }
+
+    fn set_fee_percent(&self, total_fee_percent: u64) {
+        require!(total_fee_percent <= MAX_FEE_PERCENTAGE, ERROR_BAD_PERCENTS);
+        self.total_fee_percent().set(total_fee_percent);
+    }
+
+    #[view(getReserves)]
+    fn get_reserves(&self) -> MultiValueEncoded<EsdtTokenPayment> {
+        let mut result = MultiValueEncoded::new();
+        for token_id in self.tokens().get().iter() {
+            let reserve = self.reserve(&token_id).get();
+            result.push(EsdtTokenPayment::new((*token_id).clone(), 0, reserve));
+        }
+
+        result
+    }
+
+    fn require_pool_token(&self, tokens: &ManagedVec<TokenIdentifier>, token_id: &TokenIdentifier) {
+        require!(tokens.contains(token_id), ERROR_UNKNOWN_TOKEN);
+    }
+
+    #[inline]
+    fn is_state_active(&self, state: State) -> bool {
+        state == State::Active || state == State::PartialActive
+    }
+
+    #[inline]
+    fn can_swap(&self, state: State) -> bool {
+        state == State::Active
+    }
+
+    #[view(getTokens)]
+    #[storage_mapper("tokens")]
+    fn tokens(&self) -> SingleValueMapper<ManagedVec<TokenIdentifier>>;
+
+    #[view(getReserve)]
+    #[storage_mapper("reserve")]
+    fn reserve(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[view(getLpTokenIdentifier)]
+    #[storage_mapper("lpTokenIdentifier")]
+    fn lp_token_identifier(&self) -> SingleValueMapper<TokenIdentifier>;
+
+    #[view(getTotalSupply)]
+    #[storage_mapper("lpTokenSupply")]
+    fn lp_token_supply(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalFeePercent)]
+    #[storage_mapper("totalFeePercent")]
+    fn total_fee_percent(&self) -> SingleValueMapper<u64>;
+
+    #[view(getRouterAddress)]
+    #[storage_mapper("routerAddress")]
+    fn router_address(&self) -> SingleValueMapper<ManagedAddress>;
+}
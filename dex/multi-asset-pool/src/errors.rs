@@ -0,0 +1,26 @@
+pub static ERROR_NOT_ACTIVE: &[u8] = b"Not active";
+pub static ERROR_SWAP_NOT_ENABLED: &[u8] = b"Swap is not enabled";
+pub static ERROR_LP_TOKEN_NOT_ISSUED: &[u8] = b"LP token not issued";
+pub static ERROR_LP_TOKEN_ALREADY_SET: &[u8] = b"LP token already set";
+pub static ERROR_LP_TOKEN_SAME_AS_POOL_TOKENS: &[u8] =
+    b"LP token should differ from the pool tokens";
+
+pub static ERROR_BAD_TOKENS_COUNT: &[u8] = b"Bad number of pool tokens";
+pub static ERROR_NOT_AN_ESDT: &[u8] = b"Not a valid esdt id";
+pub static ERROR_SAME_TOKENS: &[u8] = b"Pool tokens cannot be the same";
+pub static ERROR_UNKNOWN_TOKEN: &[u8] = b"Unknown token";
+pub static ERROR_BAD_PERCENTS: &[u8] = b"Bad percents";
+
+pub static ERROR_BAD_PAYMENT_TOKENS: &[u8] = b"Bad payment tokens";
+pub static ERROR_BAD_MIN_AMOUNTS: &[u8] = b"Bad number of min amounts";
+pub static ERROR_ZERO_AMOUNT: &[u8] = b"Zero amount";
+
+pub static ERROR_FIRST_LIQUDITY: &[u8] = b"First tokens needs to be greater than minimum liquidity";
+pub static ERROR_INITIAL_LIQUIDITY_NOT_ADDED: &[u8] = b"Initial liquidity was not added";
+pub static ERROR_INSUFFICIENT_LIQUIDITY: &[u8] = b"Insufficient liquidity minted";
+pub static ERROR_INSUFFICIENT_LIQ_BURNED: &[u8] = b"Insufficient liquidity burned";
+pub static ERROR_NOT_ENOUGH_LP: &[u8] = b"Not enough LP token supply";
+pub static ERROR_NOT_ENOUGH_RESERVE: &[u8] = b"Not enough reserve";
+pub static ERROR_SLIPPAGE_EXCEEDED: &[u8] = b"Slippage exceeded";
+pub static ERROR_SLIPPAGE_ON_REMOVE: &[u8] = b"Slippage amount does not match";
+pub static ERROR_K_INVARIANT_FAILED: &[u8] = b"K invariant failed";
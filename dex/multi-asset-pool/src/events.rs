@@ -0,0 +1,136 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(TypeAbi, TopEncode)]
+pub struct SwapEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    token_id_in: TokenIdentifier<M>,
+    token_amount_in: BigUint<M>,
+    token_id_out: TokenIdentifier<M>,
+    token_amount_out: BigUint<M>,
+    token_in_reserve: BigUint<M>,
+    token_out_reserve: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct LiquidityEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    tokens: ManagedVec<M, EsdtTokenPayment<M>>,
+    lp_token_id: TokenIdentifier<M>,
+    lp_token_amount: BigUint<M>,
+    lp_supply: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
+#[multiversx_sc::module]
+pub trait EventsModule {
+    fn emit_swap_event(
+        &self,
+        caller: &ManagedAddress,
+        payment_in: EsdtTokenPayment,
+        payment_out: EsdtTokenPayment,
+        token_in_reserve: BigUint,
+        token_out_reserve: BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        self.swap_event(
+            &payment_in.token_identifier.clone(),
+            &payment_out.token_identifier.clone(),
+            caller,
+            epoch,
+            &SwapEvent {
+                caller: caller.clone(),
+                token_id_in: payment_in.token_identifier,
+                token_amount_in: payment_in.amount,
+                token_id_out: payment_out.token_identifier,
+                token_amount_out: payment_out.amount,
+                token_in_reserve,
+                token_out_reserve,
+                block: self.blockchain().get_block_nonce(),
+                epoch,
+                timestamp: self.blockchain().get_block_timestamp(),
+            },
+        )
+    }
+
+    fn emit_add_liquidity_event(
+        &self,
+        caller: &ManagedAddress,
+        tokens: ManagedVec<EsdtTokenPayment>,
+        lp_token: EsdtTokenPayment,
+        lp_supply: BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        self.add_liquidity_event(
+            caller,
+            epoch,
+            &self.build_liquidity_event(caller, tokens, lp_token, lp_supply, epoch),
+        )
+    }
+
+    fn emit_remove_liquidity_event(
+        &self,
+        caller: &ManagedAddress,
+        tokens: ManagedVec<EsdtTokenPayment>,
+        lp_token: EsdtTokenPayment,
+        lp_supply: BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        self.remove_liquidity_event(
+            caller,
+            epoch,
+            &self.build_liquidity_event(caller, tokens, lp_token, lp_supply, epoch),
+        )
+    }
+
+    fn build_liquidity_event(
+        &self,
+        caller: &ManagedAddress,
+        tokens: ManagedVec<EsdtTokenPayment>,
+        lp_token: EsdtTokenPayment,
+        lp_supply: BigUint,
+        epoch: u64,
+    ) -> LiquidityEvent<Self::Api> {
+        LiquidityEvent {
+            caller: caller.clone(),
+            tokens,
+            lp_token_id: lp_token.token_identifier,
+            lp_token_amount: lp_token.amount,
+            lp_supply,
+            block: self.blockchain().get_block_nonce(),
+            epoch,
+            timestamp: self.blockchain().get_block_timestamp(),
+        }
+    }
+
+    #[event("swap")]
+    fn swap_event(
+        &self,
+        #[indexed] token_in: &TokenIdentifier,
+        #[indexed] token_out: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        swap_event: &SwapEvent<Self::Api>,
+    );
+
+    #[event("add_liquidity")]
+    fn add_liquidity_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        add_liquidity_event: &LiquidityEvent<Self::Api>,
+    );
+
+    #[event("remove_liquidity")]
+    fn remove_liquidity_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        remove_liquidity_event: &LiquidityEvent<Self::Api>,
+    );
+}
@@ -0,0 +1,85 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+pub mod amm;
+pub mod config;
+pub mod errors;
+mod events;
+pub mod liquidity;
+pub mod swap;
+
+use errors::*;
+use pausable::State;
+use permissions_module::Permissions;
+
+pub const MIN_POOL_TOKENS: usize = 3;
+pub const MAX_POOL_TOKENS: usize = 8;
+
+/// A pool of 3 to 8 equally weighted tokens, sharing a single LP token
+#[multiversx_sc::contract]
+pub trait MultiAssetPool:
+    amm::AmmModule
+    + config::ConfigModule
+    + events::EventsModule
+    + liquidity::LiquidityModule
+    + swap::SwapModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    #[init]
+    fn init(
+        &self,
+        router_address: ManagedAddress,
+        router_owner_address: ManagedAddress,
+        total_fee_percent: u64,
+        token_ids: MultiValueEncoded<TokenIdentifier>,
+    ) {
+        require!(
+            (MIN_POOL_TOKENS..=MAX_POOL_TOKENS).contains(&token_ids.len()),
+            ERROR_BAD_TOKENS_COUNT
+        );
+
+        let mut tokens = ManagedVec::new();
+        for token_id in token_ids {
+            require!(token_id.is_valid_esdt_identifier(), ERROR_NOT_AN_ESDT);
+            require!(!tokens.contains(&token_id), ERROR_SAME_TOKENS);
+            tokens.push(token_id);
+        }
+
+        self.set_fee_percent(total_fee_percent);
+        self.state().set(State::Inactive);
+
+        self.router_address().set(&router_address);
+        self.tokens().set_if_empty(tokens);
+
+        self.add_permissions(router_address, Permissions::OWNER | Permissions::PAUSE);
+        self.add_permissions(
+            router_owner_address,
+            Permissions::OWNER | Permissions::PAUSE,
+        );
+    }
+
+    #[upgrade]
+    fn upgrade(&self) {}
+
+    #[endpoint(setLpTokenIdentifier)]
+    fn set_lp_token_identifier(&self, token_identifier: TokenIdentifier) {
+        self.require_caller_has_owner_permissions();
+
+        require!(
+            self.lp_token_identifier().is_empty(),
+            ERROR_LP_TOKEN_ALREADY_SET
+        );
+        require!(
+            token_identifier.is_valid_esdt_identifier(),
+            ERROR_NOT_AN_ESDT
+        );
+        require!(
+            !self.tokens().get().contains(&token_identifier),
+            ERROR_LP_TOKEN_SAME_AS_POOL_TOKENS
+        );
+
+        self.lp_token_identifier().set(&token_identifier);
+    }
+}
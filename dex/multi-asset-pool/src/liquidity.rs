@@ -0,0 +1,282 @@
+multiversx_sc::imports!();
+
+use crate::{amm, config, errors::*, events};
+
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+#[multiversx_sc::module]
+pub trait LiquidityModule:
+    amm::AmmModule
+    + config::ConfigModule
+    + events::EventsModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    /// Expects one payment for each pool token, in the pool tokens order.
+    /// The first deposit sets the price of the pool tokens, afterwards the deposits are
+    /// matched to the reserves ratio and whatever is left is sent back.
+    #[payable("*")]
+    #[endpoint(addLiquidity)]
+    fn add_liquidity(&self, lp_amount_min: BigUint) -> ManagedVec<EsdtTokenPayment> {
+        require!(self.is_state_active(self.state().get()), ERROR_NOT_ACTIVE);
+        let lp_token_id = self.get_issued_lp_token_id();
+
+        let tokens = self.tokens().get();
+        let payments = self.call_value().all_esdt_transfers().clone_value();
+        require!(payments.len() == tokens.len(), ERROR_BAD_PAYMENT_TOKENS);
+        for (payment, token_id) in payments.iter().zip(tokens.iter()) {
+            require!(
+                payment.token_identifier == *token_id && payment.token_nonce == 0,
+                ERROR_BAD_PAYMENT_TOKENS
+            );
+            require!(payment.amount > 0u64, ERROR_ZERO_AMOUNT);
+        }
+
+        let lp_supply = self.lp_token_supply().get();
+        let liquidity = if lp_supply == 0u64 {
+            let mut initial_liquidity = BigUint::zero();
+            for payment in payments.iter() {
+                initial_liquidity += &payment.amount;
+            }
+            require!(initial_liquidity > MINIMUM_LIQUIDITY, ERROR_FIRST_LIQUDITY);
+
+            // the minimum liquidity stays locked in the pool
+            self.send()
+                .esdt_local_mint(&lp_token_id, 0, &BigUint::from(MINIMUM_LIQUIDITY));
+            self.lp_token_supply().set(BigUint::from(MINIMUM_LIQUIDITY));
+
+            initial_liquidity - MINIMUM_LIQUIDITY
+        } else {
+            let mut liquidity: Option<BigUint> = None;
+            for payment in payments.iter() {
+                let reserve = self.reserve(&payment.token_identifier).get();
+                let potential_liquidity = &payment.amount * &lp_supply / &reserve;
+                liquidity = match liquidity {
+                    Some(current) if current <= potential_liquidity => Some(current),
+                    _ => Some(potential_liquidity),
+                };
+            }
+
+            liquidity.unwrap_or_default()
+        };
+        require!(liquidity > 0u64, ERROR_INSUFFICIENT_LIQUIDITY);
+        require!(liquidity >= lp_amount_min, ERROR_SLIPPAGE_EXCEEDED);
+
+        let mut deposited_tokens = ManagedVec::new();
+        let mut refunds = ManagedVec::<Self::Api, EsdtTokenPayment>::new();
+        for payment in payments.iter() {
+            let reserve_mapper = self.reserve(&payment.token_identifier);
+            let reserve = reserve_mapper.get();
+            let amount_used = if lp_supply == 0u64 {
+                payment.amount.clone()
+            } else {
+                (&liquidity * &reserve + &lp_supply - 1u64) / &lp_supply
+            };
+            reserve_mapper.set(&reserve + &amount_used);
+
+            let refund_amount = &payment.amount - &amount_used;
+            if refund_amount > 0u64 {
+                refunds.push(EsdtTokenPayment::new(
+                    payment.token_identifier.clone(),
+                    0,
+                    refund_amount,
+                ));
+            }
+            deposited_tokens.push(EsdtTokenPayment::new(
+                payment.token_identifier.clone(),
+                0,
+                amount_used,
+            ));
+        }
+
+        let lp_payment = self.mint_lp_tokens(&lp_token_id, liquidity);
+        let mut output_payments = ManagedVec::from_single_item(lp_payment.clone());
+        output_payments.extend(&refunds);
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_multi(&caller, &output_payments);
+
+        self.emit_add_liquidity_event(
+            &caller,
+            deposited_tokens,
+            lp_payment,
+            self.lp_token_supply().get(),
+        );
+
+        output_payments
+    }
+
+    /// Deposits a single pool token. This is the same as swapping part of it for all the
+    /// other pool tokens and then adding liquidity, so the swap fee is charged on that part.
+    #[payable("*")]
+    #[endpoint(addLiquiditySingleToken)]
+    fn add_liquidity_single_token(&self, lp_amount_min: BigUint) -> EsdtTokenPayment {
+        require!(self.is_state_active(self.state().get()), ERROR_NOT_ACTIVE);
+        let lp_token_id = self.get_issued_lp_token_id();
+
+        let tokens = self.tokens().get();
+        let payment = self.call_value().single_esdt();
+        self.require_pool_token(&tokens, &payment.token_identifier);
+        require!(payment.amount > 0u64, ERROR_ZERO_AMOUNT);
+
+        let lp_supply = self.lp_token_supply().get();
+        require!(lp_supply > 0u64, ERROR_INITIAL_LIQUIDITY_NOT_ADDED);
+
+        let reserve_mapper = self.reserve(&payment.token_identifier);
+        let reserve = reserve_mapper.get();
+        let liquidity = self.get_single_token_deposit_liquidity(
+            &payment.amount,
+            &reserve,
+            &lp_supply,
+            tokens.len(),
+        );
+        require!(liquidity > 0u64, ERROR_INSUFFICIENT_LIQUIDITY);
+        require!(liquidity >= lp_amount_min, ERROR_SLIPPAGE_EXCEEDED);
+
+        reserve_mapper.set(reserve + &payment.amount);
+
+        let lp_payment = self.mint_lp_tokens(&lp_token_id, liquidity);
+        let caller = self.blockchain().get_caller();
+        self.send()
+            .direct_non_zero_esdt_payment(&caller, &lp_payment);
+
+        self.emit_add_liquidity_event(
+            &caller,
+            ManagedVec::from_single_item(payment),
+            lp_payment.clone(),
+            self.lp_token_supply().get(),
+        );
+
+        lp_payment
+    }
+
+    /// Withdraws all the pool tokens proportionally.
+    /// Expects one minimum amount for each pool token, in the pool tokens order.
+    #[payable("*")]
+    #[endpoint(removeLiquidity)]
+    fn remove_liquidity(
+        &self,
+        amounts_min: MultiValueEncoded<BigUint>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        require!(self.is_state_active(self.state().get()), ERROR_NOT_ACTIVE);
+        let lp_payment = self.get_lp_token_payment();
+
+        let tokens = self.tokens().get();
+        require!(amounts_min.len() == tokens.len(), ERROR_BAD_MIN_AMOUNTS);
+
+        let lp_supply = self.lp_token_supply().get();
+        require!(
+            lp_supply >= &lp_payment.amount + MINIMUM_LIQUIDITY,
+            ERROR_NOT_ENOUGH_LP
+        );
+
+        let mut output_payments = ManagedVec::new();
+        for (token_id, amount_min) in tokens.iter().zip(amounts_min) {
+            let reserve_mapper = self.reserve(&token_id);
+            let reserve = reserve_mapper.get();
+            let amount = &lp_payment.amount * &reserve / &lp_supply;
+            require!(amount > 0u64, ERROR_INSUFFICIENT_LIQ_BURNED);
+            require!(amount >= amount_min, ERROR_SLIPPAGE_ON_REMOVE);
+
+            reserve_mapper.set(reserve - &amount);
+            output_payments.push(EsdtTokenPayment::new((*token_id).clone(), 0, amount));
+        }
+
+        self.burn_lp_tokens(&lp_payment);
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_multi(&caller, &output_payments);
+
+        self.emit_remove_liquidity_event(
+            &caller,
+            output_payments.clone(),
+            lp_payment,
+            self.lp_token_supply().get(),
+        );
+
+        output_payments
+    }
+
+    /// Withdraws a single pool token. This is the same as removing liquidity proportionally
+    /// and then swapping all the other pool tokens, so the swap fee is charged on that part.
+    #[payable("*")]
+    #[endpoint(removeLiquiditySingleToken)]
+    fn remove_liquidity_single_token(
+        &self,
+        token_out: TokenIdentifier,
+        amount_out_min: BigUint,
+    ) -> EsdtTokenPayment {
+        require!(self.is_state_active(self.state().get()), ERROR_NOT_ACTIVE);
+        let lp_payment = self.get_lp_token_payment();
+
+        let tokens = self.tokens().get();
+        self.require_pool_token(&tokens, &token_out);
+
+        let lp_supply = self.lp_token_supply().get();
+        require!(
+            lp_supply >= &lp_payment.amount + MINIMUM_LIQUIDITY,
+            ERROR_NOT_ENOUGH_LP
+        );
+
+        let reserve_mapper = self.reserve(&token_out);
+        let reserve = reserve_mapper.get();
+        let amount_out = self.get_single_token_withdraw_amount(
+            &lp_payment.amount,
+            &reserve,
+            &lp_supply,
+            tokens.len(),
+        );
+        require!(amount_out > 0u64, ERROR_INSUFFICIENT_LIQ_BURNED);
+        require!(amount_out >= amount_out_min, ERROR_SLIPPAGE_ON_REMOVE);
+
+        reserve_mapper.set(reserve - &amount_out);
+        self.burn_lp_tokens(&lp_payment);
+
+        let caller = self.blockchain().get_caller();
+        let output_payment = EsdtTokenPayment::new(token_out, 0, amount_out);
+        self.send()
+            .direct_non_zero_esdt_payment(&caller, &output_payment);
+
+        self.emit_remove_liquidity_event(
+            &caller,
+            ManagedVec::from_single_item(output_payment.clone()),
+            lp_payment,
+            self.lp_token_supply().get(),
+        );
+
+        output_payment
+    }
+
+    fn get_issued_lp_token_id(&self) -> TokenIdentifier {
+        let lp_token_mapper = self.lp_token_identifier();
+        require!(!lp_token_mapper.is_empty(), ERROR_LP_TOKEN_NOT_ISSUED);
+
+        lp_token_mapper.get()
+    }
+
+    fn get_lp_token_payment(&self) -> EsdtTokenPayment {
+        let lp_token_id = self.get_issued_lp_token_id();
+        let payment = self.call_value().single_esdt();
+        require!(
+            payment.token_identifier == lp_token_id && payment.token_nonce == 0,
+            ERROR_BAD_PAYMENT_TOKENS
+        );
+        require!(payment.amount > 0u64, ERROR_ZERO_AMOUNT);
+
+        payment
+    }
+
+    fn mint_lp_tokens(&self, lp_token_id: &TokenIdentifier, amount: BigUint) -> EsdtTokenPayment {
+        self.send().esdt_local_mint(lp_token_id, 0, &amount);
+        self.lp_token_supply().update(|supply| *supply += &amount);
+
+        EsdtTokenPayment::new(lp_token_id.clone(), 0, amount)
+    }
+
+    fn burn_lp_tokens(&self, lp_payment: &EsdtTokenPayment) {
+        self.send()
+            .esdt_local_burn(&lp_payment.token_identifier, 0, &lp_payment.amount);
+        self.lp_token_supply()
+            .update(|supply| *supply -= &lp_payment.amount);
+    }
+}
@@ -0,0 +1,196 @@
+multiversx_sc::imports!();
+
+use common_errors::ERROR_PERMISSION_DENIED;
+
+use crate::{amm, config, errors::*, events};
+
+#[multiversx_sc::module]
+pub trait SwapModule:
+    amm::AmmModule
+    + config::ConfigModule
+    + events::EventsModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    /// Same signature as the pair's endpoint, so that the router can route through the pool
+    #[payable("*")]
+    #[endpoint(swapTokensFixedInput)]
+    fn swap_tokens_fixed_input(
+        &self,
+        token_out: TokenIdentifier,
+        amount_out_min: BigUint,
+        opt_original_caller: OptionalValue<ManagedAddress>,
+    ) -> EsdtTokenPayment {
+        require!(self.can_swap(self.state().get()), ERROR_SWAP_NOT_ENABLED);
+        require!(amount_out_min > 0u64, ERROR_ZERO_AMOUNT);
+
+        let payment = self.call_value().single_esdt();
+        require!(payment.amount > 0u64, ERROR_ZERO_AMOUNT);
+        self.require_swap_tokens(&payment.token_identifier, &token_out);
+
+        let reserve_in_mapper = self.reserve(&payment.token_identifier);
+        let reserve_out_mapper = self.reserve(&token_out);
+        let mut reserve_in = reserve_in_mapper.get();
+        let mut reserve_out = reserve_out_mapper.get();
+        require!(
+            reserve_in > 0u64 && reserve_out > 0u64,
+            ERROR_NOT_ENOUGH_RESERVE
+        );
+
+        let initial_k = &reserve_in * &reserve_out;
+        let amount_out = self.get_amount_out(&payment.amount, &reserve_in, &reserve_out);
+        require!(amount_out >= amount_out_min, ERROR_SLIPPAGE_EXCEEDED);
+        require!(reserve_out > amount_out, ERROR_NOT_ENOUGH_RESERVE);
+
+        reserve_in += &payment.amount;
+        reserve_out -= &amount_out;
+        require!(
+            initial_k <= &reserve_in * &reserve_out,
+            ERROR_K_INVARIANT_FAILED
+        );
+
+        reserve_in_mapper.set(&reserve_in);
+        reserve_out_mapper.set(&reserve_out);
+
+        let caller = self.blockchain().get_caller();
+        let payment_out = EsdtTokenPayment::new(token_out, 0, amount_out);
+        self.send()
+            .direct_non_zero_esdt_payment(&caller, &payment_out);
+
+        let swap_caller = self.get_swap_caller(&caller, opt_original_caller);
+        self.emit_swap_event(
+            &swap_caller,
+            payment,
+            payment_out.clone(),
+            reserve_in,
+            reserve_out,
+        );
+
+        payment_out
+    }
+
+    /// Same signature as the pair's endpoint, so that the router can route through the pool
+    #[payable("*")]
+    #[endpoint(swapTokensFixedOutput)]
+    fn swap_tokens_fixed_output(
+        &self,
+        token_out: TokenIdentifier,
+        amount_out: BigUint,
+        opt_original_caller: OptionalValue<ManagedAddress>,
+    ) -> MultiValue2<EsdtTokenPayment, EsdtTokenPayment> {
+        require!(self.can_swap(self.state().get()), ERROR_SWAP_NOT_ENABLED);
+        require!(amount_out > 0u64, ERROR_ZERO_AMOUNT);
+
+        let payment = self.call_value().single_esdt();
+        require!(payment.amount > 0u64, ERROR_ZERO_AMOUNT);
+        self.require_swap_tokens(&payment.token_identifier, &token_out);
+
+        let reserve_in_mapper = self.reserve(&payment.token_identifier);
+        let reserve_out_mapper = self.reserve(&token_out);
+        let mut reserve_in = reserve_in_mapper.get();
+        let mut reserve_out = reserve_out_mapper.get();
+        require!(reserve_out > amount_out, ERROR_NOT_ENOUGH_RESERVE);
+
+        let initial_k = &reserve_in * &reserve_out;
+        let amount_in = self.get_amount_in(&amount_out, &reserve_in, &reserve_out);
+        require!(amount_in <= payment.amount, ERROR_SLIPPAGE_EXCEEDED);
+
+        reserve_in += &amount_in;
+        reserve_out -= &amount_out;
+        require!(
+            initial_k <= &reserve_in * &reserve_out,
+            ERROR_K_INVARIANT_FAILED
+        );
+
+        reserve_in_mapper.set(&reserve_in);
+        reserve_out_mapper.set(&reserve_out);
+
+        let caller = self.blockchain().get_caller();
+        let payment_out = EsdtTokenPayment::new(token_out, 0, amount_out);
+        let residuum = EsdtTokenPayment::new(
+            payment.token_identifier.clone(),
+            0,
+            &payment.amount - &amount_in,
+        );
+        self.send()
+            .direct_non_zero_esdt_payment(&caller, &payment_out);
+        self.send().direct_non_zero_esdt_payment(&caller, &residuum);
+
+        let swap_caller = self.get_swap_caller(&caller, opt_original_caller);
+        self.emit_swap_event(
+            &swap_caller,
+            EsdtTokenPayment::new(payment.token_identifier, 0, amount_in),
+            payment_out.clone(),
+            reserve_in,
+            reserve_out,
+        );
+
+        (payment_out, residuum).into()
+    }
+
+    #[view(getAmountOut)]
+    fn get_amount_out_view(
+        &self,
+        token_in: TokenIdentifier,
+        token_out: TokenIdentifier,
+        amount_in: BigUint,
+    ) -> BigUint {
+        require!(amount_in > 0u64, ERROR_ZERO_AMOUNT);
+        self.require_swap_tokens(&token_in, &token_out);
+
+        let reserve_in = self.reserve(&token_in).get();
+        let reserve_out = self.reserve(&token_out).get();
+        require!(
+            reserve_in > 0u64 && reserve_out > 0u64,
+            ERROR_NOT_ENOUGH_RESERVE
+        );
+
+        let amount_out = self.get_amount_out(&amount_in, &reserve_in, &reserve_out);
+        require!(reserve_out > amount_out, ERROR_NOT_ENOUGH_RESERVE);
+
+        amount_out
+    }
+
+    #[view(getAmountIn)]
+    fn get_amount_in_view(
+        &self,
+        token_in: TokenIdentifier,
+        token_out: TokenIdentifier,
+        amount_out: BigUint,
+    ) -> BigUint {
+        require!(amount_out > 0u64, ERROR_ZERO_AMOUNT);
+        self.require_swap_tokens(&token_in, &token_out);
+
+        let reserve_in = self.reserve(&token_in).get();
+        let reserve_out = self.reserve(&token_out).get();
+        require!(reserve_out > amount_out, ERROR_NOT_ENOUGH_RESERVE);
+
+        self.get_amount_in(&amount_out, &reserve_in, &reserve_out)
+    }
+
+    fn require_swap_tokens(&self, token_in: &TokenIdentifier, token_out: &TokenIdentifier) {
+        require!(token_in != token_out, ERROR_SAME_TOKENS);
+
+        let tokens = self.tokens().get();
+        self.require_pool_token(&tokens, token_in);
+        self.require_pool_token(&tokens, token_out);
+    }
+
+    /// The router forwards the address of the user that started a multi pair swap
+    fn get_swap_caller(
+        &self,
+        caller: &ManagedAddress,
+        opt_original_caller: OptionalValue<ManagedAddress>,
+    ) -> ManagedAddress {
+        match opt_original_caller {
+            OptionalValue::Some(original_caller) => {
+                require!(
+                    caller == &self.router_address().get(),
+                    ERROR_PERMISSION_DENIED
+                );
+                original_caller
+            }
+            OptionalValue::None => caller.clone(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use multiversx_sc::codec::multi_types::OptionalValue;
+use multiversx_sc::types::{Address, EsdtLocalRole, MultiValueEncoded};
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id, rust_biguint,
+    whitebox_legacy::TxTokenTransfer, whitebox_legacy::*, DebugApi,
+};
+
+pub const POOL_WASM_PATH: &str = "multi-asset-pool/output/multi-asset-pool.wasm";
+pub const WEGLD_TOKEN_ID: &[u8] = b"WEGLD-abcdef";
+pub const MEX_TOKEN_ID: &[u8] = b"MEX-abcdef";
+pub const USDC_TOKEN_ID: &[u8] = b"USDC-abcdef";
+pub const LP_TOKEN_ID: &[u8] = b"LPTOK-abcdef";
+
+pub const USER_TOTAL_TOKENS: u64 = 5_000_000_000;
+pub const TOTAL_FEE_PERCENT: u64 = 300;
+
+use multi_asset_pool::config::ConfigModule;
+use multi_asset_pool::liquidity::LiquidityModule;
+use multi_asset_pool::swap::SwapModule;
+use multi_asset_pool::*;
+use pausable::{PausableModule, State};
+
+pub struct MultiAssetPoolSetup<PoolObjBuilder>
+where
+    PoolObjBuilder: 'static + Copy + Fn() -> multi_asset_pool::ContractObj<DebugApi>,
+{
+    pub b_mock: BlockchainStateWrapper,
+    pub owner_address: Address,
+    pub user_address: Address,
+    pub pool_wrapper: ContractObjWrapper<multi_asset_pool::ContractObj<DebugApi>, PoolObjBuilder>,
+}
+
+impl<PoolObjBuilder> MultiAssetPoolSetup<PoolObjBuilder>
+where
+    PoolObjBuilder: 'static + Copy + Fn() -> multi_asset_pool::ContractObj<DebugApi>,
+{
+    pub fn new(pool_builder: PoolObjBuilder) -> Self {
+        let rust_zero = rust_biguint!(0u64);
+        let mut b_mock = BlockchainStateWrapper::new();
+        let owner_addr = b_mock.create_user_account(&rust_zero);
+        let pool_wrapper =
+            b_mock.create_sc_account(&rust_zero, Some(&owner_addr), pool_builder, POOL_WASM_PATH);
+
+        b_mock
+            .execute_tx(&owner_addr, &pool_wrapper, &rust_zero, |sc| {
+                let mut token_ids = MultiValueEncoded::new();
+                token_ids.push(managed_token_id!(WEGLD_TOKEN_ID));
+                token_ids.push(managed_token_id!(MEX_TOKEN_ID));
+                token_ids.push(managed_token_id!(USDC_TOKEN_ID));
+
+                sc.init(
+                    managed_address!(&owner_addr),
+                    managed_address!(&owner_addr),
+                    TOTAL_FEE_PERCENT,
+                    token_ids,
+                );
+                sc.set_lp_token_identifier(managed_token_id!(LP_TOKEN_ID));
+                sc.state().set(State::Active);
+            })
+            .assert_ok();
+
+        let lp_token_roles = [EsdtLocalRole::Mint, EsdtLocalRole::Burn];
+        b_mock.set_esdt_local_roles(pool_wrapper.address_ref(), LP_TOKEN_ID, &lp_token_roles[..]);
+
+        let user_addr = b_mock.create_user_account(&rust_zero);
+        for token_id in [WEGLD_TOKEN_ID, MEX_TOKEN_ID, USDC_TOKEN_ID] {
+            b_mock.set_esdt_balance(&user_addr, token_id, &rust_biguint!(USER_TOTAL_TOKENS));
+        }
+
+        MultiAssetPoolSetup {
+            b_mock,
+            owner_address: owner_addr,
+            user_address: user_addr,
+            pool_wrapper,
+        }
+    }
+
+    pub fn add_liquidity(&mut self, amounts: [u64; 3], expected_lp_amount: u64) {
+        let payments = [WEGLD_TOKEN_ID, MEX_TOKEN_ID, USDC_TOKEN_ID]
+            .iter()
+            .zip(amounts.iter())
+            .map(|(token_id, amount)| TxTokenTransfer {
+                token_identifier: token_id.to_vec(),
+                nonce: 0,
+                value: rust_biguint!(*amount),
+            })
+            .collect::<Vec<_>>();
+
+        self.b_mock
+            .execute_esdt_multi_transfer(&self.user_address, &self.pool_wrapper, &payments, |sc| {
+                let output_payments = sc.add_liquidity(managed_biguint!(1));
+                let lp_payment = output_payments.get(0);
+                assert_eq!(lp_payment.token_identifier, managed_token_id!(LP_TOKEN_ID));
+                assert_eq!(lp_payment.amount, managed_biguint!(expected_lp_amount));
+            })
+            .assert_ok();
+    }
+
+    pub fn add_liquidity_single_token(
+        &mut self,
+        token_id: &[u8],
+        amount: u64,
+        expected_lp_amount: u64,
+    ) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pool_wrapper,
+                token_id,
+                0,
+                &rust_biguint!(amount),
+                |sc| {
+                    let lp_payment = sc.add_liquidity_single_token(managed_biguint!(1));
+                    assert_eq!(lp_payment.amount, managed_biguint!(expected_lp_amount));
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn remove_liquidity(&mut self, lp_amount: u64, expected_amounts: [u64; 3]) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pool_wrapper,
+                LP_TOKEN_ID,
+                0,
+                &rust_biguint!(lp_amount),
+                |sc| {
+                    let mut amounts_min = MultiValueEncoded::new();
+                    for _ in 0..3 {
+                        amounts_min.push(managed_biguint!(1));
+                    }
+
+                    let output_payments = sc.remove_liquidity(amounts_min);
+                    for (payment, expected_amount) in
+                        output_payments.iter().zip(expected_amounts.iter())
+                    {
+                        assert_eq!(payment.amount, managed_biguint!(*expected_amount));
+                    }
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn remove_liquidity_single_token(
+        &mut self,
+        lp_amount: u64,
+        token_out: &[u8],
+        expected_amount: u64,
+    ) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pool_wrapper,
+                LP_TOKEN_ID,
+                0,
+                &rust_biguint!(lp_amount),
+                |sc| {
+                    let payment = sc.remove_liquidity_single_token(
+                        managed_token_id!(token_out),
+                        managed_biguint!(1),
+                    );
+                    assert_eq!(payment.amount, managed_biguint!(expected_amount));
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn swap_fixed_input(
+        &mut self,
+        payment_token_id: &[u8],
+        payment_amount: u64,
+        desired_token_id: &[u8],
+        expected_amount: u64,
+    ) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pool_wrapper,
+                payment_token_id,
+                0,
+                &rust_biguint!(payment_amount),
+                |sc| {
+                    let payment = sc.swap_tokens_fixed_input(
+                        managed_token_id!(desired_token_id),
+                        managed_biguint!(1),
+                        OptionalValue::None,
+                    );
+                    assert_eq!(payment.amount, managed_biguint!(expected_amount));
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn swap_fixed_output(
+        &mut self,
+        payment_token_id: &[u8],
+        payment_amount_max: u64,
+        desired_token_id: &[u8],
+        desired_amount: u64,
+        expected_residuum: u64,
+    ) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pool_wrapper,
+                payment_token_id,
+                0,
+                &rust_biguint!(payment_amount_max),
+                |sc| {
+                    let (payment, residuum) = sc
+                        .swap_tokens_fixed_output(
+                            managed_token_id!(desired_token_id),
+                            managed_biguint!(desired_amount),
+                            OptionalValue::None,
+                        )
+                        .into_tuple();
+                    assert_eq!(payment.amount, managed_biguint!(desired_amount));
+                    assert_eq!(residuum.amount, managed_biguint!(expected_residuum));
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn check_reserves(&mut self, expected_reserves: [u64; 3]) {
+        self.b_mock
+            .execute_query(&self.pool_wrapper, |sc| {
+                for (token_id, expected_reserve) in [WEGLD_TOKEN_ID, MEX_TOKEN_ID, USDC_TOKEN_ID]
+                    .iter()
+                    .zip(expected_reserves.iter())
+                {
+                    assert_eq!(
+                        sc.reserve(&managed_token_id!(*token_id)).get(),
+                        managed_biguint!(*expected_reserve)
+                    );
+                }
+            })
+            .assert_ok();
+    }
+}
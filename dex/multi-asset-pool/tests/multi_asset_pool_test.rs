@@ -0,0 +1,145 @@
+#![allow(deprecated)]
+
+mod multi_asset_pool_setup;
+use multi_asset_pool::{liquidity::LiquidityModule, swap::SwapModule, MultiAssetPool};
+use multi_asset_pool_setup::*;
+use multiversx_sc::{codec::multi_types::OptionalValue, types::MultiValueEncoded};
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id, rust_biguint, DebugApi,
+};
+
+#[test]
+fn test_multi_asset_pool_setup() {
+    let _ = MultiAssetPoolSetup::new(multi_asset_pool::contract_obj);
+}
+
+#[test]
+fn test_multi_asset_pool_init_bad_tokens() {
+    let mut pool_setup = MultiAssetPoolSetup::new(multi_asset_pool::contract_obj);
+    let owner_address = pool_setup.owner_address.clone();
+    let rust_zero = rust_biguint!(0);
+
+    let pool_wrapper = pool_setup.b_mock.create_sc_account(
+        &rust_zero,
+        Some(&owner_address),
+        multi_asset_pool::contract_obj,
+        POOL_WASM_PATH,
+    );
+    pool_setup
+        .b_mock
+        .execute_tx(&owner_address, &pool_wrapper, &rust_zero, |sc| {
+            let mut token_ids = MultiValueEncoded::<DebugApi, _>::new();
+            token_ids.push(managed_token_id!(WEGLD_TOKEN_ID));
+            token_ids.push(managed_token_id!(MEX_TOKEN_ID));
+
+            sc.init(
+                managed_address!(&owner_address),
+                managed_address!(&owner_address),
+                TOTAL_FEE_PERCENT,
+                token_ids,
+            );
+        })
+        .assert_user_error("Bad number of pool tokens");
+
+    pool_setup
+        .b_mock
+        .execute_tx(&owner_address, &pool_wrapper, &rust_zero, |sc| {
+            let mut token_ids = MultiValueEncoded::<DebugApi, _>::new();
+            token_ids.push(managed_token_id!(WEGLD_TOKEN_ID));
+            token_ids.push(managed_token_id!(MEX_TOKEN_ID));
+            token_ids.push(managed_token_id!(WEGLD_TOKEN_ID));
+
+            sc.init(
+                managed_address!(&owner_address),
+                managed_address!(&owner_address),
+                TOTAL_FEE_PERCENT,
+                token_ids,
+            );
+        })
+        .assert_user_error("Pool tokens cannot be the same");
+}
+
+#[test]
+fn test_multi_asset_pool_liquidity_and_swaps() {
+    let mut pool_setup = MultiAssetPoolSetup::new(multi_asset_pool::contract_obj);
+
+    // the initial LP supply is the sum of the deposits, minus the locked minimum liquidity
+    pool_setup.add_liquidity([1_000_000, 1_000_000, 1_000_000], 2_999_000);
+
+    // the extra 100_000 MEX are sent back
+    pool_setup.add_liquidity([100_000, 200_000, 100_000], 300_000);
+    pool_setup.check_reserves([1_100_000, 1_100_000, 1_100_000]);
+    pool_setup.b_mock.check_esdt_balance(
+        &pool_setup.user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_TOKENS - 1_100_000),
+    );
+
+    // any two pool tokens can be swapped
+    pool_setup.swap_fixed_input(MEX_TOKEN_ID, 10_000, USDC_TOKEN_ID, 9_880);
+    pool_setup.swap_fixed_output(WEGLD_TOKEN_ID, 20_000, MEX_TOKEN_ID, 10_000, 9_969);
+    pool_setup.check_reserves([1_110_031, 1_100_000, 1_090_120]);
+
+    pool_setup.add_liquidity_single_token(USDC_TOKEN_ID, 100_000, 97_778);
+    pool_setup.remove_liquidity_single_token(100_000, WEGLD_TOKEN_ID, 94_961);
+    pool_setup.remove_liquidity(300_000, [92_341, 100_067, 108_265]);
+    pool_setup.check_reserves([922_729, 999_933, 1_081_855]);
+}
+
+#[test]
+fn test_multi_asset_pool_errors() {
+    let mut pool_setup = MultiAssetPoolSetup::new(multi_asset_pool::contract_obj);
+
+    pool_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pool_setup.user_address,
+            &pool_setup.pool_wrapper,
+            USDC_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                sc.add_liquidity_single_token(managed_biguint!(1));
+            },
+        )
+        .assert_user_error("Initial liquidity was not added");
+
+    pool_setup.add_liquidity([1_000_000, 1_000_000, 1_000_000], 2_999_000);
+
+    pool_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pool_setup.user_address,
+            &pool_setup.pool_wrapper,
+            USDC_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                sc.swap_tokens_fixed_input(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("Pool tokens cannot be the same");
+
+    let user_address = pool_setup.user_address.clone();
+    pool_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &user_address,
+            &pool_setup.pool_wrapper,
+            USDC_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::Some(managed_address!(&user_address)),
+                );
+            },
+        )
+        .assert_user_error("Permission denied");
+}
@@ -0,0 +1,54 @@
+// Code generated by the multiversx-sc build system. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+// Init:                                 1
+// Upgrade:                              1
+// Endpoints:                           28
+// Async Callback (empty):               1
+// Total number of exported functions:  31
+
+#![no_std]
+
+multiversx_sc_wasm_adapter::allocator!();
+multiversx_sc_wasm_adapter::panic_handler!();
+
+multiversx_sc_wasm_adapter::endpoints! {
+    multi_asset_pool
+    (
+        init => init
+        upgrade => upgrade
+        setLpTokenIdentifier => set_lp_token_identifier
+        setStateActiveNoSwaps => set_state_active_no_swaps
+        setFeePercent => set_fee_percent_endpoint
+        getReserves => get_reserves
+        getTokens => tokens
+        getReserve => reserve
+        getLpTokenIdentifier => lp_token_identifier
+        getTotalSupply => lp_token_supply
+        getTotalFeePercent => total_fee_percent
+        getRouterAddress => router_address
+        addLiquidity => add_liquidity
+        addLiquiditySingleToken => add_liquidity_single_token
+        removeLiquidity => remove_liquidity
+        removeLiquiditySingleToken => remove_liquidity_single_token
+        swapTokensFixedInput => swap_tokens_fixed_input
+        swapTokensFixedOutput => swap_tokens_fixed_output
+        getAmountOut => get_amount_out_view
+        getAmountIn => get_amount_in_view
+        addAdmin => add_admin_endpoint
+        removeAdmin => remove_admin_endpoint
+        updateOwnerOrAdmin => update_owner_or_admin_endpoint
+        getPermissions => permissions
+        addToPauseWhitelist => add_to_pause_whitelist
+        removeFromPauseWhitelist => remove_from_pause_whitelist
+        pause => pause
+        resume => resume
+        setWithdrawOnly => set_withdraw_only
+        getState => state
+    )
+}
+
+multiversx_sc_wasm_adapter::async_callback_empty! {}
@@ -133,6 +133,19 @@ pub trait PairMock {
             .into()
     }
 
+    #[view(getLpTokensFairPriceByDefaultOffset)]
+    fn get_lp_tokens_fair_price_by_default_offset(
+        &self,
+        _pair_address: ManagedAddress,
+        liquidity: BigUint,
+    ) -> MultiValue2<EsdtTokenPayment<Self::Api>, EsdtTokenPayment<Self::Api>> {
+        (
+            EsdtTokenPayment::new(self.first_token_id().get(), 0, liquidity.clone() / 4u64),
+            EsdtTokenPayment::new(self.second_token_id().get(), 0, liquidity / 4u64),
+        )
+            .into()
+    }
+
     #[storage_mapper("first_token_id")]
     fn first_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
 
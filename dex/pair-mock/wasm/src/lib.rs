@@ -5,9 +5,9 @@
 ////////////////////////////////////////////////////
 
 // Init:                                 1
-// Endpoints:                            2
+// Endpoints:                            3
 // Async Callback (empty):               1
-// Total number of exported functions:   4
+// Total number of exported functions:   5
 
 #![no_std]
 
@@ -20,6 +20,7 @@ multiversx_sc_wasm_adapter::endpoints! {
         init => init
         addInitialLiquidity => add_initial_liquidity
         getTokensForGivenPositionWithSafePrice => get_tokens_for_given_position_with_safe_price
+        getLpTokensFairPriceByDefaultOffset => get_lp_tokens_fair_price_by_default_offset
     )
 }
 
@@ -1,10 +1,15 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
-use crate::config::MAX_PERCENTAGE;
+use crate::config::{Curve, MAX_PERCENTAGE, TOTAL_CURVE_WEIGHT};
+use crate::contexts::base::SwapTokensOrder;
+use crate::errors::ERROR_STABLE_SWAP_NOT_CONVERGED;
 
 use super::config;
 
+pub const STABLE_SWAP_MAX_ITERATIONS: usize = 255;
+const STABLE_SWAP_COINS_POW: u64 = 4; // n^n, with n = 2 tokens
+
 #[multiversx_sc::module]
 pub trait AmmModule:
     config::ConfigModule
@@ -17,7 +22,21 @@ pub trait AmmModule:
         first_token_amount: &BigUint,
         second_token_amount: &BigUint,
     ) -> BigUint {
-        first_token_amount * second_token_amount
+        match self.get_curve() {
+            Curve::ConstantProduct => first_token_amount * second_token_amount,
+            Curve::StableSwap {
+                amplification_coefficient,
+            } => self.compute_stable_swap_invariant(
+                first_token_amount,
+                second_token_amount,
+                amplification_coefficient,
+            ),
+            Curve::Weighted { first_token_weight } => self.compute_weighted_invariant(
+                first_token_amount,
+                second_token_amount,
+                first_token_weight,
+            ),
+        }
     }
 
     fn quote(
@@ -29,29 +48,81 @@ pub trait AmmModule:
         &(first_token_amount * second_token_reserve) / first_token_reserve
     }
 
-    fn get_amount_out_no_fee(
+    /// Values the given amount at the current marginal price of the curve.
+    /// For the constant product curve this is the same as the reserves ratio.
+    fn quote_for_curve(
         &self,
+        curve: Curve,
+        swap_tokens_order: SwapTokensOrder,
         amount_in: &BigUint,
         reserve_in: &BigUint,
         reserve_out: &BigUint,
     ) -> BigUint {
-        let numerator = amount_in * reserve_out;
-        let denominator = reserve_in + amount_in;
+        match curve {
+            Curve::ConstantProduct => self.quote(amount_in, reserve_in, reserve_out),
+            Curve::StableSwap {
+                amplification_coefficient,
+            } => {
+                // dy/dx = (4 * Ann * x^2 * y^2 + D^3 * y) / (4 * Ann * x^2 * y^2 + D^3 * x)
+                let invariant = self.compute_stable_swap_invariant(
+                    reserve_in,
+                    reserve_out,
+                    amplification_coefficient,
+                );
+                let invariant_cubed = &invariant * &invariant * &invariant;
+                let reserves_product = reserve_in * reserve_out;
+                let amplified_term = &reserves_product
+                    * &reserves_product
+                    * (amplification_coefficient * STABLE_SWAP_COINS_POW * 4);
+
+                let numerator = &amplified_term + &(&invariant_cubed * reserve_out);
+                let denominator = amplified_term + invariant_cubed * reserve_in;
 
-        numerator / denominator
+                amount_in * &numerator / denominator
+            }
+            Curve::Weighted { first_token_weight } => {
+                // dy/dx = (y / w_out) / (x / w_in)
+                let (weight_in, weight_out) =
+                    self.get_curve_weights(first_token_weight, swap_tokens_order);
+
+                amount_in * reserve_out * weight_in / (reserve_in * weight_out)
+            }
+        }
     }
 
-    fn get_amount_out(
+    fn get_amount_out_no_fee(
         &self,
         amount_in: &BigUint,
         reserve_in: &BigUint,
         reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
     ) -> BigUint {
-        let amount_in_with_fee = amount_in * (MAX_PERCENTAGE - self.total_fee_percent().get());
-        let numerator = &amount_in_with_fee * reserve_out;
-        let denominator = (reserve_in * MAX_PERCENTAGE) + amount_in_with_fee;
+        self.get_amount_out_for_curve(
+            self.get_curve(),
+            swap_tokens_order,
+            0,
+            amount_in,
+            reserve_in,
+            reserve_out,
+        )
+    }
 
-        numerator / denominator
+    fn get_amount_out(
+        &self,
+        amount_in: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+        total_fee_percent: u64,
+    ) -> BigUint {
+        self.get_amount_out_for_curve(
+            self.get_curve(),
+            swap_tokens_order,
+            total_fee_percent,
+            amount_in,
+            reserve_in,
+            reserve_out,
+        )
     }
 
     fn get_amount_in(
@@ -59,12 +130,342 @@ pub trait AmmModule:
         amount_out: &BigUint,
         reserve_in: &BigUint,
         reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+        total_fee_percent: u64,
     ) -> BigUint {
-        let numerator = reserve_in * amount_out * MAX_PERCENTAGE;
-        let denominator =
-            (reserve_out - amount_out) * (MAX_PERCENTAGE - self.total_fee_percent().get());
+        self.get_amount_in_for_curve(
+            self.get_curve(),
+            swap_tokens_order,
+            total_fee_percent,
+            amount_out,
+            reserve_in,
+            reserve_out,
+        )
+    }
+
+    fn get_amount_out_for_curve(
+        &self,
+        curve: Curve,
+        swap_tokens_order: SwapTokensOrder,
+        fee_percent: u64,
+        amount_in: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+    ) -> BigUint {
+        match curve {
+            Curve::ConstantProduct => {
+                let amount_in_with_fee = amount_in * (MAX_PERCENTAGE - fee_percent);
+                let numerator = &amount_in_with_fee * reserve_out;
+                let denominator = (reserve_in * MAX_PERCENTAGE) + amount_in_with_fee;
+
+                numerator / denominator
+            }
+            Curve::StableSwap {
+                amplification_coefficient,
+            } => {
+                let amount_in_with_fee =
+                    amount_in * (MAX_PERCENTAGE - fee_percent) / MAX_PERCENTAGE;
+                let invariant = self.compute_stable_swap_invariant(
+                    reserve_in,
+                    reserve_out,
+                    amplification_coefficient,
+                );
+                let new_reserve_out = self.compute_stable_swap_other_reserve(
+                    &(reserve_in + &amount_in_with_fee),
+                    &invariant,
+                    amplification_coefficient,
+                );
+
+                // round down in favour of the pool
+                let new_reserve_out_rounded = new_reserve_out + 1u64;
+                if reserve_out > &new_reserve_out_rounded {
+                    reserve_out - &new_reserve_out_rounded
+                } else {
+                    BigUint::zero()
+                }
+            }
+            Curve::Weighted { first_token_weight } => {
+                let amount_in_with_fee =
+                    amount_in * (MAX_PERCENTAGE - fee_percent) / MAX_PERCENTAGE;
+                let (exponent_in, exponent_out) =
+                    self.get_weighted_exponents(first_token_weight, swap_tokens_order);
+                let invariant = reserve_in.pow(exponent_in) * reserve_out.pow(exponent_out);
+
+                // rounded up in favour of the pool
+                let new_reserve_in_pow = (reserve_in + &amount_in_with_fee).pow(exponent_in);
+                let new_reserve_out = self.nth_root_rounded_up(
+                    &self.div_rounded_up(&invariant, &new_reserve_in_pow),
+                    exponent_out,
+                );
+                if reserve_out > &new_reserve_out {
+                    reserve_out - &new_reserve_out
+                } else {
+                    BigUint::zero()
+                }
+            }
+        }
+    }
+
+    fn get_amount_in_for_curve(
+        &self,
+        curve: Curve,
+        swap_tokens_order: SwapTokensOrder,
+        fee_percent: u64,
+        amount_out: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+    ) -> BigUint {
+        match curve {
+            Curve::ConstantProduct => {
+                let numerator = reserve_in * amount_out * MAX_PERCENTAGE;
+                let denominator = (reserve_out - amount_out) * (MAX_PERCENTAGE - fee_percent);
+
+                (numerator / denominator) + 1u64
+            }
+            Curve::StableSwap {
+                amplification_coefficient,
+            } => {
+                let invariant = self.compute_stable_swap_invariant(
+                    reserve_in,
+                    reserve_out,
+                    amplification_coefficient,
+                );
+                let new_reserve_in = self.compute_stable_swap_other_reserve(
+                    &(reserve_out - amount_out),
+                    &invariant,
+                    amplification_coefficient,
+                );
+                let amount_in_no_fee = new_reserve_in - reserve_in + 1u64;
+
+                (amount_in_no_fee * MAX_PERCENTAGE / (MAX_PERCENTAGE - fee_percent)) + 1u64
+            }
+            Curve::Weighted { first_token_weight } => {
+                let (exponent_in, exponent_out) =
+                    self.get_weighted_exponents(first_token_weight, swap_tokens_order);
+                let invariant = reserve_in.pow(exponent_in) * reserve_out.pow(exponent_out);
+
+                let new_reserve_out_pow = (reserve_out - amount_out).pow(exponent_out);
+                let new_reserve_in = self.nth_root_rounded_up(
+                    &self.div_rounded_up(&invariant, &new_reserve_out_pow),
+                    exponent_in,
+                );
+                let amount_in_no_fee = new_reserve_in - reserve_in;
+
+                (amount_in_no_fee * MAX_PERCENTAGE / (MAX_PERCENTAGE - fee_percent)) + 1u64
+            }
+        }
+    }
+
+    /// The part of a single token deposit that has to be swapped first, so that what is left
+    /// and the swap output match the reserves ratio after the swap.
+    fn get_single_token_swap_amount(
+        &self,
+        amount: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+        fee_percent: u64,
+    ) -> BigUint {
+        let curve = self.get_curve();
+        if curve == Curve::ConstantProduct {
+            // s = (sqrt(r^2 * (2 - f)^2 + 4 * (1 - f) * a * r) - r * (2 - f)) / (2 * (1 - f))
+            let two_minus_fee = 2 * MAX_PERCENTAGE - fee_percent;
+            let one_minus_fee = MAX_PERCENTAGE - fee_percent;
+            let scaled_reserve = reserve_in * two_minus_fee;
+            let discriminant = &scaled_reserve * &scaled_reserve
+                + amount * reserve_in * (4 * one_minus_fee * MAX_PERCENTAGE);
+
+            return (discriminant.sqrt() - scaled_reserve) / (2 * one_minus_fee);
+        }
+
+        // the other curves have no closed form, so the swap amount is searched for instead,
+        // knowing that the swap output grows faster than what is left to deposit shrinks
+        let mut low = BigUint::zero();
+        let mut high = amount.clone();
+        while &high - &low > 1u64 {
+            let middle = (&low + &high) / 2u64;
+            let amount_out = self.get_amount_out_for_curve(
+                curve,
+                swap_tokens_order,
+                fee_percent,
+                &middle,
+                reserve_in,
+                reserve_out,
+            );
+            if &amount_out >= reserve_out {
+                high = middle;
+                continue;
+            }
+
+            let new_reserve_in = reserve_in + &middle;
+            let new_reserve_out = reserve_out - &amount_out;
+            if amount_out * new_reserve_in <= (amount - &middle) * new_reserve_out {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        low
+    }
+
+    /// Computes D from the StableSwap invariant, for two tokens:
+    ///
+    /// Ann * (x + y) + D = Ann * D + D^3 / (4 * x * y), with Ann = A * n^n
+    fn compute_stable_swap_invariant(
+        &self,
+        first_reserve: &BigUint,
+        second_reserve: &BigUint,
+        amplification_coefficient: u64,
+    ) -> BigUint {
+        if first_reserve == &0u64 || second_reserve == &0u64 {
+            return BigUint::zero();
+        }
+
+        let amplification = BigUint::from(amplification_coefficient * STABLE_SWAP_COINS_POW);
+        let reserves_sum = first_reserve + second_reserve;
+        let reserves_product = first_reserve * second_reserve * STABLE_SWAP_COINS_POW;
+
+        let mut invariant = reserves_sum.clone();
+        for _ in 0..STABLE_SWAP_MAX_ITERATIONS {
+            let invariant_product = &invariant * &invariant * &invariant / &reserves_product;
+            let previous_invariant = invariant.clone();
+
+            let numerator =
+                (&amplification * &reserves_sum + &invariant_product * 2u64) * &invariant;
+            let denominator = (&amplification - 1u64) * &invariant + invariant_product * 3u64;
+            invariant = numerator / denominator;
+
+            if self.is_within_one(&invariant, &previous_invariant) {
+                return invariant;
+            }
+        }
+
+        sc_panic!(ERROR_STABLE_SWAP_NOT_CONVERGED);
+    }
+
+    /// Given the new reserve of one token, computes the reserve of the other token
+    /// that keeps the StableSwap invariant D unchanged
+    fn compute_stable_swap_other_reserve(
+        &self,
+        new_reserve: &BigUint,
+        invariant: &BigUint,
+        amplification_coefficient: u64,
+    ) -> BigUint {
+        let amplification = BigUint::from(amplification_coefficient * STABLE_SWAP_COINS_POW);
+        let c = invariant * invariant * invariant
+            / (new_reserve * STABLE_SWAP_COINS_POW * &amplification);
+        let b = new_reserve + &(invariant / &amplification);
+
+        let mut other_reserve = invariant.clone();
+        for _ in 0..STABLE_SWAP_MAX_ITERATIONS {
+            let previous_other_reserve = other_reserve.clone();
+
+            let numerator = &other_reserve * &other_reserve + &c;
+            let denominator = &other_reserve * 2u64 + &b;
+            require!(denominator > *invariant, ERROR_STABLE_SWAP_NOT_CONVERGED);
+            other_reserve = numerator / (denominator - invariant);
+
+            if self.is_within_one(&other_reserve, &previous_other_reserve) {
+                return other_reserve;
+            }
+        }
+
+        sc_panic!(ERROR_STABLE_SWAP_NOT_CONVERGED);
+    }
+
+    /// Computes x^a * y^b, where a / b is the reduced ratio of the token weights.
+    /// This is a power of the weighted product invariant x^wx * y^wy,
+    /// which keeps the exact integer math enough for comparisons.
+    fn compute_weighted_invariant(
+        &self,
+        first_reserve: &BigUint,
+        second_reserve: &BigUint,
+        first_token_weight: u64,
+    ) -> BigUint {
+        let (first_exponent, second_exponent) =
+            self.get_weighted_exponents(first_token_weight, SwapTokensOrder::PoolOrder);
+
+        first_reserve.pow(first_exponent) * second_reserve.pow(second_exponent)
+    }
+
+    /// The weighted geometric mean of the reserves, used as the initial LP supply
+    fn compute_weighted_liquidity(
+        &self,
+        first_reserve: &BigUint,
+        second_reserve: &BigUint,
+        first_token_weight: u64,
+    ) -> BigUint {
+        let (first_exponent, second_exponent) =
+            self.get_weighted_exponents(first_token_weight, SwapTokensOrder::PoolOrder);
+        let invariant =
+            self.compute_weighted_invariant(first_reserve, second_reserve, first_token_weight);
+
+        math::nth_root(&invariant, first_exponent + second_exponent)
+    }
+
+    /// Brings the invariant back to the same scale as the LP supply,
+    /// so that its growth can be compared to the LP supply
+    fn get_invariant_root(&self, invariant: &BigUint) -> BigUint {
+        match self.get_curve() {
+            Curve::ConstantProduct => invariant.sqrt(),
+            Curve::StableSwap { .. } => invariant.clone(),
+            Curve::Weighted { first_token_weight } => {
+                let (first_exponent, second_exponent) =
+                    self.get_weighted_exponents(first_token_weight, SwapTokensOrder::PoolOrder);
+
+                math::nth_root(invariant, first_exponent + second_exponent)
+            }
+        }
+    }
+
+    /// Returns the (input, output) token weights
+    fn get_curve_weights(
+        &self,
+        first_token_weight: u64,
+        swap_tokens_order: SwapTokensOrder,
+    ) -> (u64, u64) {
+        let second_token_weight = TOTAL_CURVE_WEIGHT - first_token_weight;
+        match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => (first_token_weight, second_token_weight),
+            SwapTokensOrder::ReverseOrder => (second_token_weight, first_token_weight),
+        }
+    }
+
+    /// Returns the (input, output) token weights, divided by their greatest common divisor
+    fn get_weighted_exponents(
+        &self,
+        first_token_weight: u64,
+        swap_tokens_order: SwapTokensOrder,
+    ) -> (u32, u32) {
+        let (first_exponent, second_exponent) = config::weighted_exponents(first_token_weight);
+        match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => (first_exponent as u32, second_exponent as u32),
+            SwapTokensOrder::ReverseOrder => (second_exponent as u32, first_exponent as u32),
+        }
+    }
+
+    fn nth_root_rounded_up(&self, value: &BigUint, n: u32) -> BigUint {
+        let root = math::nth_root(value, n);
+        if &root.pow(n) < value {
+            root + 1u64
+        } else {
+            root
+        }
+    }
+
+    #[inline]
+    fn div_rounded_up(&self, numerator: &BigUint, denominator: &BigUint) -> BigUint {
+        (numerator + denominator - 1u64) / denominator
+    }
 
-        (numerator / denominator) + 1u64
+    #[inline]
+    fn is_within_one(&self, first: &BigUint, second: &BigUint) -> bool {
+        if first > second {
+            first - second <= 1u64
+        } else {
+            second - first <= 1u64
+        }
     }
 
     fn get_special_fee_from_input(&self, amount_in: &BigUint) -> BigUint {
@@ -0,0 +1,238 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use pausable::State;
+
+use crate::config::MAX_PERCENTAGE;
+use crate::contexts::base::{StorageCache, SwapTokensOrder};
+use crate::errors::{
+    ERROR_BAD_CIRCUIT_BREAKER_DEVIATION, ERROR_BAD_CIRCUIT_BREAKER_WINDOW,
+    ERROR_CIRCUIT_BREAKER_TRIPPED,
+};
+use crate::safe_price::Round;
+
+use super::{amm, config, events, read_pair_storage, safe_price, safe_price_view};
+
+const CIRCUIT_BREAKER_PRICE_PRECISION: u64 = 1_000_000_000_000_000_000;
+
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug,
+)]
+pub enum CircuitBreakerAction {
+    /// The swap that moves the price too far is reverted
+    RevertSwap,
+    /// The swap goes through, but the pair switches to `State::PartialActive`
+    /// until the owner resumes it
+    PauseSwaps,
+}
+
+/// When set, every swap compares the spot price after the swap with the safe price over
+/// the last `round_window` rounds. The deviation is relative to the safe price,
+/// in `MAX_PERCENTAGE` units.
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug,
+)]
+pub struct CircuitBreakerConfig {
+    pub round_window: u64,
+    pub max_deviation_percent: u64,
+    pub action: CircuitBreakerAction,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct CircuitBreakerStatus {
+    pub enabled: bool,
+    /// `None` while there are not enough price observations for the window
+    pub current_deviation_percent: Option<u64>,
+    pub last_trip_round: Round,
+    pub swaps_paused: bool,
+}
+
+#[multiversx_sc::module]
+pub trait CircuitBreakerModule:
+    safe_price_view::SafePriceViewModule
+    + safe_price::SafePriceModule
+    + read_pair_storage::ReadPairStorageModule
+    + events::EventsModule
+    + amm::AmmModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    #[endpoint(setCircuitBreakerConfig)]
+    fn set_circuit_breaker_config(
+        &self,
+        round_window: u64,
+        max_deviation_percent: u64,
+        action: CircuitBreakerAction,
+    ) {
+        self.require_caller_has_owner_or_admin_permissions();
+        require!(round_window > 0, ERROR_BAD_CIRCUIT_BREAKER_WINDOW);
+        require!(
+            max_deviation_percent > 0,
+            ERROR_BAD_CIRCUIT_BREAKER_DEVIATION
+        );
+
+        self.circuit_breaker_config().set(CircuitBreakerConfig {
+            round_window,
+            max_deviation_percent,
+            action,
+        });
+    }
+
+    #[endpoint(disableCircuitBreaker)]
+    fn disable_circuit_breaker(&self) {
+        self.require_caller_has_owner_or_admin_permissions();
+        self.circuit_breaker_config().clear();
+    }
+
+    #[view(getCircuitBreakerConfig)]
+    fn get_circuit_breaker_config(&self) -> OptionalValue<CircuitBreakerConfig> {
+        let config_mapper = self.circuit_breaker_config();
+        if config_mapper.is_empty() {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(config_mapper.get())
+        }
+    }
+
+    #[view(getCircuitBreakerStatus)]
+    fn get_circuit_breaker_status(&self) -> CircuitBreakerStatus {
+        let config_mapper = self.circuit_breaker_config();
+        let enabled = !config_mapper.is_empty();
+        let current_deviation_percent = if enabled {
+            let first_token_reserve = self.pair_reserve(&self.first_token_id().get()).get();
+            let second_token_reserve = self.pair_reserve(&self.second_token_id().get()).get();
+            self.get_price_deviation(
+                config_mapper.get().round_window,
+                &first_token_reserve,
+                &second_token_reserve,
+            )
+            .map(|(deviation_percent, _, _)| deviation_percent)
+        } else {
+            None
+        };
+
+        CircuitBreakerStatus {
+            enabled,
+            current_deviation_percent,
+            last_trip_round: self.circuit_breaker_last_trip_round().get(),
+            swaps_paused: self.state().get() == State::PartialActive,
+        }
+    }
+
+    /// Has to be called after the swap is applied to the storage cache reserves.
+    /// The reserves in storage are still the ones before the swap, so the safe price
+    /// does not include the swap itself.
+    fn check_circuit_breaker(&self, storage_cache: &mut StorageCache<Self>) {
+        let config_mapper = self.circuit_breaker_config();
+        if config_mapper.is_empty() {
+            return;
+        }
+
+        let config = config_mapper.get();
+        let opt_deviation = self.get_price_deviation(
+            config.round_window,
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        let (deviation_percent, spot_price, safe_price) = match opt_deviation {
+            Some(deviation) => deviation,
+            None => return,
+        };
+        if deviation_percent <= config.max_deviation_percent {
+            return;
+        }
+
+        match config.action {
+            CircuitBreakerAction::RevertSwap => sc_panic!(ERROR_CIRCUIT_BREAKER_TRIPPED),
+            CircuitBreakerAction::PauseSwaps => {
+                storage_cache.contract_state = State::PartialActive;
+                self.state().set(State::PartialActive);
+                self.circuit_breaker_last_trip_round()
+                    .set(self.blockchain().get_block_round());
+                self.emit_circuit_breaker_event(
+                    storage_cache,
+                    spot_price,
+                    safe_price,
+                    deviation_percent,
+                );
+            }
+        }
+    }
+
+    /// Returns the deviation percent, the spot price and the safe price,
+    /// with the prices given for `CIRCUIT_BREAKER_PRICE_PRECISION` units of the first token
+    fn get_price_deviation(
+        &self,
+        round_window: u64,
+        first_token_reserve: &BigUint,
+        second_token_reserve: &BigUint,
+    ) -> Option<(u64, BigUint, BigUint)> {
+        if first_token_reserve == &0u64 || second_token_reserve == &0u64 {
+            return None;
+        }
+
+        let current_round = self.blockchain().get_block_round();
+        if round_window >= current_round {
+            return None;
+        }
+
+        let start_round = current_round - round_window;
+        let sc_address = self.blockchain().get_sc_address();
+        let price_observations = self.get_price_observation_mapper(sc_address.clone());
+        if price_observations.is_empty() {
+            return None;
+        }
+
+        let safe_price_current_index = self
+            .get_safe_price_current_index_mapper(sc_address.clone())
+            .get();
+        let oldest_price_observation = self.get_oldest_price_observation(
+            &sc_address,
+            safe_price_current_index,
+            &price_observations,
+        );
+        if oldest_price_observation.recording_round > start_round {
+            return None;
+        }
+
+        let price_unit = BigUint::from(CIRCUIT_BREAKER_PRICE_PRECISION);
+        let safe_price = self
+            .get_safe_price(
+                sc_address,
+                start_round,
+                current_round,
+                EsdtTokenPayment::new(self.first_token_id().get(), 0, price_unit.clone()),
+            )
+            .amount;
+        if safe_price == 0u64 {
+            return None;
+        }
+
+        let spot_price = self.quote_for_curve(
+            self.get_curve(),
+            SwapTokensOrder::PoolOrder,
+            &price_unit,
+            first_token_reserve,
+            second_token_reserve,
+        );
+        let price_difference = if spot_price > safe_price {
+            &spot_price - &safe_price
+        } else {
+            &safe_price - &spot_price
+        };
+        let deviation_percent = (price_difference * MAX_PERCENTAGE / &safe_price)
+            .to_u64()
+            .unwrap_or(u64::MAX);
+
+        Some((deviation_percent, spot_price, safe_price))
+    }
+
+    #[storage_mapper("circuitBreakerConfig")]
+    fn circuit_breaker_config(&self) -> SingleValueMapper<CircuitBreakerConfig>;
+
+    #[storage_mapper("circuitBreakerLastTripRound")]
+    fn circuit_breaker_last_trip_round(&self) -> SingleValueMapper<Round>;
+}
@@ -0,0 +1,4 @@
+pub mod positions;
+pub mod swap;
+pub mod tick_math;
+pub mod ticks;
@@ -0,0 +1,457 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use super::swap;
+use super::tick_math::{self, MAX_TICK};
+use super::ticks::{self, FEE_GROWTH_PRECISION};
+use crate::config::{self, Curve};
+use crate::contexts::base::StorageCache;
+use crate::errors::*;
+use crate::pair_actions::common_result_types::{
+    AddConcentratedLiquidityResultType, CollectConcentratedFeesResultType,
+    RemoveConcentratedLiquidityResultType,
+};
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct ConcentratedPositionAttributes<M: ManagedTypeApi> {
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: BigUint<M>,
+    pub fee_growth_inside_first_last: BigInt<M>,
+    pub fee_growth_inside_second_last: BigInt<M>,
+}
+
+#[multiversx_sc::module]
+pub trait ConcentratedPositionsModule:
+    swap::ConcentratedSwapModule
+    + ticks::TicksModule
+    + tick_math::TickMathModule
+    + config::ConfigModule
+    + crate::events::EventsModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + crate::pair_actions::common_methods::CommonMethodsModule
+{
+    #[endpoint(setPositionTokenIdentifier)]
+    fn set_position_token_identifier(&self, token_identifier: TokenIdentifier) {
+        self.require_caller_has_owner_permissions();
+
+        require!(
+            self.position_token_identifier().is_empty(),
+            ERROR_POSITION_TOKEN_ALREADY_SET
+        );
+        require!(
+            token_identifier != self.first_token_id().get()
+                && token_identifier != self.second_token_id().get()
+                && token_identifier != self.lp_token_identifier().get(),
+            ERROR_POSITION_TOKEN_SAME_AS_POOL_TOKENS
+        );
+        require!(
+            token_identifier.is_valid_esdt_identifier(),
+            ERROR_NOT_AN_ESDT
+        );
+        self.position_token_identifier().set(&token_identifier);
+    }
+
+    #[endpoint(setTickSpacing)]
+    fn set_tick_spacing(&self, tick_spacing: i32) {
+        self.require_caller_has_owner_permissions();
+
+        require!(
+            !self.is_concentrated_liquidity_active(),
+            ERROR_CONCENTRATED_POSITIONS_EXIST
+        );
+        require!(
+            tick_spacing > 0 && tick_spacing <= MAX_TICK,
+            ERROR_INVALID_TICK
+        );
+        self.tick_spacing().set(tick_spacing);
+    }
+
+    #[payable("*")]
+    #[endpoint(addConcentratedLiquidity)]
+    fn add_concentrated_liquidity(
+        &self,
+        tick_lower: i32,
+        tick_upper: i32,
+        first_token_amount_min: BigUint,
+        second_token_amount_min: BigUint,
+    ) -> AddConcentratedLiquidityResultType<Self::Api> {
+        let storage_cache = StorageCache::new(self);
+        let caller = self.blockchain().get_caller();
+
+        require!(
+            self.is_state_active(storage_cache.contract_state),
+            ERROR_NOT_ACTIVE
+        );
+        require!(
+            !self.position_token_identifier().is_empty(),
+            ERROR_POSITION_TOKEN_NOT_SET
+        );
+        require!(
+            self.get_curve() == Curve::ConstantProduct,
+            ERROR_CONCENTRATED_LIQUIDITY_CURVE
+        );
+        require!(
+            storage_cache.lp_token_supply > 0u64,
+            ERROR_INITIAL_LIQUIDITY_NOT_ADDED
+        );
+        self.require_valid_tick_range(tick_lower, tick_upper);
+
+        let mut first_token_amount = BigUint::zero();
+        let mut second_token_amount = BigUint::zero();
+        for payment in self.call_value().all_esdt_transfers().iter() {
+            if payment.token_identifier == storage_cache.first_token_id {
+                first_token_amount += payment.amount;
+            } else if payment.token_identifier == storage_cache.second_token_id {
+                second_token_amount += payment.amount;
+            } else {
+                sc_panic!(ERROR_BAD_PAYMENT_TOKENS);
+            }
+        }
+
+        if !self.is_concentrated_liquidity_active() {
+            let sqrt_price = self.get_sqrt_price_from_reserves(
+                &storage_cache.first_token_reserve,
+                &storage_cache.second_token_reserve,
+            );
+            self.current_tick()
+                .set(self.get_tick_at_sqrt_price(&sqrt_price));
+            self.concentrated_sqrt_price().set(sqrt_price);
+        }
+        let sqrt_price = self.concentrated_sqrt_price().get();
+
+        let sqrt_price_lower = self.get_sqrt_price_at_tick(tick_lower);
+        let sqrt_price_upper = self.get_sqrt_price_at_tick(tick_upper);
+        let liquidity = self.get_liquidity_for_amounts(
+            &sqrt_price,
+            &sqrt_price_lower,
+            &sqrt_price_upper,
+            &first_token_amount,
+            &second_token_amount,
+        );
+        require!(liquidity > 0u64, ERROR_INSUFFICIENT_LIQUIDITY);
+
+        let (first_token_used, second_token_used) = self.get_amounts_for_liquidity(
+            &sqrt_price,
+            &sqrt_price_lower,
+            &sqrt_price_upper,
+            &liquidity,
+            true,
+        );
+        require!(
+            first_token_used <= first_token_amount && second_token_used <= second_token_amount,
+            ERROR_OPTIMAL_GRATER_THAN_PAID
+        );
+        require!(
+            first_token_used >= first_token_amount_min,
+            ERROR_INSUFFICIENT_FIRST_TOKEN
+        );
+        require!(
+            second_token_used >= second_token_amount_min,
+            ERROR_INSUFFICIENT_SECOND_TOKEN
+        );
+
+        self.update_position_liquidity(
+            tick_lower,
+            tick_upper,
+            &BigInt::from_biguint(Sign::Plus, liquidity.clone()),
+        );
+        self.concentrated_positions_count()
+            .update(|count| *count += 1);
+        self.concentrated_reserve(&storage_cache.first_token_id)
+            .update(|reserve| *reserve += &first_token_used);
+        self.concentrated_reserve(&storage_cache.second_token_id)
+            .update(|reserve| *reserve += &second_token_used);
+
+        let (fee_growth_inside_first, fee_growth_inside_second) =
+            self.get_fee_growth_inside(tick_lower, tick_upper);
+        let attributes = ConcentratedPositionAttributes {
+            tick_lower,
+            tick_upper,
+            liquidity,
+            fee_growth_inside_first_last: fee_growth_inside_first,
+            fee_growth_inside_second_last: fee_growth_inside_second,
+        };
+        let position = self.mint_position(attributes.clone());
+
+        let mut output_payments = ManagedVec::new();
+        output_payments.push(position.clone());
+        output_payments.push(EsdtTokenPayment::new(
+            storage_cache.first_token_id.clone(),
+            0,
+            &first_token_amount - &first_token_used,
+        ));
+        output_payments.push(EsdtTokenPayment::new(
+            storage_cache.second_token_id.clone(),
+            0,
+            &second_token_amount - &second_token_used,
+        ));
+        self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
+
+        let first_payment =
+            EsdtTokenPayment::new(storage_cache.first_token_id.clone(), 0, first_token_used);
+        let second_payment =
+            EsdtTokenPayment::new(storage_cache.second_token_id.clone(), 0, second_token_used);
+        self.emit_concentrated_liquidity_event(
+            &caller,
+            true,
+            &position,
+            &attributes,
+            &first_payment,
+            &second_payment,
+        );
+
+        (position, first_payment, second_payment).into()
+    }
+
+    #[payable("*")]
+    #[endpoint(removeConcentratedLiquidity)]
+    fn remove_concentrated_liquidity(
+        &self,
+        first_token_amount_min: BigUint,
+        second_token_amount_min: BigUint,
+    ) -> RemoveConcentratedLiquidityResultType<Self::Api> {
+        let storage_cache = StorageCache::new(self);
+        let caller = self.blockchain().get_caller();
+        let position = self.call_value().single_esdt();
+
+        require!(
+            self.can_withdraw(storage_cache.contract_state),
+            ERROR_NOT_ACTIVE
+        );
+        let attributes = self.get_position_attributes(&position);
+
+        let (first_fee, second_fee) = self.get_position_fees(&attributes);
+        self.update_position_liquidity(
+            attributes.tick_lower,
+            attributes.tick_upper,
+            &BigInt::from_biguint(Sign::Minus, attributes.liquidity.clone()),
+        );
+
+        let (first_token_amount, second_token_amount) = self.get_amounts_for_liquidity(
+            &self.concentrated_sqrt_price().get(),
+            &self.get_sqrt_price_at_tick(attributes.tick_lower),
+            &self.get_sqrt_price_at_tick(attributes.tick_upper),
+            &attributes.liquidity,
+            false,
+        );
+        require!(
+            first_token_amount >= first_token_amount_min,
+            ERROR_SLIPPAGE_ON_REMOVE
+        );
+        require!(
+            second_token_amount >= second_token_amount_min,
+            ERROR_SLIPPAGE_ON_REMOVE
+        );
+
+        let first_payment = EsdtTokenPayment::new(
+            storage_cache.first_token_id.clone(),
+            0,
+            self.withdraw_concentrated_reserve(
+                &storage_cache.first_token_id,
+                first_token_amount + first_fee,
+            ),
+        );
+        let second_payment = EsdtTokenPayment::new(
+            storage_cache.second_token_id.clone(),
+            0,
+            self.withdraw_concentrated_reserve(
+                &storage_cache.second_token_id,
+                second_token_amount + second_fee,
+            ),
+        );
+
+        self.send().esdt_local_burn(
+            &position.token_identifier,
+            position.token_nonce,
+            &position.amount,
+        );
+        self.concentrated_positions_count()
+            .update(|count| *count -= 1);
+
+        let mut output_payments = ManagedVec::new();
+        output_payments.push(first_payment.clone());
+        output_payments.push(second_payment.clone());
+        self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
+
+        self.emit_concentrated_liquidity_event(
+            &caller,
+            false,
+            &position,
+            &attributes,
+            &first_payment,
+            &second_payment,
+        );
+
+        (first_payment, second_payment).into()
+    }
+
+    #[payable("*")]
+    #[endpoint(collectConcentratedFees)]
+    fn collect_concentrated_fees(&self) -> CollectConcentratedFeesResultType<Self::Api> {
+        let storage_cache = StorageCache::new(self);
+        let caller = self.blockchain().get_caller();
+        let position = self.call_value().single_esdt();
+
+        require!(
+            self.can_withdraw(storage_cache.contract_state),
+            ERROR_NOT_ACTIVE
+        );
+        let mut attributes = self.get_position_attributes(&position);
+
+        let (first_fee, second_fee) = self.get_position_fees(&attributes);
+        let (fee_growth_inside_first, fee_growth_inside_second) =
+            self.get_fee_growth_inside(attributes.tick_lower, attributes.tick_upper);
+        attributes.fee_growth_inside_first_last = fee_growth_inside_first;
+        attributes.fee_growth_inside_second_last = fee_growth_inside_second;
+
+        self.send().esdt_local_burn(
+            &position.token_identifier,
+            position.token_nonce,
+            &position.amount,
+        );
+        let new_position = self.mint_position(attributes);
+
+        let first_payment = EsdtTokenPayment::new(
+            storage_cache.first_token_id.clone(),
+            0,
+            self.withdraw_concentrated_reserve(&storage_cache.first_token_id, first_fee),
+        );
+        let second_payment = EsdtTokenPayment::new(
+            storage_cache.second_token_id.clone(),
+            0,
+            self.withdraw_concentrated_reserve(&storage_cache.second_token_id, second_fee),
+        );
+
+        let mut output_payments = ManagedVec::new();
+        output_payments.push(new_position.clone());
+        output_payments.push(first_payment.clone());
+        output_payments.push(second_payment.clone());
+        self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
+
+        (new_position, first_payment, second_payment).into()
+    }
+
+    #[view(getConcentratedPositionFees)]
+    fn get_concentrated_position_fees(
+        &self,
+        attributes: ConcentratedPositionAttributes<Self::Api>,
+    ) -> MultiValue2<BigUint, BigUint> {
+        self.get_position_fees(&attributes).into()
+    }
+
+    #[view(getConcentratedPositionAmounts)]
+    fn get_concentrated_position_amounts(
+        &self,
+        attributes: ConcentratedPositionAttributes<Self::Api>,
+    ) -> MultiValue2<BigUint, BigUint> {
+        self.get_amounts_for_liquidity(
+            &self.concentrated_sqrt_price().get(),
+            &self.get_sqrt_price_at_tick(attributes.tick_lower),
+            &self.get_sqrt_price_at_tick(attributes.tick_upper),
+            &attributes.liquidity,
+            false,
+        )
+        .into()
+    }
+
+    fn update_position_liquidity(
+        &self,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity_delta: &BigInt,
+    ) {
+        self.update_tick(tick_lower, liquidity_delta, false);
+        self.update_tick(tick_upper, liquidity_delta, true);
+
+        let current_tick = self.current_tick().get();
+        if tick_lower <= current_tick && current_tick < tick_upper {
+            self.active_concentrated_liquidity()
+                .update(|active_liquidity| {
+                    *active_liquidity = self.add_liquidity_delta(active_liquidity, liquidity_delta);
+                });
+        }
+    }
+
+    fn get_position_fees(
+        &self,
+        attributes: &ConcentratedPositionAttributes<Self::Api>,
+    ) -> (BigUint, BigUint) {
+        let (fee_growth_inside_first, fee_growth_inside_second) =
+            self.get_fee_growth_inside(attributes.tick_lower, attributes.tick_upper);
+
+        (
+            self.compute_fees_owed(
+                fee_growth_inside_first,
+                &attributes.fee_growth_inside_first_last,
+                &attributes.liquidity,
+            ),
+            self.compute_fees_owed(
+                fee_growth_inside_second,
+                &attributes.fee_growth_inside_second_last,
+                &attributes.liquidity,
+            ),
+        )
+    }
+
+    fn compute_fees_owed(
+        &self,
+        fee_growth_inside: BigInt,
+        fee_growth_inside_last: &BigInt,
+        liquidity: &BigUint,
+    ) -> BigUint {
+        let fee_growth_delta = (fee_growth_inside - fee_growth_inside_last.clone())
+            .into_big_uint()
+            .unwrap_or_else(BigUint::zero);
+
+        fee_growth_delta * liquidity / FEE_GROWTH_PRECISION
+    }
+
+    fn get_position_attributes(
+        &self,
+        position: &EsdtTokenPayment,
+    ) -> ConcentratedPositionAttributes<Self::Api> {
+        require!(
+            position.token_identifier == self.position_token_identifier().get()
+                && position.amount == 1u64,
+            ERROR_BAD_PAYMENT_TOKENS
+        );
+
+        self.blockchain()
+            .get_token_attributes(&position.token_identifier, position.token_nonce)
+    }
+
+    fn mint_position(
+        &self,
+        attributes: ConcentratedPositionAttributes<Self::Api>,
+    ) -> EsdtTokenPayment {
+        let token_id = self.position_token_identifier().get();
+        let amount = BigUint::from(1u64);
+        let nonce = self
+            .send()
+            .esdt_nft_create_compact(&token_id, &amount, &attributes);
+
+        EsdtTokenPayment::new(token_id, nonce, amount)
+    }
+
+    /// Rounding may leave the last positions slightly short, in which case
+    /// they receive whatever is left in the reserve
+    fn withdraw_concentrated_reserve(
+        &self,
+        token_id: &TokenIdentifier,
+        amount: BigUint,
+    ) -> BigUint {
+        let reserve_mapper = self.concentrated_reserve(token_id);
+        let reserve = reserve_mapper.get();
+        let withdrawn = core::cmp::min(amount, reserve.clone());
+        reserve_mapper.set(reserve - &withdrawn);
+
+        withdrawn
+    }
+
+    #[view(getPositionTokenIdentifier)]
+    #[storage_mapper("positionTokenIdentifier")]
+    fn position_token_identifier(&self) -> SingleValueMapper<TokenIdentifier>;
+}
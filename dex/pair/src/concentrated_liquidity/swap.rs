@@ -0,0 +1,676 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use super::tick_math::{self, MAX_TICK, MIN_TICK};
+use super::ticks::{self, FEE_GROWTH_PRECISION};
+use crate::config::{self, MAX_PERCENTAGE};
+use crate::contexts::base::{StorageCache, SwapTokensOrder};
+use crate::errors::{ERROR_NOT_ENOUGH_RESERVE, ERROR_PRICE_LIMIT_REACHED};
+
+#[derive(ManagedVecItem, Clone)]
+pub struct CrossedTick<M: ManagedTypeApi> {
+    pub tick: i32,
+    pub fee_growth_global_in: BigUint<M>,
+}
+
+pub struct ConcentratedSwapResult<M: ManagedTypeApi> {
+    pub amount_in: BigUint<M>,
+    pub amount_out: BigUint<M>,
+    pub full_range_reserve_in: BigUint<M>,
+    pub full_range_reserve_out: BigUint<M>,
+    pub ranged_amount_in: BigUint<M>,
+    pub ranged_amount_out: BigUint<M>,
+    pub ranged_fee: BigUint<M>,
+    pub fee_growth_global_in: BigUint<M>,
+    pub sqrt_price: BigUint<M>,
+    pub tick: i32,
+    pub active_liquidity: BigUint<M>,
+    pub crossed_ticks: ManagedVec<M, CrossedTick<M>>,
+}
+
+struct SwapStep<M: ManagedTypeApi> {
+    sqrt_price_next: BigUint<M>,
+    target_reached: bool,
+    full_range_in: BigUint<M>,
+    full_range_out: BigUint<M>,
+    ranged_in: BigUint<M>,
+    ranged_out: BigUint<M>,
+}
+
+/// Moves the ranged square root price towards the full range price, by exchanging
+/// tokens between the full range reserves and the ranged positions
+pub struct PriceRebase<M: ManagedTypeApi> {
+    pub sqrt_price: BigUint<M>,
+    pub tick: i32,
+    pub price_goes_down: bool,
+    /// Paid by the full range reserves to the ranged positions
+    pub ranged_amount_in: BigUint<M>,
+    /// Paid by the ranged positions to the full range reserves
+    pub ranged_amount_out: BigUint<M>,
+}
+
+/// Swaps against the full range reserves and the ranged positions together.
+/// Between two initialized ticks, the pool behaves as a constant product curve with
+/// liquidity equal to sqrt(first_reserve * second_reserve) plus the active ranged liquidity,
+/// and the amounts are split between the two proportionally to their liquidity.
+/// The ranged positions track their own square root price, which starts from the
+/// full range price when the first position is opened. The full range reinvests its
+/// fees into the reserves, which moves its price past the ranged one, so both are
+/// rebased to their liquidity weighted average after each swap.
+#[multiversx_sc::module]
+pub trait ConcentratedSwapModule:
+    ticks::TicksModule
+    + tick_math::TickMathModule
+    + config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    fn is_concentrated_liquidity_active(&self) -> bool {
+        self.concentrated_positions_count().get() > 0
+    }
+
+    fn get_concentrated_amount_out(
+        &self,
+        first_token_reserve: &BigUint,
+        second_token_reserve: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+        amount_in: &BigUint,
+        total_fee_percent: u64,
+    ) -> BigUint {
+        let amount_in_with_fee = amount_in * (MAX_PERCENTAGE - total_fee_percent) / MAX_PERCENTAGE;
+
+        self.simulate_concentrated_swap(
+            first_token_reserve,
+            second_token_reserve,
+            swap_tokens_order,
+            &amount_in_with_fee,
+            true,
+            total_fee_percent,
+            total_fee_percent,
+        )
+        .amount_out
+    }
+
+    fn get_concentrated_amount_in(
+        &self,
+        first_token_reserve: &BigUint,
+        second_token_reserve: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+        amount_out: &BigUint,
+        total_fee_percent: u64,
+    ) -> BigUint {
+        let result = self.simulate_concentrated_swap(
+            first_token_reserve,
+            second_token_reserve,
+            swap_tokens_order,
+            amount_out,
+            false,
+            total_fee_percent,
+            total_fee_percent,
+        );
+
+        self.add_fee_to_amount_in(&result.amount_in, total_fee_percent)
+    }
+
+    #[inline]
+    fn add_fee_to_amount_in(&self, amount_in_no_fee: &BigUint, total_fee_percent: u64) -> BigUint {
+        amount_in_no_fee * MAX_PERCENTAGE / (MAX_PERCENTAGE - total_fee_percent) + 1u64
+    }
+
+    /// Computes the result of a swap without writing anything to storage.
+    /// `amount` is the input amount, net of fees, for exact input swaps
+    /// and the output amount for exact output swaps.
+    #[allow(clippy::too_many_arguments)]
+    fn simulate_concentrated_swap(
+        &self,
+        first_token_reserve: &BigUint,
+        second_token_reserve: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+        amount: &BigUint,
+        exact_input: bool,
+        total_fee_percent: u64,
+        lp_fee_percent: u64,
+    ) -> ConcentratedSwapResult<Self::Api> {
+        let price_goes_down = swap_tokens_order == SwapTokensOrder::PoolOrder;
+        let (mut reserve_in, mut reserve_out) = if price_goes_down {
+            (first_token_reserve.clone(), second_token_reserve.clone())
+        } else {
+            (second_token_reserve.clone(), first_token_reserve.clone())
+        };
+
+        let mut sqrt_price = self.concentrated_sqrt_price().get();
+        let mut tick = self.current_tick().get();
+        let mut ranged_liquidity = self.active_concentrated_liquidity().get();
+        let mut fee_growth_global_in = if price_goes_down {
+            self.fee_growth_global_first().get()
+        } else {
+            self.fee_growth_global_second().get()
+        };
+
+        let mut result = ConcentratedSwapResult {
+            amount_in: BigUint::zero(),
+            amount_out: BigUint::zero(),
+            full_range_reserve_in: BigUint::zero(),
+            full_range_reserve_out: BigUint::zero(),
+            ranged_amount_in: BigUint::zero(),
+            ranged_amount_out: BigUint::zero(),
+            ranged_fee: BigUint::zero(),
+            fee_growth_global_in: BigUint::zero(),
+            sqrt_price: BigUint::zero(),
+            tick,
+            active_liquidity: BigUint::zero(),
+            crossed_ticks: ManagedVec::new(),
+        };
+
+        let mut amount_remaining = amount.clone();
+        while amount_remaining > 0u64 {
+            let (next_tick, initialized) =
+                self.next_initialized_tick_within_one_word(tick, price_goes_down);
+            let sqrt_price_target = self.get_sqrt_price_at_tick(next_tick);
+            let full_range_liquidity = (&reserve_in * &reserve_out).sqrt();
+
+            let step = self.compute_swap_step(
+                &sqrt_price,
+                &sqrt_price_target,
+                &full_range_liquidity,
+                &ranged_liquidity,
+                &reserve_in,
+                &reserve_out,
+                &amount_remaining,
+                exact_input,
+                price_goes_down,
+            );
+            let step_amount_in = &step.full_range_in + &step.ranged_in;
+            let step_amount_out = &step.full_range_out + &step.ranged_out;
+
+            if ranged_liquidity > 0u64 && lp_fee_percent > 0 {
+                let total_liquidity = &full_range_liquidity + &ranged_liquidity;
+                let step_lp_fee =
+                    &step_amount_in * lp_fee_percent / (MAX_PERCENTAGE - total_fee_percent);
+                let step_ranged_fee = step_lp_fee * &ranged_liquidity / total_liquidity;
+
+                fee_growth_global_in += &step_ranged_fee * FEE_GROWTH_PRECISION / &ranged_liquidity;
+                result.ranged_fee += step_ranged_fee;
+            }
+
+            if exact_input {
+                amount_remaining = if amount_remaining > step_amount_in {
+                    amount_remaining - &step_amount_in
+                } else {
+                    BigUint::zero()
+                };
+            } else {
+                amount_remaining = if amount_remaining > step_amount_out {
+                    amount_remaining - &step_amount_out
+                } else {
+                    BigUint::zero()
+                };
+            }
+
+            reserve_in += &step.full_range_in;
+            reserve_out -= &step.full_range_out;
+            result.amount_in += step_amount_in;
+            result.amount_out += step_amount_out;
+            result.ranged_amount_in += step.ranged_in;
+            result.ranged_amount_out += step.ranged_out;
+
+            if step.target_reached {
+                if initialized {
+                    let liquidity_net = self.get_tick_liquidity_net(next_tick);
+                    let liquidity_delta = if price_goes_down {
+                        -liquidity_net
+                    } else {
+                        liquidity_net
+                    };
+                    ranged_liquidity =
+                        self.add_liquidity_delta(&ranged_liquidity, &liquidity_delta);
+
+                    result.crossed_ticks.push(CrossedTick {
+                        tick: next_tick,
+                        fee_growth_global_in: fee_growth_global_in.clone(),
+                    });
+                }
+
+                let limit_tick = if price_goes_down { MIN_TICK } else { MAX_TICK };
+                require!(
+                    next_tick != limit_tick || amount_remaining == 0u64,
+                    ERROR_PRICE_LIMIT_REACHED
+                );
+
+                tick = if price_goes_down {
+                    next_tick - 1
+                } else {
+                    next_tick
+                };
+            } else if price_goes_down {
+                tick = self.get_tick_at_sqrt_price_between(&step.sqrt_price_next, next_tick, tick);
+            } else {
+                tick =
+                    self.get_tick_at_sqrt_price_between(&step.sqrt_price_next, tick, next_tick - 1);
+            }
+
+            sqrt_price = step.sqrt_price_next;
+        }
+
+        result.full_range_reserve_in = reserve_in;
+        result.full_range_reserve_out = reserve_out;
+        result.fee_growth_global_in = fee_growth_global_in;
+        result.sqrt_price = sqrt_price;
+        result.tick = tick;
+        result.active_liquidity = ranged_liquidity;
+
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compute_swap_step(
+        &self,
+        sqrt_price: &BigUint,
+        sqrt_price_target: &BigUint,
+        full_range_liquidity: &BigUint,
+        ranged_liquidity: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+        amount_remaining: &BigUint,
+        exact_input: bool,
+        price_goes_down: bool,
+    ) -> SwapStep<Self::Api> {
+        let already_reached = if price_goes_down {
+            sqrt_price <= sqrt_price_target
+        } else {
+            sqrt_price >= sqrt_price_target
+        };
+        if already_reached {
+            return SwapStep {
+                sqrt_price_next: sqrt_price_target.clone(),
+                target_reached: true,
+                full_range_in: BigUint::zero(),
+                full_range_out: BigUint::zero(),
+                ranged_in: BigUint::zero(),
+                ranged_out: BigUint::zero(),
+            };
+        }
+
+        let total_liquidity = full_range_liquidity + ranged_liquidity;
+        let (lower_sqrt_price, upper_sqrt_price) = if price_goes_down {
+            (sqrt_price_target, sqrt_price)
+        } else {
+            (sqrt_price, sqrt_price_target)
+        };
+
+        let (sqrt_price_next, target_reached, step_amount) = if exact_input {
+            let max_amount_in = if price_goes_down {
+                self.get_first_token_amount_for_liquidity(
+                    &total_liquidity,
+                    lower_sqrt_price,
+                    upper_sqrt_price,
+                    true,
+                )
+            } else {
+                self.get_second_token_amount_for_liquidity(
+                    &total_liquidity,
+                    lower_sqrt_price,
+                    upper_sqrt_price,
+                    true,
+                )
+            };
+
+            if amount_remaining >= &max_amount_in {
+                (sqrt_price_target.clone(), true, max_amount_in)
+            } else if price_goes_down {
+                let sqrt_price_next = self.get_next_sqrt_price_from_first_token_input(
+                    sqrt_price,
+                    &total_liquidity,
+                    amount_remaining,
+                );
+                (sqrt_price_next, false, amount_remaining.clone())
+            } else {
+                let sqrt_price_next = self.get_next_sqrt_price_from_second_token_input(
+                    sqrt_price,
+                    &total_liquidity,
+                    amount_remaining,
+                );
+                (sqrt_price_next, false, amount_remaining.clone())
+            }
+        } else {
+            let max_amount_out = if price_goes_down {
+                self.get_second_token_amount_for_liquidity(
+                    &total_liquidity,
+                    lower_sqrt_price,
+                    upper_sqrt_price,
+                    false,
+                )
+            } else {
+                self.get_first_token_amount_for_liquidity(
+                    &total_liquidity,
+                    lower_sqrt_price,
+                    upper_sqrt_price,
+                    false,
+                )
+            };
+
+            if amount_remaining >= &max_amount_out {
+                (sqrt_price_target.clone(), true, max_amount_out)
+            } else if price_goes_down {
+                let sqrt_price_next = self.get_next_sqrt_price_from_second_token_output(
+                    sqrt_price,
+                    &total_liquidity,
+                    amount_remaining,
+                );
+                (sqrt_price_next, false, amount_remaining.clone())
+            } else {
+                let sqrt_price_next = self.get_next_sqrt_price_from_first_token_output(
+                    sqrt_price,
+                    &total_liquidity,
+                    amount_remaining,
+                );
+                (sqrt_price_next, false, amount_remaining.clone())
+            }
+        };
+
+        let (lower_sqrt_price_next, upper_sqrt_price_next) = if price_goes_down {
+            (&sqrt_price_next, sqrt_price)
+        } else {
+            (sqrt_price, &sqrt_price_next)
+        };
+        let ranged_amount = &step_amount * ranged_liquidity / &total_liquidity;
+        let full_range_amount = &step_amount - &ranged_amount;
+
+        let (full_range_in, full_range_out, ranged_in, ranged_out) = if exact_input {
+            let ranged_out = if price_goes_down {
+                self.get_second_token_amount_for_liquidity(
+                    ranged_liquidity,
+                    lower_sqrt_price_next,
+                    upper_sqrt_price_next,
+                    false,
+                )
+            } else {
+                self.get_first_token_amount_for_liquidity(
+                    ranged_liquidity,
+                    lower_sqrt_price_next,
+                    upper_sqrt_price_next,
+                    false,
+                )
+            };
+            let full_range_out =
+                &full_range_amount * reserve_out / (reserve_in + &full_range_amount);
+
+            (full_range_amount, full_range_out, ranged_amount, ranged_out)
+        } else {
+            let ranged_in = if price_goes_down {
+                self.get_first_token_amount_for_liquidity(
+                    ranged_liquidity,
+                    lower_sqrt_price_next,
+                    upper_sqrt_price_next,
+                    true,
+                )
+            } else {
+                self.get_second_token_amount_for_liquidity(
+                    ranged_liquidity,
+                    lower_sqrt_price_next,
+                    upper_sqrt_price_next,
+                    true,
+                )
+            };
+            let full_range_in = if full_range_amount > 0u64 {
+                require!(reserve_out > &full_range_amount, ERROR_NOT_ENOUGH_RESERVE);
+                reserve_in * &full_range_amount / (reserve_out - &full_range_amount) + 1u64
+            } else {
+                BigUint::zero()
+            };
+
+            (full_range_in, full_range_amount, ranged_in, ranged_amount)
+        };
+
+        SwapStep {
+            sqrt_price_next,
+            target_reached,
+            full_range_in,
+            full_range_out,
+            ranged_in,
+            ranged_out,
+        }
+    }
+
+    /// The full range reserves and the square root price `apply_concentrated_swap`
+    /// would leave after the given swap, in pool order
+    fn get_concentrated_swap_final_state(
+        &self,
+        swap_tokens_order: SwapTokensOrder,
+        result: &ConcentratedSwapResult<Self::Api>,
+        lp_fee_amount: &BigUint,
+    ) -> (BigUint, BigUint, BigUint) {
+        let ranged_fee = core::cmp::min(&result.ranged_fee, lp_fee_amount);
+        let reserve_in = &result.full_range_reserve_in + lp_fee_amount - ranged_fee;
+        let reserve_out = result.full_range_reserve_out.clone();
+        let (mut first_token_reserve, mut second_token_reserve) = match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => (reserve_in, reserve_out),
+            SwapTokensOrder::ReverseOrder => (reserve_out, reserve_in),
+        };
+
+        let rebase = self.compute_price_rebase(
+            &first_token_reserve,
+            &second_token_reserve,
+            &result.sqrt_price,
+            result.tick,
+            &result.active_liquidity,
+        );
+        if rebase.price_goes_down {
+            first_token_reserve -= &rebase.ranged_amount_in;
+            second_token_reserve += &rebase.ranged_amount_out;
+        } else {
+            second_token_reserve -= &rebase.ranged_amount_in;
+            first_token_reserve += &rebase.ranged_amount_out;
+        }
+
+        (first_token_reserve, second_token_reserve, rebase.sqrt_price)
+    }
+
+    /// Commits a simulated swap. `lp_fee_amount` is the part of the input that
+    /// stays in the pool as liquidity providers fee, shared between the full range
+    /// and the ranged positions.
+    fn apply_concentrated_swap(
+        &self,
+        storage_cache: &mut StorageCache<Self>,
+        swap_tokens_order: SwapTokensOrder,
+        result: ConcentratedSwapResult<Self::Api>,
+        lp_fee_amount: &BigUint,
+    ) {
+        let ranged_fee = core::cmp::min(&result.ranged_fee, lp_fee_amount).clone();
+        let full_range_fee = lp_fee_amount - &ranged_fee;
+
+        *storage_cache.get_mut_reserve_in(swap_tokens_order) =
+            result.full_range_reserve_in + full_range_fee;
+        *storage_cache.get_mut_reserve_out(swap_tokens_order) = result.full_range_reserve_out;
+
+        let (token_in, token_out) = match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => (
+                &storage_cache.first_token_id,
+                &storage_cache.second_token_id,
+            ),
+            SwapTokensOrder::ReverseOrder => (
+                &storage_cache.second_token_id,
+                &storage_cache.first_token_id,
+            ),
+        };
+        self.concentrated_reserve(token_in)
+            .update(|reserve| *reserve += result.ranged_amount_in + ranged_fee);
+        self.concentrated_reserve(token_out).update(|reserve| {
+            require!(
+                *reserve >= result.ranged_amount_out,
+                ERROR_NOT_ENOUGH_RESERVE
+            );
+            *reserve -= result.ranged_amount_out;
+        });
+
+        let price_goes_down = swap_tokens_order == SwapTokensOrder::PoolOrder;
+        let fee_growth_global_out = if price_goes_down {
+            self.fee_growth_global_second().get()
+        } else {
+            self.fee_growth_global_first().get()
+        };
+        for crossed_tick in result.crossed_ticks.iter() {
+            if price_goes_down {
+                self.cross_tick(
+                    crossed_tick.tick,
+                    &crossed_tick.fee_growth_global_in,
+                    &fee_growth_global_out,
+                );
+            } else {
+                self.cross_tick(
+                    crossed_tick.tick,
+                    &fee_growth_global_out,
+                    &crossed_tick.fee_growth_global_in,
+                );
+            }
+        }
+
+        if price_goes_down {
+            self.fee_growth_global_first()
+                .set(result.fee_growth_global_in);
+        } else {
+            self.fee_growth_global_second()
+                .set(result.fee_growth_global_in);
+        }
+
+        let rebase = self.compute_price_rebase(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &result.sqrt_price,
+            result.tick,
+            &result.active_liquidity,
+        );
+        self.apply_price_rebase(storage_cache, rebase);
+        self.active_concentrated_liquidity()
+            .set(result.active_liquidity);
+    }
+
+    /// The ranged positions and the full range both move to the square root price
+    /// sqrt_price* = (ranged_liquidity * ranged_sqrt_price + full_range_liquidity * full_range_sqrt_price)
+    ///     / (ranged_liquidity + full_range_liquidity),
+    /// where the tokens needed by the ranged positions are paid by the full range reserves.
+    /// The new price never crosses an initialized tick, as that would change the active liquidity.
+    fn compute_price_rebase(
+        &self,
+        first_token_reserve: &BigUint,
+        second_token_reserve: &BigUint,
+        sqrt_price: &BigUint,
+        tick: i32,
+        ranged_liquidity: &BigUint,
+    ) -> PriceRebase<Self::Api> {
+        let mut rebase = PriceRebase {
+            sqrt_price: sqrt_price.clone(),
+            tick,
+            price_goes_down: false,
+            ranged_amount_in: BigUint::zero(),
+            ranged_amount_out: BigUint::zero(),
+        };
+        if first_token_reserve == &0u64 || second_token_reserve == &0u64 {
+            return rebase;
+        }
+
+        let full_range_sqrt_price =
+            self.get_sqrt_price_from_reserves(first_token_reserve, second_token_reserve);
+        let full_range_liquidity = (first_token_reserve * second_token_reserve).sqrt();
+        let target_sqrt_price = (ranged_liquidity * sqrt_price
+            + &full_range_liquidity * &full_range_sqrt_price)
+            / (ranged_liquidity + &full_range_liquidity);
+
+        let price_goes_down = &target_sqrt_price < sqrt_price;
+        let (bound_tick, _) = self.next_initialized_tick_within_one_word(tick, price_goes_down);
+        let bound_sqrt_price = self.get_sqrt_price_at_tick(bound_tick);
+        let new_sqrt_price = if price_goes_down {
+            core::cmp::max(target_sqrt_price, bound_sqrt_price)
+        } else {
+            let upper_limit = bound_sqrt_price - 1u64;
+            if &upper_limit <= sqrt_price {
+                return rebase;
+            }
+
+            core::cmp::min(target_sqrt_price, upper_limit)
+        };
+        if &new_sqrt_price == sqrt_price {
+            return rebase;
+        }
+
+        if price_goes_down {
+            rebase.tick = self.get_tick_at_sqrt_price_between(&new_sqrt_price, bound_tick, tick);
+            rebase.ranged_amount_in = self.get_first_token_amount_for_liquidity(
+                ranged_liquidity,
+                &new_sqrt_price,
+                sqrt_price,
+                true,
+            );
+            rebase.ranged_amount_out = self.get_second_token_amount_for_liquidity(
+                ranged_liquidity,
+                &new_sqrt_price,
+                sqrt_price,
+                false,
+            );
+        } else {
+            rebase.tick =
+                self.get_tick_at_sqrt_price_between(&new_sqrt_price, tick, bound_tick - 1);
+            rebase.ranged_amount_in = self.get_second_token_amount_for_liquidity(
+                ranged_liquidity,
+                sqrt_price,
+                &new_sqrt_price,
+                true,
+            );
+            rebase.ranged_amount_out = self.get_first_token_amount_for_liquidity(
+                ranged_liquidity,
+                sqrt_price,
+                &new_sqrt_price,
+                false,
+            );
+        }
+        rebase.price_goes_down = price_goes_down;
+        rebase.sqrt_price = new_sqrt_price;
+
+        rebase
+    }
+
+    fn apply_price_rebase(
+        &self,
+        storage_cache: &mut StorageCache<Self>,
+        rebase: PriceRebase<Self::Api>,
+    ) {
+        let (token_in, token_out) = if rebase.price_goes_down {
+            (
+                storage_cache.first_token_id.clone(),
+                storage_cache.second_token_id.clone(),
+            )
+        } else {
+            (
+                storage_cache.second_token_id.clone(),
+                storage_cache.first_token_id.clone(),
+            )
+        };
+        let swap_tokens_order = if rebase.price_goes_down {
+            SwapTokensOrder::PoolOrder
+        } else {
+            SwapTokensOrder::ReverseOrder
+        };
+
+        let full_range_reserve_in = storage_cache.get_mut_reserve_in(swap_tokens_order);
+        require!(
+            *full_range_reserve_in > rebase.ranged_amount_in,
+            ERROR_NOT_ENOUGH_RESERVE
+        );
+        *full_range_reserve_in -= &rebase.ranged_amount_in;
+        *storage_cache.get_mut_reserve_out(swap_tokens_order) += &rebase.ranged_amount_out;
+
+        self.concentrated_reserve(&token_in)
+            .update(|reserve| *reserve += rebase.ranged_amount_in);
+        self.concentrated_reserve(&token_out).update(|reserve| {
+            require!(
+                *reserve >= rebase.ranged_amount_out,
+                ERROR_NOT_ENOUGH_RESERVE
+            );
+            *reserve -= rebase.ranged_amount_out;
+        });
+
+        self.concentrated_sqrt_price().set(rebase.sqrt_price);
+        self.current_tick().set(rebase.tick);
+    }
+}
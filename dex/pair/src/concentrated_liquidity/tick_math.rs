@@ -0,0 +1,304 @@
+multiversx_sc::imports!();
+
+use crate::errors::{ERROR_INVALID_TICK, ERROR_NOT_ENOUGH_LIQUIDITY_IN_RANGE};
+
+pub const MIN_TICK: i32 = -400_000;
+pub const MAX_TICK: i32 = 400_000;
+pub const SQRT_PRICE_PRECISION: u64 = 1_000_000_000_000_000_000;
+
+// sqrt(1.0001) * SQRT_PRICE_PRECISION
+const SQRT_TICK_BASE: u64 = 1_000_049_998_750_062_496;
+const TICK_SEARCH_MAX_ITERATIONS: usize = 64;
+
+/// Prices are expressed as second token per first token.
+/// Square root prices are scaled by `SQRT_PRICE_PRECISION`.
+#[multiversx_sc::module]
+pub trait TickMathModule {
+    /// sqrt(1.0001 ^ tick), scaled by `SQRT_PRICE_PRECISION`
+    fn get_sqrt_price_at_tick(&self, tick: i32) -> BigUint {
+        require!((MIN_TICK..=MAX_TICK).contains(&tick), ERROR_INVALID_TICK);
+
+        let precision = BigUint::from(SQRT_PRICE_PRECISION);
+        let mut result = precision.clone();
+        let mut base = BigUint::from(SQRT_TICK_BASE);
+        let mut exponent = tick.unsigned_abs();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base / &precision;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base / &precision;
+            }
+        }
+
+        if tick < 0 {
+            &precision * &precision / result
+        } else {
+            result
+        }
+    }
+
+    /// The greatest tick in `[lower_tick, upper_tick]` whose square root price
+    /// is lower than or equal to the given one
+    fn get_tick_at_sqrt_price_between(
+        &self,
+        sqrt_price: &BigUint,
+        lower_tick: i32,
+        upper_tick: i32,
+    ) -> i32 {
+        let mut low = lower_tick;
+        let mut high = upper_tick;
+        for _ in 0..TICK_SEARCH_MAX_ITERATIONS {
+            if low >= high {
+                break;
+            }
+
+            let middle = low + (high - low + 1) / 2;
+            if &self.get_sqrt_price_at_tick(middle) <= sqrt_price {
+                low = middle;
+            } else {
+                high = middle - 1;
+            }
+        }
+
+        low
+    }
+
+    fn get_tick_at_sqrt_price(&self, sqrt_price: &BigUint) -> i32 {
+        self.get_tick_at_sqrt_price_between(sqrt_price, MIN_TICK, MAX_TICK)
+    }
+
+    /// sqrt(second_reserve / first_reserve), scaled by `SQRT_PRICE_PRECISION`
+    fn get_sqrt_price_from_reserves(
+        &self,
+        first_token_reserve: &BigUint,
+        second_token_reserve: &BigUint,
+    ) -> BigUint {
+        let precision = BigUint::from(SQRT_PRICE_PRECISION);
+        (second_token_reserve * &precision * precision / first_token_reserve).sqrt()
+    }
+
+    /// The second token amount worth `first_token_amount` at the given price
+    fn get_second_token_quote(
+        &self,
+        first_token_amount: &BigUint,
+        sqrt_price: &BigUint,
+    ) -> BigUint {
+        let precision = BigUint::from(SQRT_PRICE_PRECISION);
+        first_token_amount * sqrt_price * sqrt_price / (&precision * &precision)
+    }
+
+    /// The first token amount worth `second_token_amount` at the given price
+    fn get_first_token_quote(
+        &self,
+        second_token_amount: &BigUint,
+        sqrt_price: &BigUint,
+    ) -> BigUint {
+        let precision = BigUint::from(SQRT_PRICE_PRECISION);
+        second_token_amount * &precision * precision / (sqrt_price * sqrt_price)
+    }
+
+    /// L * (sqrt_b - sqrt_a) / (sqrt_a * sqrt_b)
+    fn get_first_token_amount_for_liquidity(
+        &self,
+        liquidity: &BigUint,
+        sqrt_price_a: &BigUint,
+        sqrt_price_b: &BigUint,
+        round_up: bool,
+    ) -> BigUint {
+        let numerator = liquidity * &(sqrt_price_b - sqrt_price_a) * SQRT_PRICE_PRECISION;
+        let denominator = sqrt_price_a * sqrt_price_b;
+
+        self.div_rounding(numerator, denominator, round_up)
+    }
+
+    /// L * (sqrt_b - sqrt_a)
+    fn get_second_token_amount_for_liquidity(
+        &self,
+        liquidity: &BigUint,
+        sqrt_price_a: &BigUint,
+        sqrt_price_b: &BigUint,
+        round_up: bool,
+    ) -> BigUint {
+        let numerator = liquidity * &(sqrt_price_b - sqrt_price_a);
+
+        self.div_rounding(numerator, BigUint::from(SQRT_PRICE_PRECISION), round_up)
+    }
+
+    fn get_liquidity_for_first_token_amount(
+        &self,
+        amount: &BigUint,
+        sqrt_price_a: &BigUint,
+        sqrt_price_b: &BigUint,
+    ) -> BigUint {
+        amount * sqrt_price_a * sqrt_price_b
+            / ((sqrt_price_b - sqrt_price_a) * SQRT_PRICE_PRECISION)
+    }
+
+    fn get_liquidity_for_second_token_amount(
+        &self,
+        amount: &BigUint,
+        sqrt_price_a: &BigUint,
+        sqrt_price_b: &BigUint,
+    ) -> BigUint {
+        amount * SQRT_PRICE_PRECISION / (sqrt_price_b - sqrt_price_a)
+    }
+
+    /// The liquidity that can be minted with the given amounts, for a position
+    /// between `sqrt_price_a` and `sqrt_price_b`, at the `sqrt_price` current price
+    fn get_liquidity_for_amounts(
+        &self,
+        sqrt_price: &BigUint,
+        sqrt_price_a: &BigUint,
+        sqrt_price_b: &BigUint,
+        first_token_amount: &BigUint,
+        second_token_amount: &BigUint,
+    ) -> BigUint {
+        if sqrt_price <= sqrt_price_a {
+            self.get_liquidity_for_first_token_amount(
+                first_token_amount,
+                sqrt_price_a,
+                sqrt_price_b,
+            )
+        } else if sqrt_price < sqrt_price_b {
+            let first_liquidity = self.get_liquidity_for_first_token_amount(
+                first_token_amount,
+                sqrt_price,
+                sqrt_price_b,
+            );
+            let second_liquidity = self.get_liquidity_for_second_token_amount(
+                second_token_amount,
+                sqrt_price_a,
+                sqrt_price,
+            );
+
+            core::cmp::min(first_liquidity, second_liquidity)
+        } else {
+            self.get_liquidity_for_second_token_amount(
+                second_token_amount,
+                sqrt_price_a,
+                sqrt_price_b,
+            )
+        }
+    }
+
+    /// The token amounts backing the given liquidity, for a position
+    /// between `sqrt_price_a` and `sqrt_price_b`, at the `sqrt_price` current price
+    fn get_amounts_for_liquidity(
+        &self,
+        sqrt_price: &BigUint,
+        sqrt_price_a: &BigUint,
+        sqrt_price_b: &BigUint,
+        liquidity: &BigUint,
+        round_up: bool,
+    ) -> (BigUint, BigUint) {
+        if sqrt_price <= sqrt_price_a {
+            let first_amount = self.get_first_token_amount_for_liquidity(
+                liquidity,
+                sqrt_price_a,
+                sqrt_price_b,
+                round_up,
+            );
+
+            (first_amount, BigUint::zero())
+        } else if sqrt_price < sqrt_price_b {
+            let first_amount = self.get_first_token_amount_for_liquidity(
+                liquidity,
+                sqrt_price,
+                sqrt_price_b,
+                round_up,
+            );
+            let second_amount = self.get_second_token_amount_for_liquidity(
+                liquidity,
+                sqrt_price_a,
+                sqrt_price,
+                round_up,
+            );
+
+            (first_amount, second_amount)
+        } else {
+            let second_amount = self.get_second_token_amount_for_liquidity(
+                liquidity,
+                sqrt_price_a,
+                sqrt_price_b,
+                round_up,
+            );
+
+            (BigUint::zero(), second_amount)
+        }
+    }
+
+    /// The square root price reached after adding `amount_in` of the first token,
+    /// which moves the price down: L * sqrt_p / (L + amount_in * sqrt_p)
+    fn get_next_sqrt_price_from_first_token_input(
+        &self,
+        sqrt_price: &BigUint,
+        liquidity: &BigUint,
+        amount_in: &BigUint,
+    ) -> BigUint {
+        let scaled_liquidity = liquidity * SQRT_PRICE_PRECISION;
+        let numerator = &scaled_liquidity * sqrt_price;
+        let denominator = scaled_liquidity + amount_in * sqrt_price;
+
+        self.div_rounding(numerator, denominator, true)
+    }
+
+    /// The square root price reached after adding `amount_in` of the second token,
+    /// which moves the price up: sqrt_p + amount_in / L
+    fn get_next_sqrt_price_from_second_token_input(
+        &self,
+        sqrt_price: &BigUint,
+        liquidity: &BigUint,
+        amount_in: &BigUint,
+    ) -> BigUint {
+        sqrt_price + &(amount_in * SQRT_PRICE_PRECISION / liquidity)
+    }
+
+    /// The square root price reached after removing `amount_out` of the first token,
+    /// which moves the price up: L * sqrt_p / (L - amount_out * sqrt_p)
+    fn get_next_sqrt_price_from_first_token_output(
+        &self,
+        sqrt_price: &BigUint,
+        liquidity: &BigUint,
+        amount_out: &BigUint,
+    ) -> BigUint {
+        let scaled_liquidity = liquidity * SQRT_PRICE_PRECISION;
+        let amount_product = amount_out * sqrt_price;
+        require!(
+            scaled_liquidity > amount_product,
+            ERROR_NOT_ENOUGH_LIQUIDITY_IN_RANGE
+        );
+
+        let numerator = &scaled_liquidity * sqrt_price;
+        let denominator = scaled_liquidity - amount_product;
+
+        self.div_rounding(numerator, denominator, true)
+    }
+
+    /// The square root price reached after removing `amount_out` of the second token,
+    /// which moves the price down: sqrt_p - amount_out / L
+    fn get_next_sqrt_price_from_second_token_output(
+        &self,
+        sqrt_price: &BigUint,
+        liquidity: &BigUint,
+        amount_out: &BigUint,
+    ) -> BigUint {
+        let price_delta =
+            self.div_rounding(amount_out * SQRT_PRICE_PRECISION, liquidity.clone(), true);
+        require!(
+            sqrt_price > &price_delta,
+            ERROR_NOT_ENOUGH_LIQUIDITY_IN_RANGE
+        );
+
+        sqrt_price - &price_delta
+    }
+
+    fn div_rounding(&self, numerator: BigUint, denominator: BigUint, round_up: bool) -> BigUint {
+        if round_up {
+            (numerator + &denominator - 1u64) / denominator
+        } else {
+            numerator / denominator
+        }
+    }
+}
@@ -0,0 +1,261 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use super::tick_math::{MAX_TICK, MIN_TICK};
+use crate::errors::{
+    ERROR_INVALID_TICK, ERROR_INVALID_TICK_RANGE, ERROR_NOT_ENOUGH_LIQUIDITY_IN_RANGE,
+    ERROR_TICK_NOT_INITIALIZED,
+};
+
+pub const FEE_GROWTH_PRECISION: u64 = 1_000_000_000_000_000_000;
+const TICK_BITMAP_WORD_SIZE: i32 = 64;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct TickInfo<M: ManagedTypeApi> {
+    pub liquidity_gross: BigUint<M>,
+    pub liquidity_net: BigInt<M>,
+    pub fee_growth_outside_first: BigUint<M>,
+    pub fee_growth_outside_second: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> Default for TickInfo<M> {
+    fn default() -> Self {
+        TickInfo {
+            liquidity_gross: BigUint::zero(),
+            liquidity_net: BigInt::zero(),
+            fee_growth_outside_first: BigUint::zero(),
+            fee_growth_outside_second: BigUint::zero(),
+        }
+    }
+}
+
+#[multiversx_sc::module]
+pub trait TicksModule {
+    fn require_valid_tick_range(&self, tick_lower: i32, tick_upper: i32) {
+        let tick_spacing = self.tick_spacing().get();
+        require!(tick_spacing > 0, ERROR_INVALID_TICK);
+        require!(
+            tick_lower % tick_spacing == 0 && tick_upper % tick_spacing == 0,
+            ERROR_INVALID_TICK
+        );
+        require!(
+            MIN_TICK <= tick_lower && tick_lower < tick_upper && tick_upper <= MAX_TICK,
+            ERROR_INVALID_TICK_RANGE
+        );
+    }
+
+    /// Adds (or removes, for a negative delta) liquidity referencing the given tick.
+    /// Ticks become initialized when first referenced and are cleared when no
+    /// position references them anymore.
+    fn update_tick(&self, tick: i32, liquidity_delta: &BigInt, is_upper_tick: bool) {
+        let current_tick = self.current_tick().get();
+        let tick_mapper = self.tick_info(tick);
+        let mut tick_info = if tick_mapper.is_empty() {
+            let mut new_tick_info = TickInfo::default();
+            // by convention, all fee growth is assumed to have happened below the tick
+            if tick <= current_tick {
+                new_tick_info.fee_growth_outside_first = self.fee_growth_global_first().get();
+                new_tick_info.fee_growth_outside_second = self.fee_growth_global_second().get();
+            }
+            self.flip_tick(tick);
+
+            new_tick_info
+        } else {
+            tick_mapper.get()
+        };
+
+        tick_info.liquidity_gross =
+            self.add_liquidity_delta(&tick_info.liquidity_gross, liquidity_delta);
+        if is_upper_tick {
+            tick_info.liquidity_net -= liquidity_delta;
+        } else {
+            tick_info.liquidity_net += liquidity_delta;
+        }
+
+        if tick_info.liquidity_gross == 0 {
+            self.flip_tick(tick);
+            tick_mapper.clear();
+        } else {
+            tick_mapper.set(tick_info);
+        }
+    }
+
+    /// Moves the fee growth of the tick to the other side of the price
+    fn cross_tick(
+        &self,
+        tick: i32,
+        fee_growth_global_first: &BigUint,
+        fee_growth_global_second: &BigUint,
+    ) {
+        let tick_mapper = self.tick_info(tick);
+        if tick_mapper.is_empty() {
+            return;
+        }
+
+        let mut tick_info = tick_mapper.get();
+        tick_info.fee_growth_outside_first =
+            fee_growth_global_first - &tick_info.fee_growth_outside_first;
+        tick_info.fee_growth_outside_second =
+            fee_growth_global_second - &tick_info.fee_growth_outside_second;
+        tick_mapper.set(tick_info);
+    }
+
+    fn add_liquidity_delta(&self, liquidity: &BigUint, liquidity_delta: &BigInt) -> BigUint {
+        let liquidity_delta_magnitude = liquidity_delta.magnitude();
+        if liquidity_delta.sign() == Sign::Minus {
+            require!(
+                liquidity >= &liquidity_delta_magnitude,
+                ERROR_NOT_ENOUGH_LIQUIDITY_IN_RANGE
+            );
+            liquidity - &liquidity_delta_magnitude
+        } else {
+            liquidity + &liquidity_delta_magnitude
+        }
+    }
+
+    fn get_tick_liquidity_net(&self, tick: i32) -> BigInt {
+        let tick_mapper = self.tick_info(tick);
+        if tick_mapper.is_empty() {
+            BigInt::zero()
+        } else {
+            tick_mapper.get().liquidity_net
+        }
+    }
+
+    /// Returns the fee growth per unit of liquidity accumulated between the two ticks.
+    /// Values are allowed to go below zero, only differences between them are meaningful.
+    /// Both ticks have to be referenced by at least one position.
+    fn get_fee_growth_inside(&self, tick_lower: i32, tick_upper: i32) -> (BigInt, BigInt) {
+        let current_tick = self.current_tick().get();
+        let global_first = BigInt::from_biguint(Sign::Plus, self.fee_growth_global_first().get());
+        let global_second = BigInt::from_biguint(Sign::Plus, self.fee_growth_global_second().get());
+
+        let lower_mapper = self.tick_info(tick_lower);
+        let upper_mapper = self.tick_info(tick_upper);
+        require!(
+            !lower_mapper.is_empty() && !upper_mapper.is_empty(),
+            ERROR_TICK_NOT_INITIALIZED
+        );
+
+        let lower_info = lower_mapper.get();
+        let upper_info = upper_mapper.get();
+        let lower_first = BigInt::from_biguint(Sign::Plus, lower_info.fee_growth_outside_first);
+        let lower_second = BigInt::from_biguint(Sign::Plus, lower_info.fee_growth_outside_second);
+        let upper_first = BigInt::from_biguint(Sign::Plus, upper_info.fee_growth_outside_first);
+        let upper_second = BigInt::from_biguint(Sign::Plus, upper_info.fee_growth_outside_second);
+
+        let (below_first, below_second) = if current_tick >= tick_lower {
+            (lower_first, lower_second)
+        } else {
+            (
+                global_first.clone() - lower_first,
+                global_second.clone() - lower_second,
+            )
+        };
+        let (above_first, above_second) = if current_tick < tick_upper {
+            (upper_first, upper_second)
+        } else {
+            (
+                global_first.clone() - upper_first,
+                global_second.clone() - upper_second,
+            )
+        };
+
+        (
+            global_first - below_first - above_first,
+            global_second - below_second - above_second,
+        )
+    }
+
+    /// Returns the next initialized tick contained in the same bitmap word as the given tick,
+    /// or the word boundary if there is none, together with the initialized flag.
+    /// Searches at or below the tick when `lte` is set, strictly above it otherwise.
+    fn next_initialized_tick_within_one_word(&self, tick: i32, lte: bool) -> (i32, bool) {
+        let tick_spacing = self.tick_spacing().get();
+        let compressed = tick.div_euclid(tick_spacing);
+
+        let (next_compressed, initialized) = if lte {
+            let (word, bit) = self.get_tick_bitmap_position(compressed);
+            let mask = if bit == 63 {
+                u64::MAX
+            } else {
+                (1u64 << (bit + 1)) - 1
+            };
+            let masked = self.tick_bitmap(word).get() & mask;
+            if masked != 0 {
+                let most_significant_bit = 63 - masked.leading_zeros();
+                (compressed - (bit - most_significant_bit) as i32, true)
+            } else {
+                (compressed - bit as i32, false)
+            }
+        } else {
+            let (word, bit) = self.get_tick_bitmap_position(compressed + 1);
+            let mask = !((1u64 << bit) - 1);
+            let masked = self.tick_bitmap(word).get() & mask;
+            if masked != 0 {
+                let least_significant_bit = masked.trailing_zeros();
+                (compressed + 1 + (least_significant_bit - bit) as i32, true)
+            } else {
+                (compressed + 1 + (63 - bit) as i32, false)
+            }
+        };
+
+        let next_tick = (next_compressed * tick_spacing).clamp(MIN_TICK, MAX_TICK);
+
+        (next_tick, initialized)
+    }
+
+    fn flip_tick(&self, tick: i32) {
+        let tick_spacing = self.tick_spacing().get();
+        let (word, bit) = self.get_tick_bitmap_position(tick.div_euclid(tick_spacing));
+        self.tick_bitmap(word)
+            .update(|bitmap| *bitmap ^= 1u64 << bit);
+    }
+
+    #[inline]
+    fn get_tick_bitmap_position(&self, compressed_tick: i32) -> (i32, u32) {
+        (
+            compressed_tick.div_euclid(TICK_BITMAP_WORD_SIZE),
+            compressed_tick.rem_euclid(TICK_BITMAP_WORD_SIZE) as u32,
+        )
+    }
+
+    #[view(getConcentratedPositionsCount)]
+    #[storage_mapper("concentratedPositionsCount")]
+    fn concentrated_positions_count(&self) -> SingleValueMapper<u64>;
+
+    #[view(getTickSpacing)]
+    #[storage_mapper("tickSpacing")]
+    fn tick_spacing(&self) -> SingleValueMapper<i32>;
+
+    #[view(getConcentratedSqrtPrice)]
+    #[storage_mapper("concentratedSqrtPrice")]
+    fn concentrated_sqrt_price(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getCurrentTick)]
+    #[storage_mapper("currentTick")]
+    fn current_tick(&self) -> SingleValueMapper<i32>;
+
+    #[view(getActiveConcentratedLiquidity)]
+    #[storage_mapper("activeConcentratedLiquidity")]
+    fn active_concentrated_liquidity(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getTickInfo)]
+    #[storage_mapper("tickInfo")]
+    fn tick_info(&self, tick: i32) -> SingleValueMapper<TickInfo<Self::Api>>;
+
+    #[storage_mapper("tickBitmap")]
+    fn tick_bitmap(&self, word: i32) -> SingleValueMapper<u64>;
+
+    #[view(getFeeGrowthGlobalFirst)]
+    #[storage_mapper("feeGrowthGlobalFirst")]
+    fn fee_growth_global_first(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getFeeGrowthGlobalSecond)]
+    #[storage_mapper("feeGrowthGlobalSecond")]
+    fn fee_growth_global_second(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getConcentratedReserve)]
+    #[storage_mapper("concentratedReserve")]
+    fn concentrated_reserve(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+}
@@ -7,6 +7,40 @@ use super::errors::*;
 
 pub const MAX_PERCENTAGE: u64 = 100_000;
 pub const MAX_FEE_PERCENTAGE: u64 = 5_000;
+pub const MIN_AMPLIFICATION_COEFFICIENT: u64 = 1;
+pub const MAX_AMPLIFICATION_COEFFICIENT: u64 = 1_000_000;
+pub const TOTAL_CURVE_WEIGHT: u64 = 100;
+pub const MIN_CURVE_WEIGHT: u64 = 20;
+pub const MAX_CURVE_WEIGHT: u64 = 80;
+pub const MAX_WEIGHTED_EXPONENT_SUM: u64 = 5;
+
+/// Returns the (first, second) token weights, divided by their greatest common divisor
+pub fn weighted_exponents(first_token_weight: u64) -> (u64, u64) {
+    let second_token_weight = TOTAL_CURVE_WEIGHT - first_token_weight;
+
+    let mut divisor = first_token_weight;
+    let mut remainder = second_token_weight;
+    while remainder != 0 {
+        (divisor, remainder) = (remainder, divisor % remainder);
+    }
+
+    (first_token_weight / divisor, second_token_weight / divisor)
+}
+
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug,
+)]
+pub enum Curve {
+    ConstantProduct,
+    StableSwap {
+        amplification_coefficient: u64,
+    },
+    /// `first_token_weight` out of `TOTAL_CURVE_WEIGHT`,
+    /// the second token gets the remaining weight
+    Weighted {
+        first_token_weight: u64,
+    },
+}
 
 #[multiversx_sc::module]
 pub trait ConfigModule:
@@ -33,6 +67,55 @@ pub trait ConfigModule:
         self.special_fee_percent().set(special_fee_percent);
     }
 
+    /// The curve can only be changed before any liquidity is added,
+    /// as changing it afterwards would instantly reprice the pool.
+    #[endpoint(setCurve)]
+    fn set_curve(&self, curve: Curve) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            self.lp_token_supply().get() == 0,
+            ERROR_CURVE_LIQUIDITY_ADDED
+        );
+
+        match curve {
+            Curve::ConstantProduct => {}
+            Curve::StableSwap {
+                amplification_coefficient,
+            } => {
+                require!(
+                    (MIN_AMPLIFICATION_COEFFICIENT..=MAX_AMPLIFICATION_COEFFICIENT)
+                        .contains(&amplification_coefficient),
+                    ERROR_BAD_AMPLIFICATION_COEFFICIENT
+                );
+            }
+            Curve::Weighted { first_token_weight } => {
+                require!(
+                    (MIN_CURVE_WEIGHT..=MAX_CURVE_WEIGHT).contains(&first_token_weight),
+                    ERROR_BAD_CURVE_WEIGHT
+                );
+
+                // swaps raise the reserves to the weights divided by their greatest common
+                // divisor, and take a root of their sum, so only small ratios are allowed
+                let (first_exponent, second_exponent) = weighted_exponents(first_token_weight);
+                require!(
+                    first_exponent + second_exponent <= MAX_WEIGHTED_EXPONENT_SUM,
+                    ERROR_BAD_CURVE_WEIGHT
+                );
+            }
+        }
+
+        self.curve().set(curve);
+    }
+
+    #[view(getCurve)]
+    fn get_curve(&self) -> Curve {
+        if self.curve().is_empty() {
+            return Curve::ConstantProduct;
+        }
+
+        self.curve().get()
+    }
+
     #[view(getLpTokenIdentifier)]
     fn get_lp_token_identifier(&self) -> TokenIdentifier {
         self.lp_token_identifier().get()
@@ -50,6 +133,10 @@ pub trait ConfigModule:
     #[storage_mapper("router_address")]
     fn router_address(&self) -> SingleValueMapper<ManagedAddress>;
 
+    #[view(getTemplateVersion)]
+    #[storage_mapper("templateVersion")]
+    fn template_version(&self) -> SingleValueMapper<u64>;
+
     #[storage_mapper("lpTokenIdentifier")]
     fn lp_token_identifier(&self) -> SingleValueMapper<TokenIdentifier>;
 
@@ -69,7 +156,14 @@ pub trait ConfigModule:
     #[storage_mapper("initial_liquidity_adder")]
     fn initial_liquidity_adder(&self) -> SingleValueMapper<Option<ManagedAddress>>;
 
+    #[storage_mapper("curve")]
+    fn curve(&self) -> SingleValueMapper<Curve>;
+
     #[view(getReserve)]
     #[storage_mapper("reserve")]
     fn pair_reserve(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    /// Only set while a flash loan is ongoing, holding the amount repaid so far
+    #[storage_mapper("flashLoanRepayment")]
+    fn flash_loan_repayment(&self) -> SingleValueMapper<EsdtTokenPayment>;
 }
@@ -14,6 +14,9 @@ pub struct SwapContext<M: ManagedTypeApi> {
     pub final_input_amount: BigUint<M>,
     pub final_output_amount: BigUint<M>,
     pub fee_amount: BigUint<M>,
+    pub total_fee_percent: u64,
+    pub native_egld_in: bool,
+    pub native_egld_out: bool,
 }
 
 impl<M: ManagedTypeApi> SwapContext<M> {
@@ -33,6 +36,9 @@ impl<M: ManagedTypeApi> SwapContext<M> {
             final_input_amount: BigUint::zero(),
             final_output_amount: BigUint::zero(),
             fee_amount: BigUint::zero(),
+            total_fee_percent: 0,
+            native_egld_in: false,
+            native_egld_out: false,
         }
     }
 }
@@ -0,0 +1,166 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::config::{MAX_FEE_PERCENTAGE, MAX_PERCENTAGE};
+use crate::errors::{
+    ERROR_BAD_DYNAMIC_FEE_VOLATILITY, ERROR_BAD_DYNAMIC_FEE_WINDOW, ERROR_BAD_PERCENTS,
+};
+
+use super::{amm, config, safe_price};
+
+pub const MAX_DYNAMIC_FEE_OBSERVATIONS_WINDOW: usize = 100;
+const VOLATILITY_PRICE_PRECISION: u64 = 1_000_000_000_000_000_000;
+
+/// When set, the total fee moves between `min_fee_percent` and `max_fee_percent`
+/// proportionally to the volatility of the last `observations_window` price observations.
+/// Volatility is the spread between the highest and the lowest price seen, relative to
+/// the lowest one, in `MAX_PERCENTAGE` units. Starting from `max_volatility`, the
+/// maximum fee applies.
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug,
+)]
+pub struct DynamicFeeConfig {
+    pub min_fee_percent: u64,
+    pub max_fee_percent: u64,
+    pub observations_window: usize,
+    pub max_volatility: u64,
+}
+
+#[multiversx_sc::module]
+pub trait DynamicFeeModule:
+    safe_price::SafePriceModule
+    + amm::AmmModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    #[endpoint(setDynamicFeeConfig)]
+    fn set_dynamic_fee_config(
+        &self,
+        min_fee_percent: u64,
+        max_fee_percent: u64,
+        observations_window: usize,
+        max_volatility: u64,
+    ) {
+        self.require_caller_has_owner_or_admin_permissions();
+        require!(
+            self.special_fee_percent().get() <= min_fee_percent
+                && min_fee_percent <= max_fee_percent
+                && max_fee_percent <= MAX_FEE_PERCENTAGE,
+            ERROR_BAD_PERCENTS
+        );
+        require!(
+            (1..=MAX_DYNAMIC_FEE_OBSERVATIONS_WINDOW).contains(&observations_window),
+            ERROR_BAD_DYNAMIC_FEE_WINDOW
+        );
+        require!(max_volatility > 0, ERROR_BAD_DYNAMIC_FEE_VOLATILITY);
+
+        self.dynamic_fee_config().set(DynamicFeeConfig {
+            min_fee_percent,
+            max_fee_percent,
+            observations_window,
+            max_volatility,
+        });
+    }
+
+    #[endpoint(disableDynamicFee)]
+    fn disable_dynamic_fee(&self) {
+        self.require_caller_has_owner_or_admin_permissions();
+        self.dynamic_fee_config().clear();
+    }
+
+    #[view(getDynamicFeeConfig)]
+    fn get_dynamic_fee_config(&self) -> OptionalValue<DynamicFeeConfig> {
+        let config_mapper = self.dynamic_fee_config();
+        if config_mapper.is_empty() {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(config_mapper.get())
+        }
+    }
+
+    /// The total fee percent applied to swaps, which is the static
+    /// `total_fee_percent` unless the dynamic fee mode is enabled.
+    #[view(getCurrentTotalFeePercent)]
+    fn get_current_total_fee_percent(&self) -> u64 {
+        let config_mapper = self.dynamic_fee_config();
+        if config_mapper.is_empty() {
+            return self.total_fee_percent().get();
+        }
+
+        let config = config_mapper.get();
+        let volatility = self.get_recent_volatility(config.observations_window);
+        let dynamic_fee_percent = if volatility >= config.max_volatility {
+            config.max_fee_percent
+        } else {
+            config.min_fee_percent
+                + (config.max_fee_percent - config.min_fee_percent) * volatility
+                    / config.max_volatility
+        };
+
+        // the special fee is always taken out of the total fee
+        core::cmp::max(dynamic_fee_percent, self.special_fee_percent().get())
+    }
+
+    fn get_recent_volatility(&self, observations_window: usize) -> u64 {
+        let first_token_reserve = self.pair_reserve(&self.first_token_id().get()).get();
+        let second_token_reserve = self.pair_reserve(&self.second_token_id().get()).get();
+        if first_token_reserve == 0 || second_token_reserve == 0 {
+            return 0;
+        }
+
+        let spot_price = second_token_reserve * VOLATILITY_PRICE_PRECISION / first_token_reserve;
+        let mut min_price = spot_price.clone();
+        let mut max_price = spot_price;
+
+        // reserves are constant between two consecutive observations,
+        // so the ratio of the accumulated deltas is the price in that interval
+        let observations_len = core::cmp::min(
+            self.price_observations().len(),
+            self.get_observation_cardinality(),
+        );
+        let intervals_count =
+            core::cmp::min(observations_window, observations_len.saturating_sub(1));
+        if intervals_count == 0 {
+            return 0;
+        }
+
+        let mut index = self.safe_price_current_index().get();
+        let mut observation = self.get_price_observation_at(index);
+        for _ in 0..intervals_count {
+            index = if index == 1 {
+                observations_len
+            } else {
+                index - 1
+            };
+            let previous_observation = self.get_price_observation_at(index);
+
+            let first_token_delta = &observation.first_token_reserve_accumulated
+                - &previous_observation.first_token_reserve_accumulated;
+            let second_token_delta = &observation.second_token_reserve_accumulated
+                - &previous_observation.second_token_reserve_accumulated;
+            if first_token_delta > 0 {
+                let price = second_token_delta * VOLATILITY_PRICE_PRECISION / first_token_delta;
+                if price < min_price {
+                    min_price = price;
+                } else if price > max_price {
+                    max_price = price;
+                }
+            }
+
+            observation = previous_observation;
+        }
+
+        if min_price == 0 {
+            return 0;
+        }
+
+        let volatility = (max_price - &min_price) * MAX_PERCENTAGE / min_price;
+        volatility.to_u64().unwrap_or(u64::MAX)
+    }
+
+    #[storage_mapper("dynamicFeeConfig")]
+    fn dynamic_fee_config(&self) -> SingleValueMapper<DynamicFeeConfig>;
+}
@@ -0,0 +1,188 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::config::MAX_PERCENTAGE;
+use crate::contexts::base::{StorageCache, SwapTokensOrder};
+use crate::contexts::swap::SwapContext;
+use crate::errors::ERROR_BAD_EPOCH_RANGE;
+
+use super::config;
+
+pub type Epoch = u64;
+
+pub const MAX_STATS_EPOCHS_RANGE: Epoch = 365;
+
+/// Swap volume and fees of a pair over one or more epochs.
+/// The volume of a token counts both the amounts swapped in and out.
+/// The reserves and the LP supply are the ones after the last swap
+/// or liquidity change of the period.
+#[derive(
+    ManagedVecItem,
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    PartialEq,
+    Clone,
+    Debug,
+)]
+pub struct EpochStats<M: ManagedTypeApi> {
+    pub first_token_volume: BigUint<M>,
+    pub second_token_volume: BigUint<M>,
+    pub first_token_lp_fees: BigUint<M>,
+    pub second_token_lp_fees: BigUint<M>,
+    pub first_token_special_fees: BigUint<M>,
+    pub second_token_special_fees: BigUint<M>,
+    pub swaps_count: u64,
+    pub first_token_reserve: BigUint<M>,
+    pub second_token_reserve: BigUint<M>,
+    pub lp_token_supply: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> Default for EpochStats<M> {
+    fn default() -> Self {
+        EpochStats {
+            first_token_volume: BigUint::zero(),
+            second_token_volume: BigUint::zero(),
+            first_token_lp_fees: BigUint::zero(),
+            second_token_lp_fees: BigUint::zero(),
+            first_token_special_fees: BigUint::zero(),
+            second_token_special_fees: BigUint::zero(),
+            swaps_count: 0,
+            first_token_reserve: BigUint::zero(),
+            second_token_reserve: BigUint::zero(),
+            lp_token_supply: BigUint::zero(),
+        }
+    }
+}
+
+#[multiversx_sc::module]
+pub trait EpochStatsModule:
+    config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    /// Returns the stats of every epoch in the range that had activity
+    #[view(getEpochStats)]
+    fn get_epoch_stats(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> MultiValueEncoded<MultiValue2<Epoch, EpochStats<Self::Api>>> {
+        self.require_valid_epoch_range(start_epoch, end_epoch);
+
+        let mut result = MultiValueEncoded::new();
+        for epoch in start_epoch..=end_epoch {
+            let stats_mapper = self.epoch_stats(epoch);
+            if !stats_mapper.is_empty() {
+                result.push((epoch, stats_mapper.get()).into());
+            }
+        }
+
+        result
+    }
+
+    /// Sums the volume, fees and swaps of the epochs in the range.
+    /// The reserves and the LP supply are the ones of the last epoch with activity.
+    #[view(getAggregatedStats)]
+    fn get_aggregated_stats(&self, start_epoch: Epoch, end_epoch: Epoch) -> EpochStats<Self::Api> {
+        self.require_valid_epoch_range(start_epoch, end_epoch);
+
+        let mut aggregated_stats = EpochStats::default();
+        for epoch in start_epoch..=end_epoch {
+            let stats_mapper = self.epoch_stats(epoch);
+            if stats_mapper.is_empty() {
+                continue;
+            }
+
+            let stats = stats_mapper.get();
+            aggregated_stats.first_token_volume += stats.first_token_volume;
+            aggregated_stats.second_token_volume += stats.second_token_volume;
+            aggregated_stats.first_token_lp_fees += stats.first_token_lp_fees;
+            aggregated_stats.second_token_lp_fees += stats.second_token_lp_fees;
+            aggregated_stats.first_token_special_fees += stats.first_token_special_fees;
+            aggregated_stats.second_token_special_fees += stats.second_token_special_fees;
+            aggregated_stats.swaps_count += stats.swaps_count;
+            aggregated_stats.first_token_reserve = stats.first_token_reserve;
+            aggregated_stats.second_token_reserve = stats.second_token_reserve;
+            aggregated_stats.lp_token_supply = stats.lp_token_supply;
+        }
+
+        aggregated_stats
+    }
+
+    fn record_swap_stats(
+        &self,
+        storage_cache: &StorageCache<Self>,
+        context: &SwapContext<Self::Api>,
+    ) {
+        let total_fee_amount =
+            &context.final_input_amount * context.total_fee_percent / MAX_PERCENTAGE;
+        let lp_fee_amount = if total_fee_amount > context.fee_amount {
+            total_fee_amount - &context.fee_amount
+        } else {
+            BigUint::zero()
+        };
+
+        let stats_mapper = self.epoch_stats(self.blockchain().get_block_epoch());
+        let mut stats = self.get_or_default_stats(&stats_mapper);
+        match context.swap_tokens_order {
+            SwapTokensOrder::PoolOrder => {
+                stats.first_token_volume += &context.final_input_amount;
+                stats.second_token_volume += &context.final_output_amount;
+                stats.first_token_lp_fees += lp_fee_amount;
+                stats.first_token_special_fees += &context.fee_amount;
+            }
+            SwapTokensOrder::ReverseOrder => {
+                stats.second_token_volume += &context.final_input_amount;
+                stats.first_token_volume += &context.final_output_amount;
+                stats.second_token_lp_fees += lp_fee_amount;
+                stats.second_token_special_fees += &context.fee_amount;
+            }
+        }
+        stats.swaps_count += 1;
+
+        self.set_stats_reserves(&mut stats, storage_cache);
+        stats_mapper.set(stats);
+    }
+
+    fn record_liquidity_stats(&self, storage_cache: &StorageCache<Self>) {
+        let stats_mapper = self.epoch_stats(self.blockchain().get_block_epoch());
+        let mut stats = self.get_or_default_stats(&stats_mapper);
+        self.set_stats_reserves(&mut stats, storage_cache);
+        stats_mapper.set(stats);
+    }
+
+    fn set_stats_reserves(
+        &self,
+        stats: &mut EpochStats<Self::Api>,
+        storage_cache: &StorageCache<Self>,
+    ) {
+        stats.first_token_reserve = storage_cache.first_token_reserve.clone();
+        stats.second_token_reserve = storage_cache.second_token_reserve.clone();
+        stats.lp_token_supply = storage_cache.lp_token_supply.clone();
+    }
+
+    fn get_or_default_stats(
+        &self,
+        stats_mapper: &SingleValueMapper<EpochStats<Self::Api>>,
+    ) -> EpochStats<Self::Api> {
+        if stats_mapper.is_empty() {
+            return EpochStats::default();
+        }
+
+        stats_mapper.get()
+    }
+
+    fn require_valid_epoch_range(&self, start_epoch: Epoch, end_epoch: Epoch) {
+        require!(
+            start_epoch <= end_epoch && end_epoch - start_epoch < MAX_STATS_EPOCHS_RANGE,
+            ERROR_BAD_EPOCH_RANGE
+        );
+    }
+
+    #[storage_mapper("epochStats")]
+    fn epoch_stats(&self, epoch: Epoch) -> SingleValueMapper<EpochStats<Self::Api>>;
+}
@@ -45,6 +45,43 @@ pub static ERROR_LP_TOKEN_SAME_AS_POOL_TOKENS: &[u8] =
 pub static ERROR_SWAP_NOT_ENABLED: &[u8] = b"Swap is not enabled";
 pub static ERROR_SLIPPAGE_EXCEEDED: &[u8] = b"Slippage exceeded";
 pub static ERROR_NOTHING_TO_DO_WITH_FEE_SLICE: &[u8] = b"Nothing to do with fee slice";
+pub static ERROR_PROTOCOL_FEE_RECIPIENT_NOT_SET: &[u8] = b"Protocol fee recipient not set";
+
+pub static ERROR_CURVE_LIQUIDITY_ADDED: &[u8] =
+    b"Curve cannot be changed after liquidity was added";
+pub static ERROR_BAD_AMPLIFICATION_COEFFICIENT: &[u8] = b"Bad amplification coefficient";
+pub static ERROR_BAD_CURVE_WEIGHT: &[u8] = b"Bad curve weight";
+pub static ERROR_STABLE_SWAP_NOT_CONVERGED: &[u8] = b"Stable swap computation did not converge";
+
+pub static ERROR_POSITION_TOKEN_ALREADY_SET: &[u8] = b"Position token already set";
+pub static ERROR_POSITION_TOKEN_NOT_SET: &[u8] = b"Position token not set";
+pub static ERROR_POSITION_TOKEN_SAME_AS_POOL_TOKENS: &[u8] =
+    b"Position token should differ from the pool tokens";
+pub static ERROR_CONCENTRATED_LIQUIDITY_CURVE: &[u8] =
+    b"Concentrated liquidity is only available for the constant product curve";
+pub static ERROR_CONCENTRATED_POSITIONS_EXIST: &[u8] = b"Concentrated positions exist";
+pub static ERROR_INVALID_TICK: &[u8] = b"Invalid tick";
+pub static ERROR_INVALID_TICK_RANGE: &[u8] = b"Invalid tick range";
+pub static ERROR_TICK_NOT_INITIALIZED: &[u8] = b"Tick not initialized";
+pub static ERROR_NOT_ENOUGH_LIQUIDITY_IN_RANGE: &[u8] = b"Not enough liquidity in range";
+pub static ERROR_PRICE_LIMIT_REACHED: &[u8] = b"Price limit reached";
+
+pub static ERROR_BAD_DYNAMIC_FEE_WINDOW: &[u8] = b"Bad dynamic fee observations window";
+pub static ERROR_BAD_DYNAMIC_FEE_VOLATILITY: &[u8] = b"Bad dynamic fee volatility threshold";
+
+pub static ERROR_FEE_DISCOUNT_TIER_ALREADY_EXISTS: &[u8] = b"Fee discount tier already exists";
+pub static ERROR_FEE_DISCOUNT_TIER_NOT_FOUND: &[u8] = b"Fee discount tier not found";
+pub static ERROR_TOO_MANY_FEE_DISCOUNT_TIERS: &[u8] = b"Too many fee discount tiers";
+
+pub static ERROR_BAD_CIRCUIT_BREAKER_WINDOW: &[u8] = b"Bad circuit breaker round window";
+pub static ERROR_BAD_CIRCUIT_BREAKER_DEVIATION: &[u8] = b"Bad circuit breaker deviation threshold";
+pub static ERROR_CIRCUIT_BREAKER_TRIPPED: &[u8] = b"Circuit breaker tripped";
+
+pub static ERROR_FLASH_LOAN_ONGOING: &[u8] = b"Flash loan ongoing";
+pub static ERROR_NO_FLASH_LOAN_ONGOING: &[u8] = b"No flash loan ongoing";
+pub static ERROR_FLASH_LOAN_NOT_REPAID: &[u8] = b"Flash loan not repaid";
+pub static ERROR_FLASH_LOAN_CALLER_NOT_SC: &[u8] =
+    b"Flash loans are only available to smart contracts";
 
 pub static ERROR_SAFE_PRICE_SAME_ROUNDS: &[u8] =
     b"The safe price can be computed only between different rounds";
@@ -52,3 +89,15 @@ pub static ERROR_SAFE_PRICE_CURRENT_INDEX: &[u8] =
     b"The current safe price index is greater than the maximum number of observations";
 pub static ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST: &[u8] =
     b"The price observation does not exist";
+pub static ERROR_TWAP_NOT_AVAILABLE: &[u8] =
+    b"The price accumulators are not available for the given rounds";
+pub static ERROR_FAIR_PRICE_CURVE: &[u8] =
+    b"The fair LP price is only available for constant product pairs";
+pub static ERROR_BAD_OBSERVATION_CARDINALITY: &[u8] =
+    b"The observation cardinality must be between 2 and the maximum number of observations";
+pub static ERROR_BAD_EPOCH_RANGE: &[u8] = b"Invalid epoch range";
+pub static ERROR_EGLD_WRAPPER_NOT_SET: &[u8] = b"EGLD wrapper not set";
+pub static ERROR_NOTHING_TO_SKIM: &[u8] = b"Nothing to skim";
+pub static ERROR_BAD_WRAPPED_EGLD_TOKEN: &[u8] = b"Wrapped EGLD is not one of the pair tokens";
+pub static ERROR_BAD_SWAP_HOOK_FLAGS: &[u8] = b"Bad swap hook flags";
+pub static ERROR_BAD_SWAP_HOOK_FEE: &[u8] = b"Bad swap hook fee";
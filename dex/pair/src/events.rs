@@ -1,7 +1,9 @@
+use crate::concentrated_liquidity::positions::ConcentratedPositionAttributes;
 use crate::contexts::add_liquidity::AddLiquidityContext;
 use crate::contexts::base::StorageCache;
 use crate::contexts::remove_liquidity::RemoveLiquidityContext;
 use crate::contexts::swap::SwapContext;
+use crate::native_egld::native_egld_token_id;
 
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
@@ -19,6 +21,55 @@ pub struct SwapEvent<M: ManagedTypeApi> {
     block: u64,
     epoch: u64,
     timestamp: u64,
+    total_fee_percent: u64,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct FlashLoanEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    token_id: TokenIdentifier<M>,
+    token_amount: BigUint<M>,
+    fee_amount: BigUint<M>,
+    token_reserve: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct CircuitBreakerEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    spot_price: BigUint<M>,
+    safe_price: BigUint<M>,
+    deviation_percent: u64,
+    first_token_reserve: BigUint<M>,
+    second_token_reserve: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct SkimEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    to: ManagedAddress<M>,
+    first_token_amount: BigUint<M>,
+    second_token_amount: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct SyncEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    previous_first_token_reserve: BigUint<M>,
+    previous_second_token_reserve: BigUint<M>,
+    first_token_reserve: BigUint<M>,
+    second_token_reserve: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
 }
 
 #[derive(TypeAbi, TopEncode)]
@@ -68,6 +119,23 @@ pub struct RemoveLiquidityEvent<M: ManagedTypeApi> {
     timestamp: u64,
 }
 
+#[derive(TypeAbi, TopEncode)]
+pub struct ConcentratedLiquidityEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    position_token_id: TokenIdentifier<M>,
+    position_token_nonce: u64,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: BigUint<M>,
+    first_token_id: TokenIdentifier<M>,
+    first_token_amount: BigUint<M>,
+    second_token_id: TokenIdentifier<M>,
+    second_token_amount: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
 #[multiversx_sc::module]
 pub trait EventsModule:
     crate::config::ConfigModule
@@ -78,16 +146,26 @@ pub trait EventsModule:
     fn emit_swap_event(&self, storage_cache: &StorageCache<Self>, context: SwapContext<Self::Api>) {
         let epoch = self.blockchain().get_block_epoch();
         let caller = self.blockchain().get_caller();
+        let token_id_in = if context.native_egld_in {
+            native_egld_token_id()
+        } else {
+            context.input_token_id
+        };
+        let token_id_out = if context.native_egld_out {
+            native_egld_token_id()
+        } else {
+            context.output_token_id
+        };
         self.swap_event(
-            &context.input_token_id.clone(),
-            &context.output_token_id.clone(),
+            &token_id_in.clone(),
+            &token_id_out.clone(),
             &caller,
             epoch,
             &SwapEvent {
                 caller: caller.clone(),
-                token_id_in: context.input_token_id,
+                token_id_in,
                 token_amount_in: context.final_input_amount,
-                token_id_out: context.output_token_id,
+                token_id_out,
                 token_amount_out: context.final_output_amount,
                 fee_amount: context.fee_amount,
                 token_in_reserve: storage_cache
@@ -99,6 +177,7 @@ pub trait EventsModule:
                 block: self.blockchain().get_block_nonce(),
                 epoch,
                 timestamp: self.blockchain().get_block_timestamp(),
+                total_fee_percent: context.total_fee_percent,
             },
         )
     }
@@ -128,6 +207,110 @@ pub trait EventsModule:
         )
     }
 
+    fn emit_flash_loan_event(
+        &self,
+        caller: &ManagedAddress,
+        loan: EsdtTokenPayment,
+        fee_amount: BigUint,
+        token_reserve: BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        self.flash_loan_event(
+            &loan.token_identifier.clone(),
+            caller,
+            epoch,
+            &FlashLoanEvent {
+                caller: caller.clone(),
+                token_id: loan.token_identifier,
+                token_amount: loan.amount,
+                fee_amount,
+                token_reserve,
+                block: self.blockchain().get_block_nonce(),
+                epoch,
+                timestamp: self.blockchain().get_block_timestamp(),
+            },
+        )
+    }
+
+    fn emit_circuit_breaker_event(
+        &self,
+        storage_cache: &StorageCache<Self>,
+        spot_price: BigUint,
+        safe_price: BigUint,
+        deviation_percent: u64,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.circuit_breaker_event(
+            &storage_cache.first_token_id,
+            &storage_cache.second_token_id,
+            epoch,
+            &CircuitBreakerEvent {
+                caller,
+                spot_price,
+                safe_price,
+                deviation_percent,
+                first_token_reserve: storage_cache.first_token_reserve.clone(),
+                second_token_reserve: storage_cache.second_token_reserve.clone(),
+                block: self.blockchain().get_block_nonce(),
+                epoch,
+                timestamp: self.blockchain().get_block_timestamp(),
+            },
+        )
+    }
+
+    fn emit_skim_event(
+        &self,
+        storage_cache: &StorageCache<Self>,
+        to: &ManagedAddress,
+        first_token_amount: &BigUint,
+        second_token_amount: &BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.skim_event(
+            &storage_cache.first_token_id,
+            &storage_cache.second_token_id,
+            &caller,
+            epoch,
+            &SkimEvent {
+                caller: caller.clone(),
+                to: to.clone(),
+                first_token_amount: first_token_amount.clone(),
+                second_token_amount: second_token_amount.clone(),
+                block: self.blockchain().get_block_nonce(),
+                epoch,
+                timestamp: self.blockchain().get_block_timestamp(),
+            },
+        )
+    }
+
+    fn emit_sync_event(
+        &self,
+        storage_cache: &StorageCache<Self>,
+        previous_first_token_reserve: BigUint,
+        previous_second_token_reserve: BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.sync_event(
+            &storage_cache.first_token_id,
+            &storage_cache.second_token_id,
+            &caller,
+            epoch,
+            &SyncEvent {
+                caller: caller.clone(),
+                previous_first_token_reserve,
+                previous_second_token_reserve,
+                first_token_reserve: storage_cache.first_token_reserve.clone(),
+                second_token_reserve: storage_cache.second_token_reserve.clone(),
+                block: self.blockchain().get_block_nonce(),
+                epoch,
+                timestamp: self.blockchain().get_block_timestamp(),
+            },
+        )
+    }
+
     fn emit_add_liquidity_event(
         &self,
         storage_cache: &StorageCache<Self>,
@@ -188,6 +371,51 @@ pub trait EventsModule:
         )
     }
 
+    fn emit_concentrated_liquidity_event(
+        &self,
+        caller: &ManagedAddress,
+        is_add: bool,
+        position: &EsdtTokenPayment,
+        attributes: &ConcentratedPositionAttributes<Self::Api>,
+        first_payment: &EsdtTokenPayment,
+        second_payment: &EsdtTokenPayment,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        let event = ConcentratedLiquidityEvent {
+            caller: caller.clone(),
+            position_token_id: position.token_identifier.clone(),
+            position_token_nonce: position.token_nonce,
+            tick_lower: attributes.tick_lower,
+            tick_upper: attributes.tick_upper,
+            liquidity: attributes.liquidity.clone(),
+            first_token_id: first_payment.token_identifier.clone(),
+            first_token_amount: first_payment.amount.clone(),
+            second_token_id: second_payment.token_identifier.clone(),
+            second_token_amount: second_payment.amount.clone(),
+            block: self.blockchain().get_block_nonce(),
+            epoch,
+            timestamp: self.blockchain().get_block_timestamp(),
+        };
+
+        if is_add {
+            self.add_concentrated_liquidity_event(
+                &first_payment.token_identifier,
+                &second_payment.token_identifier,
+                caller,
+                epoch,
+                &event,
+            );
+        } else {
+            self.remove_concentrated_liquidity_event(
+                &first_payment.token_identifier,
+                &second_payment.token_identifier,
+                caller,
+                epoch,
+                &event,
+            );
+        }
+    }
+
     #[event("swap")]
     fn swap_event(
         &self,
@@ -207,6 +435,44 @@ pub trait EventsModule:
         swap_no_fee_and_forward_event: &SwapNoFeeAndForwardEvent<Self::Api>,
     );
 
+    #[event("flash_loan")]
+    fn flash_loan_event(
+        &self,
+        #[indexed] token_id: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        flash_loan_event: &FlashLoanEvent<Self::Api>,
+    );
+
+    #[event("circuit_breaker")]
+    fn circuit_breaker_event(
+        &self,
+        #[indexed] first_token: &TokenIdentifier,
+        #[indexed] second_token: &TokenIdentifier,
+        #[indexed] epoch: u64,
+        circuit_breaker_event: &CircuitBreakerEvent<Self::Api>,
+    );
+
+    #[event("skim")]
+    fn skim_event(
+        &self,
+        #[indexed] first_token: &TokenIdentifier,
+        #[indexed] second_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        skim_event: &SkimEvent<Self::Api>,
+    );
+
+    #[event("sync")]
+    fn sync_event(
+        &self,
+        #[indexed] first_token: &TokenIdentifier,
+        #[indexed] second_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        sync_event: &SyncEvent<Self::Api>,
+    );
+
     #[event("add_liquidity")]
     fn add_liquidity_event(
         &self,
@@ -226,4 +492,24 @@ pub trait EventsModule:
         #[indexed] epoch: u64,
         remove_liquidity_event: &RemoveLiquidityEvent<Self::Api>,
     );
+
+    #[event("add_concentrated_liquidity")]
+    fn add_concentrated_liquidity_event(
+        &self,
+        #[indexed] first_token: &TokenIdentifier,
+        #[indexed] second_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        concentrated_liquidity_event: &ConcentratedLiquidityEvent<Self::Api>,
+    );
+
+    #[event("remove_concentrated_liquidity")]
+    fn remove_concentrated_liquidity_event(
+        &self,
+        #[indexed] first_token: &TokenIdentifier,
+        #[indexed] second_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        concentrated_liquidity_event: &ConcentratedLiquidityEvent<Self::Api>,
+    );
 }
@@ -12,6 +12,15 @@ use crate::contexts::base::SwapTokensOrder;
 use common_structs::TokenPair;
 use fees_collector::fees_accumulation::ProxyTrait as _;
 
+#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Clone, Copy, Debug)]
+pub enum FeeMode {
+    /// The special fee is taken out of every swap and sent to the fee destinations
+    SpecialFeeTransfers,
+    /// The whole fee stays in the pool, and the protocol's share is minted as LP tokens
+    /// on liquidity events, based on the growth of the invariant since the last one
+    ProtocolFeeMint,
+}
+
 mod self_proxy {
     multiversx_sc::imports!();
 
@@ -31,12 +40,126 @@ pub trait FeeModule:
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
 {
     #[view(getFeeState)]
     fn is_fee_enabled(&self) -> bool {
+        if self.is_protocol_fee_mint_enabled() {
+            return false;
+        }
+
         !self.destination_map().is_empty() || !self.fees_collector_address().is_empty()
     }
 
+    /// The part of the total fee that stays in the pool
+    fn get_lp_fee_percent(&self, total_fee_percent: u64) -> u64 {
+        if self.is_fee_enabled() {
+            total_fee_percent - self.special_fee_percent().get()
+        } else {
+            total_fee_percent
+        }
+    }
+
+    #[endpoint(setFeeMode)]
+    fn set_fee_mode(&self, fee_mode: FeeMode) {
+        self.require_caller_has_owner_permissions();
+
+        if fee_mode == FeeMode::ProtocolFeeMint {
+            require!(
+                !self.protocol_fee_recipient().is_empty(),
+                ERROR_PROTOCOL_FEE_RECIPIENT_NOT_SET
+            );
+        }
+
+        // the fees accumulated in the previous mode are settled first,
+        // and only the invariant growth from now on is charged in the new one
+        let mut storage_cache = StorageCache::new(self);
+        self.mint_protocol_fee(&mut storage_cache);
+
+        self.fee_mode().set(fee_mode);
+        self.k_last().clear();
+        self.update_k_last(&storage_cache);
+    }
+
+    /// The minted LP tokens are sent directly to the recipient, even if it is the fees collector,
+    /// since depositing them would require the LP token to be one of its known tokens
+    #[endpoint(setProtocolFeeRecipient)]
+    fn set_protocol_fee_recipient(&self, recipient: ManagedAddress) {
+        self.require_caller_has_owner_permissions();
+        require!(!recipient.is_zero(), ERROR_PROTOCOL_FEE_RECIPIENT_NOT_SET);
+
+        self.protocol_fee_recipient().set(recipient);
+    }
+
+    #[inline]
+    fn is_protocol_fee_mint_enabled(&self) -> bool {
+        self.fee_mode().get() == FeeMode::ProtocolFeeMint
+    }
+
+    /// Mints the special fee's share of the invariant growth since the last liquidity event.
+    /// With `s` the special fee out of the total fee, the minted amount is
+    /// `supply * s * (root_k - root_k_last) / (root_k * (1 - s) + root_k_last * s)`.
+    /// `s` uses the configured total fee, so the minted share is approximate
+    /// while dynamic fees or fee discounts change the fee actually charged.
+    fn mint_protocol_fee(&self, storage_cache: &mut StorageCache<Self>) {
+        if !self.is_protocol_fee_mint_enabled() || storage_cache.lp_token_supply == 0u64 {
+            return;
+        }
+
+        let k_last = self.k_last().get();
+        if k_last == 0u64 {
+            return;
+        }
+
+        let k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        let root_k = self.get_invariant_root(&k);
+        let root_k_last = self.get_invariant_root(&k_last);
+        if root_k <= root_k_last {
+            return;
+        }
+
+        let total_fee_percent = self.total_fee_percent().get();
+        let special_fee_percent = self.special_fee_percent().get();
+        if special_fee_percent == 0 {
+            return;
+        }
+
+        let numerator =
+            &storage_cache.lp_token_supply * &(&root_k - &root_k_last) * special_fee_percent;
+        let denominator =
+            root_k * (total_fee_percent - special_fee_percent) + root_k_last * special_fee_percent;
+        let protocol_fee = numerator / denominator;
+        if protocol_fee == 0u64 {
+            return;
+        }
+
+        self.send()
+            .esdt_local_mint(&storage_cache.lp_token_id, 0, &protocol_fee);
+        storage_cache.lp_token_supply += &protocol_fee;
+
+        let recipient = self.protocol_fee_recipient().get();
+        self.send()
+            .direct_esdt(&recipient, &storage_cache.lp_token_id, 0, &protocol_fee);
+    }
+
+    /// Has to be called after every liquidity event, once the reserves are updated
+    fn update_k_last(&self, storage_cache: &StorageCache<Self>) {
+        if !self.is_protocol_fee_mint_enabled() {
+            return;
+        }
+
+        let k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        self.k_last().set(k);
+    }
+
     #[endpoint(whitelist)]
     fn whitelist_endpoint(&self, address: ManagedAddress) {
         self.require_caller_has_owner_permissions();
@@ -411,6 +534,18 @@ pub trait FeeModule:
     #[storage_mapper("feesCollectorCutPercentage")]
     fn fees_collector_cut_percentage(&self) -> SingleValueMapper<u64>;
 
+    #[view(getFeeMode)]
+    #[storage_mapper("feeMode")]
+    fn fee_mode(&self) -> SingleValueMapper<FeeMode>;
+
+    #[view(getProtocolFeeRecipient)]
+    #[storage_mapper("protocolFeeRecipient")]
+    fn protocol_fee_recipient(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getKLast)]
+    #[storage_mapper("kLast")]
+    fn k_last(&self) -> SingleValueMapper<BigUint>;
+
     #[storage_mapper("fee_destination")]
     fn destination_map(&self) -> MapMapper<ManagedAddress, TokenIdentifier>;
 
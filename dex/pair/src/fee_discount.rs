@@ -0,0 +1,126 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::config::MAX_PERCENTAGE;
+use crate::errors::{
+    ERROR_BAD_PERCENTS, ERROR_FEE_DISCOUNT_TIER_ALREADY_EXISTS, ERROR_FEE_DISCOUNT_TIER_NOT_FOUND,
+    ERROR_TOO_MANY_FEE_DISCOUNT_TIERS,
+};
+
+use super::{amm, config, dynamic_fee, safe_price};
+
+pub const MAX_FEE_DISCOUNT_TIERS: usize = 10;
+
+/// Callers with at least `min_energy` get `discount_percent` (in `MAX_PERCENTAGE` units)
+/// off the total swap fee. The tier with the highest `min_energy` reached applies.
+#[derive(
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    ManagedVecItem,
+    PartialEq,
+    Clone,
+    Debug,
+)]
+pub struct FeeDiscountTier<M: ManagedTypeApi> {
+    pub min_energy: BigUint<M>,
+    pub discount_percent: u64,
+}
+
+#[multiversx_sc::module]
+pub trait FeeDiscountModule:
+    dynamic_fee::DynamicFeeModule
+    + safe_price::SafePriceModule
+    + amm::AmmModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + energy_query::EnergyQueryModule
+{
+    #[endpoint(addFeeDiscountTier)]
+    fn add_fee_discount_tier(&self, min_energy: BigUint, discount_percent: u64) {
+        self.require_caller_has_owner_or_admin_permissions();
+        require!(discount_percent <= MAX_PERCENTAGE, ERROR_BAD_PERCENTS);
+
+        let tiers = self.fee_discount_tiers().get();
+        require!(
+            tiers.len() < MAX_FEE_DISCOUNT_TIERS,
+            ERROR_TOO_MANY_FEE_DISCOUNT_TIERS
+        );
+
+        // tiers are kept sorted by min energy
+        let new_tier = FeeDiscountTier {
+            min_energy,
+            discount_percent,
+        };
+        let mut new_tiers = ManagedVec::new();
+        let mut is_inserted = false;
+        for tier in tiers.iter() {
+            require!(
+                tier.min_energy != new_tier.min_energy,
+                ERROR_FEE_DISCOUNT_TIER_ALREADY_EXISTS
+            );
+            if !is_inserted && tier.min_energy > new_tier.min_energy {
+                new_tiers.push(new_tier.clone());
+                is_inserted = true;
+            }
+            new_tiers.push(tier);
+        }
+        if !is_inserted {
+            new_tiers.push(new_tier);
+        }
+
+        self.fee_discount_tiers().set(new_tiers);
+    }
+
+    #[endpoint(removeFeeDiscountTier)]
+    fn remove_fee_discount_tier(&self, min_energy: BigUint) {
+        self.require_caller_has_owner_or_admin_permissions();
+
+        let mut tiers = self.fee_discount_tiers().get();
+        let tier_index = tiers
+            .iter()
+            .position(|tier| tier.min_energy == min_energy)
+            .unwrap_or_else(|| sc_panic!(ERROR_FEE_DISCOUNT_TIER_NOT_FOUND));
+        tiers.remove(tier_index);
+
+        self.fee_discount_tiers().set(tiers);
+    }
+
+    /// The total fee percent the given address would currently pay on swaps
+    #[view(getEffectiveFeePercent)]
+    fn get_effective_fee_percent(&self, user: ManagedAddress) -> u64 {
+        self.get_total_fee_percent_for_user(&user)
+    }
+
+    fn get_total_fee_percent_for_user(&self, user: &ManagedAddress) -> u64 {
+        let total_fee_percent = self.get_current_total_fee_percent();
+        let tiers = self.fee_discount_tiers().get();
+        if tiers.is_empty() {
+            return total_fee_percent;
+        }
+
+        let user_energy = self.get_energy_amount(user);
+        let mut discount_percent = 0;
+        for tier in tiers.iter() {
+            if tier.min_energy > user_energy {
+                break;
+            }
+            discount_percent = tier.discount_percent;
+        }
+
+        let discounted_fee_percent =
+            total_fee_percent * (MAX_PERCENTAGE - discount_percent) / MAX_PERCENTAGE;
+
+        // the special fee is always taken out of the total fee
+        core::cmp::max(discounted_fee_percent, self.special_fee_percent().get())
+    }
+
+    #[view(getFeeDiscountTiers)]
+    #[storage_mapper("feeDiscountTiers")]
+    fn fee_discount_tiers(&self) -> SingleValueMapper<ManagedVec<FeeDiscountTier<Self::Api>>>;
+}
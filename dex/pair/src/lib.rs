@@ -4,17 +4,24 @@ multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
 mod amm;
+pub mod circuit_breaker;
+pub mod concentrated_liquidity;
 pub mod config;
 mod contexts;
+pub mod dynamic_fee;
+pub mod epoch_stats;
 pub mod errors;
 mod events;
 pub mod fee;
+pub mod fee_discount;
 mod liquidity_pool;
 pub mod locking_wrapper;
+pub mod native_egld;
 pub mod pair_actions;
 pub mod read_pair_storage;
 pub mod safe_price;
 pub mod safe_price_view;
+pub mod swap_hooks;
 
 use crate::errors::*;
 
@@ -43,10 +50,25 @@ pub trait Pair<ContractReader>:
     + pausable::PausableModule
     + pair_actions::initial_liq::InitialLiquidityModule
     + pair_actions::add_liq::AddLiquidityModule
+    + pair_actions::add_liq_single_token::AddLiquiditySingleTokenModule
     + pair_actions::remove_liq::RemoveLiquidityModule
+    + pair_actions::remove_liq_single_token::RemoveLiquiditySingleTokenModule
     + pair_actions::swap::SwapModule
+    + pair_actions::flash_loan::FlashLoanModule
+    + pair_actions::skim_sync::SkimSyncModule
     + pair_actions::views::ViewsModule
     + pair_actions::common_methods::CommonMethodsModule
+    + concentrated_liquidity::tick_math::TickMathModule
+    + concentrated_liquidity::ticks::TicksModule
+    + concentrated_liquidity::swap::ConcentratedSwapModule
+    + concentrated_liquidity::positions::ConcentratedPositionsModule
+    + dynamic_fee::DynamicFeeModule
+    + epoch_stats::EpochStatsModule
+    + native_egld::NativeEgldModule
+    + swap_hooks::SwapHooksModule
+    + circuit_breaker::CircuitBreakerModule
+    + fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
     + utils::UtilsModule
 {
     #[init]
@@ -101,8 +123,14 @@ pub trait Pair<ContractReader>:
         };
     }
 
+    /// The Router passes the version of the template the pair is upgraded from,
+    /// so that the version is only recorded if the upgrade succeeds
     #[upgrade]
-    fn upgrade(&self) {}
+    fn upgrade(&self, opt_template_version: OptionalValue<u64>) {
+        if let OptionalValue::Some(template_version) = opt_template_version {
+            self.template_version().set(template_version);
+        }
+    }
 
     #[endpoint(setLpTokenIdentifier)]
     fn set_lp_token_identifier(&self, token_identifier: TokenIdentifier) {
@@ -1,5 +1,6 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
+use crate::config::Curve;
 use crate::contexts::add_liquidity::AddLiquidityContext;
 use crate::contexts::base::StorageCache;
 use crate::contexts::base::SwapTokensOrder;
@@ -18,6 +19,9 @@ pub trait LiquidityPoolModule:
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
 {
     fn pool_add_liquidity(
         &self,
@@ -25,12 +29,41 @@ pub trait LiquidityPoolModule:
         second_token_optimal_amount: &BigUint,
         storage_cache: &mut StorageCache<Self>,
     ) -> BigUint {
-        let first_potential_amt = first_token_optimal_amount * &storage_cache.lp_token_supply
-            / &storage_cache.first_token_reserve;
-        let second_potential_amt = second_token_optimal_amount * &storage_cache.lp_token_supply
-            / &storage_cache.second_token_reserve;
+        let liquidity = match self.get_curve() {
+            // deposits are proportional to the reserves, so any homogeneous invariant
+            // grows by the same ratio as the reserves
+            Curve::ConstantProduct | Curve::Weighted { .. } => {
+                let first_potential_amt = first_token_optimal_amount
+                    * &storage_cache.lp_token_supply
+                    / &storage_cache.first_token_reserve;
+                let second_potential_amt = second_token_optimal_amount
+                    * &storage_cache.lp_token_supply
+                    / &storage_cache.second_token_reserve;
 
-        let liquidity = core::cmp::min(first_potential_amt, second_potential_amt);
+                core::cmp::min(first_potential_amt, second_potential_amt)
+            }
+            Curve::StableSwap {
+                amplification_coefficient,
+            } => {
+                let initial_invariant = self.compute_stable_swap_invariant(
+                    &storage_cache.first_token_reserve,
+                    &storage_cache.second_token_reserve,
+                    amplification_coefficient,
+                );
+                let new_invariant = self.compute_stable_swap_invariant(
+                    &(&storage_cache.first_token_reserve + first_token_optimal_amount),
+                    &(&storage_cache.second_token_reserve + second_token_optimal_amount),
+                    amplification_coefficient,
+                );
+                require!(
+                    new_invariant > initial_invariant,
+                    ERROR_INSUFFICIENT_LIQUIDITY
+                );
+
+                (new_invariant - &initial_invariant) * &storage_cache.lp_token_supply
+                    / initial_invariant
+            }
+        };
         require!(liquidity > 0, ERROR_INSUFFICIENT_LIQUIDITY);
 
         storage_cache.lp_token_supply += &liquidity;
@@ -47,8 +80,23 @@ pub trait LiquidityPoolModule:
         second_token_optimal_amount: &BigUint,
         storage_cache: &mut StorageCache<Self>,
     ) -> BigUint {
-        let liquidity =
-            core::cmp::min(first_token_optimal_amount, second_token_optimal_amount).clone();
+        let liquidity = match self.get_curve() {
+            Curve::ConstantProduct => {
+                core::cmp::min(first_token_optimal_amount, second_token_optimal_amount).clone()
+            }
+            Curve::StableSwap {
+                amplification_coefficient,
+            } => self.compute_stable_swap_invariant(
+                first_token_optimal_amount,
+                second_token_optimal_amount,
+                amplification_coefficient,
+            ),
+            Curve::Weighted { first_token_weight } => self.compute_weighted_liquidity(
+                first_token_optimal_amount,
+                second_token_optimal_amount,
+                first_token_weight,
+            ),
+        };
         let minimum_liquidity = BigUint::from(MINIMUM_LIQUIDITY);
         require!(liquidity > minimum_liquidity, ERROR_FIRST_LIQUDITY);
 
@@ -198,11 +246,35 @@ pub trait LiquidityPoolModule:
         swap_tokens_order: SwapTokensOrder,
         amount_in: &BigUint,
     ) -> BigUint {
+        if self.is_concentrated_liquidity_active() {
+            let result = self.simulate_concentrated_swap(
+                &storage_cache.first_token_reserve,
+                &storage_cache.second_token_reserve,
+                swap_tokens_order,
+                amount_in,
+                true,
+                0,
+                0,
+            );
+            let amount_out = result.amount_out.clone();
+            require!(amount_out != 0, ERROR_ZERO_AMOUNT);
+
+            self.apply_concentrated_swap(
+                storage_cache,
+                swap_tokens_order,
+                result,
+                &BigUint::zero(),
+            );
+
+            return amount_out;
+        }
+
         let reserve_in = storage_cache.get_reserve_in(swap_tokens_order);
         let reserve_out = storage_cache.get_reserve_out(swap_tokens_order);
         require!(*reserve_in != 0, ERROR_ZERO_AMOUNT);
 
-        let amount_out = self.get_amount_out_no_fee(amount_in, reserve_in, reserve_out);
+        let amount_out =
+            self.get_amount_out_no_fee(amount_in, reserve_in, reserve_out, swap_tokens_order);
         require!(
             *reserve_out > amount_out && amount_out != 0,
             ERROR_ZERO_AMOUNT
@@ -0,0 +1,153 @@
+multiversx_sc::imports!();
+
+use crate::config;
+use crate::errors::{ERROR_BAD_WRAPPED_EGLD_TOKEN, ERROR_EGLD_WRAPPER_NOT_SET};
+
+pub mod egld_wrapper_proxy {
+    multiversx_sc::imports!();
+
+    #[multiversx_sc::proxy]
+    pub trait EgldWrapperProxy {
+        #[payable("EGLD")]
+        #[endpoint(wrapEgld)]
+        fn wrap_egld(&self) -> EsdtTokenPayment;
+
+        #[payable("*")]
+        #[endpoint(unwrapEgld)]
+        fn unwrap_egld(&self);
+    }
+}
+
+/// The identifier used for native EGLD in the arguments, the results and the events
+pub fn native_egld_token_id<M: ManagedTypeApi>() -> TokenIdentifier<M> {
+    TokenIdentifier::from(&EgldOrEsdtTokenIdentifier::<M>::EGLD_REPRESENTATION[..])
+}
+
+#[multiversx_sc::module]
+pub trait NativeEgldModule:
+    config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    /// Pairs with wrapped EGLD as one of their tokens can be paid in EGLD,
+    /// which is wrapped through the given contract.
+    /// Swaps also send back EGLD when `EGLD` is given as the wanted token.
+    #[endpoint(setEgldWrapper)]
+    fn set_egld_wrapper(
+        &self,
+        wrapper_address: ManagedAddress,
+        wrapped_egld_token_id: TokenIdentifier,
+    ) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            self.blockchain().is_smart_contract(&wrapper_address),
+            "Invalid SC Address"
+        );
+        require!(
+            wrapped_egld_token_id == self.first_token_id().get()
+                || wrapped_egld_token_id == self.second_token_id().get(),
+            ERROR_BAD_WRAPPED_EGLD_TOKEN
+        );
+
+        self.egld_wrapper_address().set(wrapper_address);
+        self.wrapped_egld_token_id().set(wrapped_egld_token_id);
+    }
+
+    /// Returns the payment, with EGLD wrapped, and whether it was paid in EGLD
+    fn get_payment_wrapping_egld(&self) -> (EsdtTokenPayment, bool) {
+        let (token_id, amount) = self.call_value().egld_or_single_fungible_esdt();
+        if token_id.is_esdt() {
+            return (
+                EsdtTokenPayment::new(token_id.unwrap_esdt(), 0, amount),
+                false,
+            );
+        }
+
+        require!(
+            !self.egld_wrapper_address().is_empty(),
+            ERROR_EGLD_WRAPPER_NOT_SET
+        );
+        let wrapper_address = self.egld_wrapper_address().get();
+        let payment = self
+            .egld_wrapper_proxy(wrapper_address)
+            .wrap_egld()
+            .with_egld_transfer(amount)
+            .execute_on_dest_context();
+
+        (payment, true)
+    }
+
+    /// Replaces `EGLD` with the wrapped EGLD token,
+    /// and returns whether the output has to be unwrapped
+    fn resolve_wanted_token(&self, token_wanted: TokenIdentifier) -> (TokenIdentifier, bool) {
+        if token_wanted != native_egld_token_id::<Self::Api>() {
+            return (token_wanted, false);
+        }
+
+        require!(
+            !self.egld_wrapper_address().is_empty(),
+            ERROR_EGLD_WRAPPER_NOT_SET
+        );
+
+        (self.wrapped_egld_token_id().get(), true)
+    }
+
+    /// Sends the payments, with wrapped EGLD unwrapped if requested.
+    /// The returned payments show the unwrapped ones as `EGLD`.
+    fn send_payments_unwrapping_egld(
+        &self,
+        destination: &ManagedAddress,
+        payments: &ManagedVec<EsdtTokenPayment>,
+        unwrap_egld: bool,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        if !unwrap_egld {
+            self.send_multiple_tokens_if_not_zero(destination, payments);
+            return payments.clone();
+        }
+
+        let wrapped_egld_token_id = self.wrapped_egld_token_id().get();
+        let mut esdt_payments = ManagedVec::new();
+        let mut sent_payments = ManagedVec::new();
+        for payment in payments {
+            if payment.token_identifier != wrapped_egld_token_id {
+                esdt_payments.push(payment.clone());
+                sent_payments.push(payment);
+                continue;
+            }
+
+            if payment.amount > 0u64 {
+                self.unwrap_egld(&payment);
+                self.send().direct_egld(destination, &payment.amount);
+            }
+            sent_payments.push(EsdtTokenPayment::new(
+                native_egld_token_id(),
+                0,
+                payment.amount,
+            ));
+        }
+        self.send_multiple_tokens_if_not_zero(destination, &esdt_payments);
+
+        sent_payments
+    }
+
+    fn unwrap_egld(&self, payment: &EsdtTokenPayment) {
+        let wrapper_address = self.egld_wrapper_address().get();
+        let _: IgnoreValue = self
+            .egld_wrapper_proxy(wrapper_address)
+            .unwrap_egld()
+            .with_esdt_transfer(payment.clone())
+            .execute_on_dest_context();
+    }
+
+    #[proxy]
+    fn egld_wrapper_proxy(&self, to: ManagedAddress) -> egld_wrapper_proxy::Proxy<Self::Api>;
+
+    #[view(getEgldWrapperAddress)]
+    #[storage_mapper("egldWrapperAddress")]
+    fn egld_wrapper_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getWrappedEgldTokenId)]
+    #[storage_mapper("wrappedEgldTokenId")]
+    fn wrapped_egld_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
+}
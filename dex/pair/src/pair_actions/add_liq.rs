@@ -16,12 +16,17 @@ pub trait AddLiquidityModule:
     + crate::locking_wrapper::LockingWrapperModule
     + crate::events::EventsModule
     + crate::safe_price::SafePriceModule
+    + crate::fee::FeeModule
     + crate::config::ConfigModule
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
     + super::common_methods::CommonMethodsModule
     + utils::UtilsModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
+    + crate::epoch_stats::EpochStatsModule
 {
     #[payable("*")]
     #[endpoint(addLiquidity)]
@@ -68,6 +73,8 @@ pub trait AddLiquidityModule:
             &storage_cache.lp_token_supply,
         );
 
+        self.mint_protocol_fee(&mut storage_cache);
+
         let initial_k = self.calculate_k_constant(
             &storage_cache.first_token_reserve,
             &storage_cache.second_token_reserve,
@@ -115,9 +122,11 @@ pub trait AddLiquidityModule:
         output_payments.push(lp_payment);
 
         self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
+        self.update_k_last(&storage_cache);
 
         let output = self.build_add_liq_results(&storage_cache, &add_liq_context);
 
+        self.record_liquidity_stats(&storage_cache);
         self.emit_add_liquidity_event(&storage_cache, add_liq_context);
 
         output
@@ -0,0 +1,224 @@
+use crate::{
+    contexts::{add_liquidity::AddLiquidityContext, base::SwapTokensOrder, swap::SwapContext},
+    pair_actions::swap::SwapType,
+    swap_hooks::SwapHookPayload,
+    StorageCache, ERROR_BAD_PAYMENT_TOKENS, ERROR_INITIAL_LIQUIDITY_NOT_ADDED, ERROR_INVALID_ARGS,
+    ERROR_K_INVARIANT_FAILED, ERROR_LP_TOKEN_NOT_ISSUED, ERROR_SLIPPAGE_EXCEEDED,
+    ERROR_SWAP_NOT_ENABLED, ERROR_ZERO_AMOUNT,
+};
+
+use super::common_result_types::AddLiquiditySingleTokenResultType;
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait AddLiquiditySingleTokenModule:
+    crate::liquidity_pool::LiquidityPoolModule
+    + crate::amm::AmmModule
+    + crate::contexts::output_builder::OutputBuilderModule
+    + crate::locking_wrapper::LockingWrapperModule
+    + crate::events::EventsModule
+    + crate::safe_price::SafePriceModule
+    + crate::fee::FeeModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + super::common_methods::CommonMethodsModule
+    + super::swap::SwapModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
+    + crate::dynamic_fee::DynamicFeeModule
+    + crate::safe_price_view::SafePriceViewModule
+    + crate::read_pair_storage::ReadPairStorageModule
+    + crate::circuit_breaker::CircuitBreakerModule
+    + crate::fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
+    + utils::UtilsModule
+    + crate::epoch_stats::EpochStatsModule
+    + crate::native_egld::NativeEgldModule
+    + crate::swap_hooks::SwapHooksModule
+{
+    /// Swaps the optimal part of the payment for the other pool token, then adds both as liquidity.
+    /// Returns the LP tokens, followed by whatever was left of the first and second tokens.
+    #[payable("*")]
+    #[endpoint(addLiquiditySingleToken)]
+    fn add_liquidity_single_token(
+        &self,
+        lp_amount_min: BigUint,
+    ) -> AddLiquiditySingleTokenResultType<Self::Api> {
+        require!(lp_amount_min > 0, ERROR_INVALID_ARGS);
+
+        let caller = self.blockchain().get_caller();
+
+        let (payment, native_egld_in) = self.get_payment_wrapping_egld();
+        require!(payment.amount > 0, ERROR_ZERO_AMOUNT);
+        let first_token_id = self.first_token_id().get();
+        let second_token_id = self.second_token_id().get();
+        let (swap_tokens_order, token_out) = if payment.token_identifier == first_token_id {
+            (SwapTokensOrder::PoolOrder, second_token_id)
+        } else if payment.token_identifier == second_token_id {
+            (SwapTokensOrder::ReverseOrder, first_token_id)
+        } else {
+            sc_panic!(ERROR_BAD_PAYMENT_TOKENS);
+        };
+
+        // the hooks are called while no storage cache is alive, so that they can call the pair.
+        // The part of the payment to swap depends on the fee, so the hook gets the whole payment.
+        let swap_hook = self.swap_hook().get();
+        let total_fee_percent = self.call_before_swap_hook(
+            &swap_hook,
+            SwapHookPayload {
+                user: caller.clone(),
+                swap_type: SwapType::FixedInput,
+                token_in: payment.token_identifier.clone(),
+                amount_in: payment.amount.clone(),
+                token_out: token_out.clone(),
+                amount_out: BigUint::zero(),
+                total_fee_percent: self.get_total_fee_percent_for_user(&caller),
+                fee_amount: BigUint::zero(),
+            },
+        );
+
+        let mut storage_cache = StorageCache::new(self);
+
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+        require!(
+            storage_cache.lp_token_id.is_valid_esdt_identifier(),
+            ERROR_LP_TOKEN_NOT_ISSUED
+        );
+        require!(
+            storage_cache.lp_token_supply != 0,
+            ERROR_INITIAL_LIQUIDITY_NOT_ADDED
+        );
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+
+        let swap_amount = self.get_single_token_swap_amount(
+            &payment.amount,
+            storage_cache.get_reserve_in(swap_tokens_order),
+            storage_cache.get_reserve_out(swap_tokens_order),
+            swap_tokens_order,
+            total_fee_percent,
+        );
+        require!(
+            swap_amount > 0 && swap_amount < payment.amount,
+            ERROR_ZERO_AMOUNT
+        );
+
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+
+        let mut swap_context = SwapContext::new(
+            payment.token_identifier.clone(),
+            swap_amount.clone(),
+            token_out,
+            BigUint::zero(),
+            swap_tokens_order,
+        );
+        swap_context.total_fee_percent = total_fee_percent;
+        swap_context.native_egld_in = native_egld_in;
+        self.perform_swap_fixed_input(&mut swap_context, &mut storage_cache);
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+        self.check_circuit_breaker(&mut storage_cache);
+
+        if swap_context.fee_amount > 0 {
+            self.send_fee(
+                &mut storage_cache,
+                swap_context.swap_tokens_order,
+                &swap_context.input_token_id,
+                &swap_context.fee_amount,
+            );
+        }
+
+        let amount_left =
+            EsdtTokenPayment::new(payment.token_identifier, 0, &payment.amount - &swap_amount);
+        let amount_swapped = EsdtTokenPayment::new(
+            swap_context.output_token_id.clone(),
+            0,
+            swap_context.final_output_amount.clone(),
+        );
+        let (first_payment, second_payment) = match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => (amount_left, amount_swapped),
+            SwapTokensOrder::ReverseOrder => (amount_swapped, amount_left),
+        };
+
+        self.record_swap_stats(&storage_cache, &swap_context);
+        let after_swap_payload =
+            SwapHookPayload::from_context(caller.clone(), SwapType::FixedInput, &swap_context);
+        self.emit_swap_event(&storage_cache, swap_context);
+
+        self.mint_protocol_fee(&mut storage_cache);
+
+        let initial_k = new_k;
+        let mut add_liq_context = AddLiquidityContext::new(
+            first_payment,
+            second_payment,
+            BigUint::zero(),
+            BigUint::zero(),
+        );
+        self.set_optimal_amounts(&mut add_liq_context, &storage_cache);
+
+        add_liq_context.liq_added = self.pool_add_liquidity(
+            &add_liq_context.first_token_optimal_amount,
+            &add_liq_context.second_token_optimal_amount,
+            &mut storage_cache,
+        );
+        require!(
+            add_liq_context.liq_added >= lp_amount_min,
+            ERROR_SLIPPAGE_EXCEEDED
+        );
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+
+        self.send()
+            .esdt_local_mint(&storage_cache.lp_token_id, 0, &add_liq_context.liq_added);
+
+        let lp_payment = EsdtTokenPayment::new(
+            storage_cache.lp_token_id.clone(),
+            0,
+            add_liq_context.liq_added.clone(),
+        );
+
+        let leftover_payments =
+            self.build_add_liq_output_payments(&storage_cache, &add_liq_context);
+        let mut output_payments = ManagedVec::from_single_item(lp_payment);
+        output_payments.append_vec(leftover_payments);
+
+        let output_payments =
+            self.send_payments_unwrapping_egld(&caller, &output_payments, native_egld_in);
+        self.update_k_last(&storage_cache);
+
+        self.record_liquidity_stats(&storage_cache);
+        self.emit_add_liquidity_event(&storage_cache, add_liq_context);
+        drop(storage_cache);
+
+        self.call_after_swap_hook(&swap_hook, after_swap_payload);
+
+        (
+            output_payments.get(0),
+            output_payments.get(1),
+            output_payments.get(2),
+        )
+            .into()
+    }
+}
@@ -9,6 +9,11 @@ pub trait CommonMethodsModule {
         state == State::Active || state == State::PartialActive
     }
 
+    #[inline]
+    fn can_withdraw(&self, state: State) -> bool {
+        self.is_state_active(state) || state == State::WithdrawOnly
+    }
+
     #[inline]
     fn can_swap(&self, state: State) -> bool {
         state == State::Active
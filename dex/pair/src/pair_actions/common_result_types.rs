@@ -3,8 +3,22 @@ multiversx_sc::imports!();
 pub type AddLiquidityResultType<M> =
     MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
 
+pub type AddLiquiditySingleTokenResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+
 pub type RemoveLiquidityResultType<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
 
+pub type RemoveLiquiditySingleTokenResultType<M> = EsdtTokenPayment<M>;
+
 pub type SwapTokensFixedInputResultType<M> = EsdtTokenPayment<M>;
 
 pub type SwapTokensFixedOutputResultType<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+
+pub type AddConcentratedLiquidityResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+
+pub type RemoveConcentratedLiquidityResultType<M> =
+    MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+
+pub type CollectConcentratedFeesResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
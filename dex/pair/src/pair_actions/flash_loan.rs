@@ -0,0 +1,132 @@
+use crate::{
+    config::MAX_PERCENTAGE, contexts::base::SwapTokensOrder, StorageCache,
+    ERROR_BAD_PAYMENT_TOKENS, ERROR_FLASH_LOAN_CALLER_NOT_SC, ERROR_FLASH_LOAN_NOT_REPAID,
+    ERROR_NOT_ENOUGH_RESERVE, ERROR_NO_FLASH_LOAN_ONGOING, ERROR_SWAP_NOT_ENABLED,
+    ERROR_UNKNOWN_TOKEN, ERROR_ZERO_AMOUNT,
+};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait FlashLoanModule:
+    crate::liquidity_pool::LiquidityPoolModule
+    + crate::amm::AmmModule
+    + crate::events::EventsModule
+    + crate::safe_price::SafePriceModule
+    + crate::fee::FeeModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + super::common_methods::CommonMethodsModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
+    + crate::dynamic_fee::DynamicFeeModule
+    + crate::fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
+{
+    /// Sends `amount` of `token_id` to the calling contract and calls its `callback_endpoint`.
+    /// Before the callback returns, the contract has to pay back the loan plus the swap fee
+    /// through `repayFlashLoan`, otherwise the whole transaction fails.
+    /// The fee is split like the swap fees: the special fee is sent to the fee destinations
+    /// and the rest is added to the reserves.
+    #[endpoint(flashLoan)]
+    fn flash_loan(
+        &self,
+        token_id: TokenIdentifier,
+        amount: BigUint,
+        callback_endpoint: ManagedBuffer,
+        callback_args: MultiValueEncoded<ManagedBuffer>,
+    ) {
+        require!(amount > 0u64, ERROR_ZERO_AMOUNT);
+
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.blockchain().is_smart_contract(&caller),
+            ERROR_FLASH_LOAN_CALLER_NOT_SC
+        );
+
+        let mut storage_cache = StorageCache::new(self);
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+
+        let swap_tokens_order = if token_id == storage_cache.first_token_id {
+            SwapTokensOrder::PoolOrder
+        } else if token_id == storage_cache.second_token_id {
+            SwapTokensOrder::ReverseOrder
+        } else {
+            sc_panic!(ERROR_UNKNOWN_TOKEN);
+        };
+        require!(
+            *storage_cache.get_reserve_in(swap_tokens_order) > amount,
+            ERROR_NOT_ENOUGH_RESERVE
+        );
+
+        let total_fee_percent = self.get_total_fee_percent_for_user(&caller);
+        let fee_amount = &amount * total_fee_percent / MAX_PERCENTAGE;
+
+        self.flash_loan_repayment().set(EsdtTokenPayment::new(
+            token_id.clone(),
+            0,
+            BigUint::zero(),
+        ));
+
+        self.tx()
+            .to(&caller)
+            .raw_call(callback_endpoint)
+            .arguments_raw(callback_args.to_arg_buffer())
+            .single_esdt(&token_id, 0, &amount)
+            .sync_call();
+
+        let repayment = self.flash_loan_repayment().take();
+        require!(
+            repayment.amount >= &amount + &fee_amount,
+            ERROR_FLASH_LOAN_NOT_REPAID
+        );
+
+        // anything paid on top of the loan is treated as fee
+        let total_fee_amount = &repayment.amount - &amount;
+        let special_fee_amount = if self.is_fee_enabled() {
+            self.get_special_fee_from_input(&amount)
+        } else {
+            BigUint::zero()
+        };
+        *storage_cache.get_mut_reserve_in(swap_tokens_order) +=
+            &total_fee_amount - &special_fee_amount;
+
+        self.send_fee(
+            &mut storage_cache,
+            swap_tokens_order,
+            &token_id,
+            &special_fee_amount,
+        );
+
+        let token_reserve = storage_cache.get_reserve_in(swap_tokens_order).clone();
+        self.emit_flash_loan_event(
+            &caller,
+            EsdtTokenPayment::new(token_id, 0, amount),
+            total_fee_amount,
+            token_reserve,
+        );
+    }
+
+    #[payable("*")]
+    #[endpoint(repayFlashLoan)]
+    fn repay_flash_loan(&self) {
+        let repayment_mapper = self.flash_loan_repayment();
+        require!(!repayment_mapper.is_empty(), ERROR_NO_FLASH_LOAN_ONGOING);
+
+        let payment = self.call_value().single_esdt();
+        repayment_mapper.update(|repayment| {
+            require!(
+                payment.token_identifier == repayment.token_identifier && payment.token_nonce == 0,
+                ERROR_BAD_PAYMENT_TOKENS
+            );
+
+            repayment.amount += payment.amount;
+        });
+    }
+}
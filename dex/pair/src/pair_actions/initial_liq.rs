@@ -17,12 +17,17 @@ pub trait InitialLiquidityModule:
     + crate::contexts::output_builder::OutputBuilderModule
     + crate::locking_wrapper::LockingWrapperModule
     + crate::events::EventsModule
+    + crate::fee::FeeModule
     + crate::config::ConfigModule
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
     + super::common_methods::CommonMethodsModule
     + utils::UtilsModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
+    + crate::epoch_stats::EpochStatsModule
 {
     #[payable("*")]
     #[endpoint(addInitialLiquidity)]
@@ -47,7 +52,7 @@ pub trait InitialLiquidityModule:
             ERROR_BAD_PAYMENT_TOKENS
         );
         require!(
-            !self.is_state_active(storage_cache.contract_state),
+            storage_cache.contract_state == State::Inactive,
             ERROR_ACTIVE
         );
         require!(
@@ -71,6 +76,7 @@ pub trait InitialLiquidityModule:
 
         self.send()
             .direct_non_zero_esdt_payment(&caller, &lp_payment);
+        self.update_k_last(&storage_cache);
 
         self.state().set(State::PartialActive);
 
@@ -85,6 +91,7 @@ pub trait InitialLiquidityModule:
         };
         let output = self.build_add_initial_liq_results(&storage_cache, &add_liq_context);
 
+        self.record_liquidity_stats(&storage_cache);
         self.emit_add_liquidity_event(&storage_cache, add_liq_context);
 
         output
@@ -1,7 +1,11 @@
 pub mod add_liq;
+pub mod add_liq_single_token;
 pub mod common_methods;
 pub mod common_result_types;
+pub mod flash_loan;
 pub mod initial_liq;
 pub mod remove_liq;
+pub mod remove_liq_single_token;
+pub mod skim_sync;
 pub mod swap;
 pub mod views;
@@ -23,6 +23,10 @@ pub trait RemoveLiquidityModule:
     + pausable::PausableModule
     + super::common_methods::CommonMethodsModule
     + utils::UtilsModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
+    + crate::epoch_stats::EpochStatsModule
 {
     #[payable("*")]
     #[endpoint(removeLiquidity)]
@@ -41,7 +45,7 @@ pub trait RemoveLiquidityModule:
         let payment = self.call_value().single_esdt();
 
         require!(
-            self.is_state_active(storage_cache.contract_state),
+            self.can_withdraw(storage_cache.contract_state),
             ERROR_NOT_ACTIVE
         );
         require!(
@@ -64,6 +68,8 @@ pub trait RemoveLiquidityModule:
             &storage_cache.second_token_reserve,
         );
 
+        self.mint_protocol_fee(&mut storage_cache);
+
         let mut remove_liq_context = RemoveLiquidityContext::new(
             payment.amount,
             first_token_amount_min,
@@ -97,7 +103,9 @@ pub trait RemoveLiquidityModule:
         );
 
         self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
+        self.update_k_last(&storage_cache);
 
+        self.record_liquidity_stats(&storage_cache);
         self.emit_remove_liquidity_event(&storage_cache, remove_liq_context);
 
         self.build_remove_liq_results(output_payments)
@@ -126,6 +134,8 @@ pub trait RemoveLiquidityModule:
             &storage_cache.lp_token_supply,
         );
 
+        self.mint_protocol_fee(&mut storage_cache);
+
         let mut remove_liq_context =
             RemoveLiquidityContext::new(payment.amount, BigUint::from(1u64), BigUint::from(1u64));
         self.pool_remove_liquidity(&mut remove_liq_context, &mut storage_cache);
@@ -155,5 +165,7 @@ pub trait RemoveLiquidityModule:
             &dest_address,
             &token_to_buyback_and_burn,
         );
+
+        self.update_k_last(&storage_cache);
     }
 }
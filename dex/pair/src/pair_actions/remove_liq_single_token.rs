@@ -0,0 +1,199 @@
+use crate::{
+    contexts::{
+        base::SwapTokensOrder, remove_liquidity::RemoveLiquidityContext, swap::SwapContext,
+    },
+    pair_actions::swap::SwapType,
+    swap_hooks::SwapHookPayload,
+    StorageCache, ERROR_BAD_PAYMENT_TOKENS, ERROR_INVALID_ARGS, ERROR_K_INVARIANT_FAILED,
+    ERROR_LP_TOKEN_NOT_ISSUED, ERROR_SLIPPAGE_ON_REMOVE, ERROR_SWAP_NOT_ENABLED,
+    ERROR_UNKNOWN_TOKEN,
+};
+
+use super::common_result_types::RemoveLiquiditySingleTokenResultType;
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait RemoveLiquiditySingleTokenModule:
+    crate::liquidity_pool::LiquidityPoolModule
+    + crate::amm::AmmModule
+    + crate::contexts::output_builder::OutputBuilderModule
+    + crate::locking_wrapper::LockingWrapperModule
+    + crate::events::EventsModule
+    + crate::safe_price::SafePriceModule
+    + crate::fee::FeeModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + super::common_methods::CommonMethodsModule
+    + super::swap::SwapModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
+    + crate::dynamic_fee::DynamicFeeModule
+    + crate::safe_price_view::SafePriceViewModule
+    + crate::read_pair_storage::ReadPairStorageModule
+    + crate::circuit_breaker::CircuitBreakerModule
+    + crate::fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
+    + utils::UtilsModule
+    + crate::epoch_stats::EpochStatsModule
+    + crate::native_egld::NativeEgldModule
+    + crate::swap_hooks::SwapHooksModule
+{
+    /// Removes liquidity and swaps the other pool token for `token_wanted`,
+    /// so that only `token_wanted` is sent back.
+    #[payable("*")]
+    #[endpoint(removeLiquiditySingleToken)]
+    fn remove_liquidity_single_token(
+        &self,
+        token_wanted: TokenIdentifier,
+        min_out: BigUint,
+    ) -> RemoveLiquiditySingleTokenResultType<Self::Api> {
+        require!(min_out > 0, ERROR_INVALID_ARGS);
+
+        let mut storage_cache = StorageCache::new(self);
+        let caller = self.blockchain().get_caller();
+        let payment = self.call_value().single_esdt();
+        let (token_wanted, native_egld_out) = self.resolve_wanted_token(token_wanted);
+
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+        require!(
+            storage_cache.lp_token_id.is_valid_esdt_identifier(),
+            ERROR_LP_TOKEN_NOT_ISSUED
+        );
+        require!(
+            payment.token_identifier == storage_cache.lp_token_id && payment.amount > 0,
+            ERROR_BAD_PAYMENT_TOKENS
+        );
+
+        let swap_tokens_order = if token_wanted == storage_cache.second_token_id {
+            SwapTokensOrder::PoolOrder
+        } else if token_wanted == storage_cache.first_token_id {
+            SwapTokensOrder::ReverseOrder
+        } else {
+            sc_panic!(ERROR_UNKNOWN_TOKEN);
+        };
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+
+        self.mint_protocol_fee(&mut storage_cache);
+
+        let mut remove_liq_context =
+            RemoveLiquidityContext::new(payment.amount, BigUint::zero(), BigUint::zero());
+        self.pool_remove_liquidity(&mut remove_liq_context, &mut storage_cache);
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(new_k <= initial_k, ERROR_K_INVARIANT_FAILED);
+
+        self.burn(
+            &storage_cache.lp_token_id,
+            &remove_liq_context.lp_token_payment_amount,
+        );
+
+        let (token_unwanted, amount_unwanted, amount_wanted) = match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => (
+                storage_cache.first_token_id.clone(),
+                remove_liq_context.first_token_amount_removed.clone(),
+                remove_liq_context.second_token_amount_removed.clone(),
+            ),
+            SwapTokensOrder::ReverseOrder => (
+                storage_cache.second_token_id.clone(),
+                remove_liq_context.second_token_amount_removed.clone(),
+                remove_liq_context.first_token_amount_removed.clone(),
+            ),
+        };
+
+        self.update_k_last(&storage_cache);
+        self.record_liquidity_stats(&storage_cache);
+        self.emit_remove_liquidity_event(&storage_cache, remove_liq_context);
+        drop(storage_cache);
+
+        // the hooks are called while no storage cache is alive, so that they can call the pair
+        let swap_hook = self.swap_hook().get();
+        let total_fee_percent = self.call_before_swap_hook(
+            &swap_hook,
+            SwapHookPayload {
+                user: caller.clone(),
+                swap_type: SwapType::FixedInput,
+                token_in: token_unwanted.clone(),
+                amount_in: amount_unwanted.clone(),
+                token_out: token_wanted.clone(),
+                amount_out: BigUint::zero(),
+                total_fee_percent: self.get_total_fee_percent_for_user(&caller),
+                fee_amount: BigUint::zero(),
+            },
+        );
+
+        let mut storage_cache = StorageCache::new(self);
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        let mut swap_context = SwapContext::new(
+            token_unwanted,
+            amount_unwanted,
+            token_wanted.clone(),
+            BigUint::zero(),
+            swap_tokens_order,
+        );
+        swap_context.total_fee_percent = total_fee_percent;
+        swap_context.native_egld_out = native_egld_out;
+        self.perform_swap_fixed_input(&mut swap_context, &mut storage_cache);
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+        self.check_circuit_breaker(&mut storage_cache);
+
+        if swap_context.fee_amount > 0 {
+            self.send_fee(
+                &mut storage_cache,
+                swap_context.swap_tokens_order,
+                &swap_context.input_token_id,
+                &swap_context.fee_amount,
+            );
+        }
+
+        let output_payment = EsdtTokenPayment::new(
+            token_wanted,
+            0,
+            amount_wanted + &swap_context.final_output_amount,
+        );
+        require!(output_payment.amount >= min_out, ERROR_SLIPPAGE_ON_REMOVE);
+
+        let output_payments = self.send_payments_unwrapping_egld(
+            &caller,
+            &ManagedVec::from_single_item(output_payment),
+            native_egld_out,
+        );
+
+        self.record_swap_stats(&storage_cache, &swap_context);
+        let after_swap_payload =
+            SwapHookPayload::from_context(caller, SwapType::FixedInput, &swap_context);
+        self.emit_swap_event(&storage_cache, swap_context);
+        drop(storage_cache);
+
+        self.call_after_swap_hook(&swap_hook, after_swap_payload);
+
+        output_payments.get(0)
+    }
+}
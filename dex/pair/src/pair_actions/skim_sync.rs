@@ -0,0 +1,109 @@
+use crate::{contexts::base::StorageCache, ERROR_NOTHING_TO_SKIM, ERROR_NOT_ENOUGH_LP};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait SkimSyncModule:
+    crate::config::ConfigModule
+    + crate::events::EventsModule
+    + crate::safe_price::SafePriceModule
+    + crate::epoch_stats::EpochStatsModule
+    + crate::amm::AmmModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    /// Sends the balances above the recorded reserves to `to`.
+    /// The tokens held for the concentrated positions are not touched.
+    #[endpoint]
+    fn skim(&self, to: ManagedAddress) -> MultiValue2<EsdtTokenPayment, EsdtTokenPayment> {
+        self.require_caller_has_owner_permissions();
+
+        let storage_cache = StorageCache::new(self);
+        let first_token_excess = self.get_excess_balance(
+            &storage_cache.first_token_id,
+            &storage_cache.first_token_reserve,
+        );
+        let second_token_excess = self.get_excess_balance(
+            &storage_cache.second_token_id,
+            &storage_cache.second_token_reserve,
+        );
+        require!(
+            first_token_excess > 0u64 || second_token_excess > 0u64,
+            ERROR_NOTHING_TO_SKIM
+        );
+
+        let first_payment =
+            EsdtTokenPayment::new(storage_cache.first_token_id.clone(), 0, first_token_excess);
+        let second_payment = EsdtTokenPayment::new(
+            storage_cache.second_token_id.clone(),
+            0,
+            second_token_excess,
+        );
+        self.send()
+            .direct_non_zero_esdt_payment(&to, &first_payment);
+        self.send()
+            .direct_non_zero_esdt_payment(&to, &second_payment);
+
+        self.emit_skim_event(
+            &storage_cache,
+            &to,
+            &first_payment.amount,
+            &second_payment.amount,
+        );
+
+        (first_payment, second_payment).into()
+    }
+
+    /// Sets the reserves to the balances, minus the tokens held for the concentrated positions.
+    /// A price observation is recorded with the reserves before the sync.
+    #[endpoint]
+    fn sync(&self) {
+        self.require_caller_has_owner_or_admin_permissions();
+
+        let mut storage_cache = StorageCache::new(self);
+        require!(storage_cache.lp_token_supply > 0u64, ERROR_NOT_ENOUGH_LP);
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+
+        let previous_first_token_reserve = storage_cache.first_token_reserve.clone();
+        let previous_second_token_reserve = storage_cache.second_token_reserve.clone();
+        storage_cache.first_token_reserve = self.get_reserve_balance(&storage_cache.first_token_id);
+        storage_cache.second_token_reserve =
+            self.get_reserve_balance(&storage_cache.second_token_id);
+
+        self.record_liquidity_stats(&storage_cache);
+        self.emit_sync_event(
+            &storage_cache,
+            previous_first_token_reserve,
+            previous_second_token_reserve,
+        );
+    }
+
+    fn get_excess_balance(&self, token_id: &TokenIdentifier, reserve: &BigUint) -> BigUint {
+        let reserve_balance = self.get_reserve_balance(token_id);
+        if reserve_balance > *reserve {
+            reserve_balance - reserve
+        } else {
+            BigUint::zero()
+        }
+    }
+
+    fn get_reserve_balance(&self, token_id: &TokenIdentifier) -> BigUint {
+        let balance = self
+            .blockchain()
+            .get_sc_balance(&EgldOrEsdtTokenIdentifier::esdt(token_id.clone()), 0);
+        let concentrated_reserve = self.concentrated_reserve(token_id).get();
+        if balance > concentrated_reserve {
+            balance - concentrated_reserve
+        } else {
+            BigUint::zero()
+        }
+    }
+}
@@ -1,7 +1,9 @@
+use common_errors::ERROR_PERMISSION_DENIED;
+
 use crate::{
-    contexts::swap::SwapContext, StorageCache, ERROR_INVALID_ARGS, ERROR_K_INVARIANT_FAILED,
-    ERROR_NOT_ENOUGH_RESERVE, ERROR_NOT_WHITELISTED, ERROR_SLIPPAGE_EXCEEDED,
-    ERROR_SWAP_NOT_ENABLED, ERROR_ZERO_AMOUNT,
+    config::MAX_PERCENTAGE, contexts::swap::SwapContext, swap_hooks::SwapHookPayload, StorageCache,
+    ERROR_INVALID_ARGS, ERROR_K_INVARIANT_FAILED, ERROR_NOT_ENOUGH_RESERVE, ERROR_NOT_WHITELISTED,
+    ERROR_SLIPPAGE_EXCEEDED, ERROR_SWAP_NOT_ENABLED, ERROR_ZERO_AMOUNT,
 };
 
 use super::common_result_types::{SwapTokensFixedInputResultType, SwapTokensFixedOutputResultType};
@@ -29,7 +31,19 @@ pub trait SwapModule:
     + permissions_module::PermissionsModule
     + pausable::PausableModule
     + super::common_methods::CommonMethodsModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
+    + crate::dynamic_fee::DynamicFeeModule
+    + crate::safe_price_view::SafePriceViewModule
+    + crate::read_pair_storage::ReadPairStorageModule
+    + crate::circuit_breaker::CircuitBreakerModule
+    + crate::fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
     + utils::UtilsModule
+    + crate::epoch_stats::EpochStatsModule
+    + crate::native_egld::NativeEgldModule
+    + crate::swap_hooks::SwapHooksModule
 {
     #[payable("*")]
     #[endpoint(swapNoFeeAndForward)]
@@ -96,11 +110,33 @@ pub trait SwapModule:
         &self,
         token_out: TokenIdentifier,
         amount_out_min: BigUint,
+        opt_original_caller: OptionalValue<ManagedAddress>,
     ) -> SwapTokensFixedInputResultType<Self::Api> {
         require!(amount_out_min > 0, ERROR_INVALID_ARGS);
 
+        let caller = self.blockchain().get_caller();
+        let fee_payer = self.get_swap_fee_payer(&caller, opt_original_caller);
+
+        let (payment, native_egld_in) = self.get_payment_wrapping_egld();
+        let (token_out, native_egld_out) = self.resolve_wanted_token(token_out);
+
+        // the hooks are called while no storage cache is alive, so that they can call the pair
+        let swap_hook = self.swap_hook().get();
+        let total_fee_percent = self.call_before_swap_hook(
+            &swap_hook,
+            SwapHookPayload {
+                user: fee_payer.clone(),
+                swap_type: SwapType::FixedInput,
+                token_in: payment.token_identifier.clone(),
+                amount_in: payment.amount.clone(),
+                token_out: token_out.clone(),
+                amount_out: amount_out_min.clone(),
+                total_fee_percent: self.get_total_fee_percent_for_user(&fee_payer),
+                fee_amount: BigUint::zero(),
+            },
+        );
+
         let mut storage_cache = StorageCache::new(self);
-        let payment = self.call_value().single_esdt();
         let swap_tokens_order =
             storage_cache.get_swap_tokens_order(&payment.token_identifier, &token_out);
 
@@ -130,6 +166,9 @@ pub trait SwapModule:
             amount_out_min,
             swap_tokens_order,
         );
+        swap_context.total_fee_percent = total_fee_percent;
+        swap_context.native_egld_in = native_egld_in;
+        swap_context.native_egld_out = native_egld_out;
         self.perform_swap_fixed_input(&mut swap_context, &mut storage_cache);
 
         let new_k = self.calculate_k_constant(
@@ -137,6 +176,7 @@ pub trait SwapModule:
             &storage_cache.second_token_reserve,
         );
         require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+        self.check_circuit_breaker(&mut storage_cache);
 
         if swap_context.fee_amount > 0 {
             self.send_fee(
@@ -147,7 +187,6 @@ pub trait SwapModule:
             );
         }
 
-        let caller = self.blockchain().get_caller();
         let output_payments = self.build_swap_output_payments(&swap_context);
 
         require!(
@@ -155,9 +194,19 @@ pub trait SwapModule:
             ERROR_SLIPPAGE_EXCEEDED
         );
 
-        self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
+        let output_payments = self.send_payments_unwrapping_egld(
+            &caller,
+            &output_payments,
+            native_egld_in || native_egld_out,
+        );
 
+        self.record_swap_stats(&storage_cache, &swap_context);
+        let after_swap_payload =
+            SwapHookPayload::from_context(fee_payer, SwapType::FixedInput, &swap_context);
         self.emit_swap_event(&storage_cache, swap_context);
+        drop(storage_cache);
+
+        self.call_after_swap_hook(&swap_hook, after_swap_payload);
 
         self.build_swap_fixed_input_results(output_payments)
     }
@@ -168,11 +217,33 @@ pub trait SwapModule:
         &self,
         token_out: TokenIdentifier,
         amount_out: BigUint,
+        opt_original_caller: OptionalValue<ManagedAddress>,
     ) -> SwapTokensFixedOutputResultType<Self::Api> {
         require!(amount_out > 0, ERROR_INVALID_ARGS);
 
+        let caller = self.blockchain().get_caller();
+        let fee_payer = self.get_swap_fee_payer(&caller, opt_original_caller);
+
+        let (payment, native_egld_in) = self.get_payment_wrapping_egld();
+        let (token_out, native_egld_out) = self.resolve_wanted_token(token_out);
+
+        // the hooks are called while no storage cache is alive, so that they can call the pair
+        let swap_hook = self.swap_hook().get();
+        let total_fee_percent = self.call_before_swap_hook(
+            &swap_hook,
+            SwapHookPayload {
+                user: fee_payer.clone(),
+                swap_type: SwapType::FixedOutput,
+                token_in: payment.token_identifier.clone(),
+                amount_in: payment.amount.clone(),
+                token_out: token_out.clone(),
+                amount_out: amount_out.clone(),
+                total_fee_percent: self.get_total_fee_percent_for_user(&fee_payer),
+                fee_amount: BigUint::zero(),
+            },
+        );
+
         let mut storage_cache = StorageCache::new(self);
-        let payment = self.call_value().single_esdt();
         let swap_tokens_order =
             storage_cache.get_swap_tokens_order(&payment.token_identifier, &token_out);
 
@@ -202,6 +273,9 @@ pub trait SwapModule:
             amount_out,
             swap_tokens_order,
         );
+        swap_context.total_fee_percent = total_fee_percent;
+        swap_context.native_egld_in = native_egld_in;
+        swap_context.native_egld_out = native_egld_out;
         self.perform_swap_fixed_output(&mut swap_context, &mut storage_cache);
 
         let new_k = self.calculate_k_constant(
@@ -209,6 +283,7 @@ pub trait SwapModule:
             &storage_cache.second_token_reserve,
         );
         require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+        self.check_circuit_breaker(&mut storage_cache);
 
         if swap_context.fee_amount > 0 {
             self.send_fee(
@@ -219,12 +294,21 @@ pub trait SwapModule:
             );
         }
 
-        let caller = self.blockchain().get_caller();
         let output_payments = self.build_swap_output_payments(&swap_context);
 
-        self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
+        let output_payments = self.send_payments_unwrapping_egld(
+            &caller,
+            &output_payments,
+            native_egld_in || native_egld_out,
+        );
 
+        self.record_swap_stats(&storage_cache, &swap_context);
+        let after_swap_payload =
+            SwapHookPayload::from_context(fee_payer, SwapType::FixedOutput, &swap_context);
         self.emit_swap_event(&storage_cache, swap_context);
+        drop(storage_cache);
+
+        self.call_after_swap_hook(&swap_hook, after_swap_payload);
 
         self.build_swap_fixed_output_results(output_payments)
     }
@@ -236,11 +320,21 @@ pub trait SwapModule:
     ) {
         context.final_input_amount = context.input_token_amount.clone();
 
+        if self.is_concentrated_liquidity_active() {
+            self.perform_concentrated_swap_fixed_input(context, storage_cache);
+            return;
+        }
+
         let reserve_in = storage_cache.get_reserve_in(context.swap_tokens_order);
         let reserve_out = storage_cache.get_reserve_out(context.swap_tokens_order);
 
-        let amount_out_optimal =
-            self.get_amount_out(&context.input_token_amount, reserve_in, reserve_out);
+        let amount_out_optimal = self.get_amount_out(
+            &context.input_token_amount,
+            reserve_in,
+            reserve_out,
+            context.swap_tokens_order,
+            context.total_fee_percent,
+        );
         require!(
             amount_out_optimal >= context.output_token_amount,
             ERROR_SLIPPAGE_EXCEEDED
@@ -270,11 +364,21 @@ pub trait SwapModule:
     ) {
         context.final_output_amount = context.output_token_amount.clone();
 
+        if self.is_concentrated_liquidity_active() {
+            self.perform_concentrated_swap_fixed_output(context, storage_cache);
+            return;
+        }
+
         let reserve_in = storage_cache.get_reserve_in(context.swap_tokens_order);
         let reserve_out = storage_cache.get_reserve_out(context.swap_tokens_order);
 
-        let amount_in_optimal =
-            self.get_amount_in(&context.output_token_amount, reserve_in, reserve_out);
+        let amount_in_optimal = self.get_amount_in(
+            &context.output_token_amount,
+            reserve_in,
+            reserve_out,
+            context.swap_tokens_order,
+            context.total_fee_percent,
+        );
         require!(
             amount_in_optimal <= context.input_token_amount,
             ERROR_SLIPPAGE_EXCEEDED
@@ -295,4 +399,98 @@ pub trait SwapModule:
         *storage_cache.get_mut_reserve_out(context.swap_tokens_order) -=
             &context.final_output_amount;
     }
+
+    fn perform_concentrated_swap_fixed_input(
+        &self,
+        context: &mut SwapContext<Self::Api>,
+        storage_cache: &mut StorageCache<Self>,
+    ) {
+        let total_fee_percent = context.total_fee_percent;
+        let amount_in_with_fee =
+            &context.input_token_amount * (MAX_PERCENTAGE - total_fee_percent) / MAX_PERCENTAGE;
+        require!(amount_in_with_fee != 0u64, ERROR_ZERO_AMOUNT);
+
+        let result = self.simulate_concentrated_swap(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            context.swap_tokens_order,
+            &amount_in_with_fee,
+            true,
+            total_fee_percent,
+            self.get_lp_fee_percent(total_fee_percent),
+        );
+        require!(
+            result.amount_out >= context.output_token_amount,
+            ERROR_SLIPPAGE_EXCEEDED
+        );
+        require!(result.amount_out != 0u64, ERROR_ZERO_AMOUNT);
+
+        context.final_output_amount = result.amount_out.clone();
+        if self.is_fee_enabled() {
+            context.fee_amount = self.get_special_fee_from_input(&context.input_token_amount);
+        }
+
+        let lp_fee_amount = &context.input_token_amount - &result.amount_in - &context.fee_amount;
+        self.apply_concentrated_swap(
+            storage_cache,
+            context.swap_tokens_order,
+            result,
+            &lp_fee_amount,
+        );
+    }
+
+    fn perform_concentrated_swap_fixed_output(
+        &self,
+        context: &mut SwapContext<Self::Api>,
+        storage_cache: &mut StorageCache<Self>,
+    ) {
+        let total_fee_percent = context.total_fee_percent;
+        let result = self.simulate_concentrated_swap(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            context.swap_tokens_order,
+            &context.output_token_amount,
+            false,
+            total_fee_percent,
+            self.get_lp_fee_percent(total_fee_percent),
+        );
+
+        let amount_in_optimal = self.add_fee_to_amount_in(&result.amount_in, total_fee_percent);
+        require!(
+            amount_in_optimal <= context.input_token_amount,
+            ERROR_SLIPPAGE_EXCEEDED
+        );
+
+        context.final_input_amount = amount_in_optimal;
+        if self.is_fee_enabled() {
+            context.fee_amount = self.get_special_fee_from_input(&context.final_input_amount);
+        }
+
+        let lp_fee_amount = &context.final_input_amount - &result.amount_in - &context.fee_amount;
+        self.apply_concentrated_swap(
+            storage_cache,
+            context.swap_tokens_order,
+            result,
+            &lp_fee_amount,
+        );
+    }
+
+    /// Fee discounts are granted based on the energy of the original caller
+    /// when the swap is routed through the router contract
+    fn get_swap_fee_payer(
+        &self,
+        caller: &ManagedAddress,
+        opt_original_caller: OptionalValue<ManagedAddress>,
+    ) -> ManagedAddress {
+        match opt_original_caller {
+            OptionalValue::Some(original_caller) => {
+                require!(
+                    caller == &self.router_address().get(),
+                    ERROR_PERMISSION_DENIED
+                );
+                original_caller
+            }
+            OptionalValue::None => caller.clone(),
+        }
+    }
 }
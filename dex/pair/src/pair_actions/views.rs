@@ -1,6 +1,32 @@
-use crate::{ERROR_NOT_ENOUGH_RESERVE, ERROR_UNKNOWN_TOKEN, ERROR_ZERO_AMOUNT};
+use crate::{
+    config::MAX_PERCENTAGE, contexts::base::SwapTokensOrder,
+    safe_price_view::DEFAULT_SAFE_PRICE_ROUNDS_OFFSET, ERROR_NOT_ENOUGH_RESERVE,
+    ERROR_UNKNOWN_TOKEN, ERROR_ZERO_AMOUNT,
+};
 
 multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+pub const SWAP_QUOTE_PRICE_PRECISION: u64 = 1_000_000_000_000_000_000;
+pub const BASIS_POINTS: u64 = 10_000;
+
+/// Prices are given in output tokens for `SWAP_QUOTE_PRICE_PRECISION` units of the input token.
+/// The fees are taken from the input token, and the fees collector cut is part of the special fee.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct SwapQuote<M: ManagedTypeApi> {
+    pub amount_out: BigUint<M>,
+    pub total_fee_percent: u64,
+    pub lp_fee_amount: BigUint<M>,
+    pub special_fee_amount: BigUint<M>,
+    pub fees_collector_cut_amount: BigUint<M>,
+    pub spot_price_before: BigUint<M>,
+    pub spot_price_after: BigUint<M>,
+    pub price_impact_bps: u64,
+    /// Deviation of the spot price after the swap from the safe price, over the circuit
+    /// breaker window if set, or the default safe price offset otherwise.
+    /// `None` while there are not enough price observations for the window
+    pub safe_price_deviation_bps: Option<u64>,
+}
 
 #[multiversx_sc::module]
 pub trait ViewsModule:
@@ -16,6 +42,13 @@ pub trait ViewsModule:
     + permissions_module::PermissionsModule
     + pausable::PausableModule
     + super::common_methods::CommonMethodsModule
+    + crate::concentrated_liquidity::tick_math::TickMathModule
+    + crate::concentrated_liquidity::ticks::TicksModule
+    + crate::concentrated_liquidity::swap::ConcentratedSwapModule
+    + crate::dynamic_fee::DynamicFeeModule
+    + crate::safe_price_view::SafePriceViewModule
+    + crate::read_pair_storage::ReadPairStorageModule
+    + crate::circuit_breaker::CircuitBreakerModule
 {
     #[view(getTokensForGivenPosition)]
     fn get_tokens_for_given_position(
@@ -43,17 +76,46 @@ pub trait ViewsModule:
         let second_token_id = self.second_token_id().get();
         let first_token_reserve = self.pair_reserve(&first_token_id).get();
         let second_token_reserve = self.pair_reserve(&second_token_id).get();
+        let total_fee_percent = self.get_current_total_fee_percent();
+
+        if self.is_concentrated_liquidity_active() {
+            let swap_tokens_order = if token_in == first_token_id {
+                SwapTokensOrder::PoolOrder
+            } else if token_in == second_token_id {
+                SwapTokensOrder::ReverseOrder
+            } else {
+                sc_panic!(ERROR_UNKNOWN_TOKEN);
+            };
+
+            return self.get_concentrated_amount_out(
+                &first_token_reserve,
+                &second_token_reserve,
+                swap_tokens_order,
+                &amount_in,
+                total_fee_percent,
+            );
+        }
 
         if token_in == first_token_id {
             require!(second_token_reserve > 0u64, ERROR_NOT_ENOUGH_RESERVE);
-            let amount_out =
-                self.get_amount_out(&amount_in, &first_token_reserve, &second_token_reserve);
+            let amount_out = self.get_amount_out(
+                &amount_in,
+                &first_token_reserve,
+                &second_token_reserve,
+                SwapTokensOrder::PoolOrder,
+                total_fee_percent,
+            );
             require!(second_token_reserve > amount_out, ERROR_NOT_ENOUGH_RESERVE);
             amount_out
         } else if token_in == second_token_id {
             require!(first_token_reserve > 0u64, ERROR_NOT_ENOUGH_RESERVE);
-            let amount_out =
-                self.get_amount_out(&amount_in, &second_token_reserve, &first_token_reserve);
+            let amount_out = self.get_amount_out(
+                &amount_in,
+                &second_token_reserve,
+                &first_token_reserve,
+                SwapTokensOrder::ReverseOrder,
+                total_fee_percent,
+            );
             require!(first_token_reserve > amount_out, ERROR_NOT_ENOUGH_RESERVE);
             amount_out
         } else {
@@ -69,6 +131,25 @@ pub trait ViewsModule:
         let second_token_id = self.second_token_id().get();
         let first_token_reserve = self.pair_reserve(&first_token_id).get();
         let second_token_reserve = self.pair_reserve(&second_token_id).get();
+        let total_fee_percent = self.get_current_total_fee_percent();
+
+        if self.is_concentrated_liquidity_active() {
+            let swap_tokens_order = if token_wanted == second_token_id {
+                SwapTokensOrder::PoolOrder
+            } else if token_wanted == first_token_id {
+                SwapTokensOrder::ReverseOrder
+            } else {
+                sc_panic!(ERROR_UNKNOWN_TOKEN);
+            };
+
+            return self.get_concentrated_amount_in(
+                &first_token_reserve,
+                &second_token_reserve,
+                swap_tokens_order,
+                &amount_wanted,
+                total_fee_percent,
+            );
+        }
 
         if token_wanted == first_token_id {
             require!(
@@ -76,19 +157,184 @@ pub trait ViewsModule:
                 ERROR_NOT_ENOUGH_RESERVE
             );
 
-            self.get_amount_in(&amount_wanted, &second_token_reserve, &first_token_reserve)
+            self.get_amount_in(
+                &amount_wanted,
+                &second_token_reserve,
+                &first_token_reserve,
+                SwapTokensOrder::ReverseOrder,
+                total_fee_percent,
+            )
         } else if token_wanted == second_token_id {
             require!(
                 second_token_reserve > amount_wanted,
                 ERROR_NOT_ENOUGH_RESERVE
             );
 
-            self.get_amount_in(&amount_wanted, &first_token_reserve, &second_token_reserve)
+            self.get_amount_in(
+                &amount_wanted,
+                &first_token_reserve,
+                &second_token_reserve,
+                SwapTokensOrder::PoolOrder,
+                total_fee_percent,
+            )
         } else {
             sc_panic!(ERROR_UNKNOWN_TOKEN);
         }
     }
 
+    #[view(getSwapQuote)]
+    fn get_swap_quote(
+        &self,
+        token_in: TokenIdentifier,
+        amount_in: BigUint,
+    ) -> SwapQuote<Self::Api> {
+        let amount_out = self.get_amount_out_view(token_in.clone(), amount_in.clone());
+
+        let first_token_id = self.first_token_id().get();
+        let first_token_reserve = self.pair_reserve(&first_token_id).get();
+        let second_token_reserve = self.pair_reserve(&self.second_token_id().get()).get();
+        let (swap_tokens_order, reserve_in, reserve_out) = if token_in == first_token_id {
+            (
+                SwapTokensOrder::PoolOrder,
+                &first_token_reserve,
+                &second_token_reserve,
+            )
+        } else {
+            (
+                SwapTokensOrder::ReverseOrder,
+                &second_token_reserve,
+                &first_token_reserve,
+            )
+        };
+
+        let total_fee_percent = self.get_current_total_fee_percent();
+        let total_fee_amount = &amount_in * total_fee_percent / MAX_PERCENTAGE;
+        let special_fee_amount = if self.is_fee_enabled() {
+            self.get_special_fee_from_input(&amount_in)
+        } else {
+            BigUint::zero()
+        };
+        let fees_collector_cut_amount = if self.fees_collector_address().is_empty() {
+            BigUint::zero()
+        } else {
+            &special_fee_amount * self.fees_collector_cut_percentage().get() / MAX_PERCENTAGE
+        };
+        let lp_fee_amount = if total_fee_amount > special_fee_amount {
+            &total_fee_amount - &special_fee_amount
+        } else {
+            BigUint::zero()
+        };
+
+        let price_unit = BigUint::from(SWAP_QUOTE_PRICE_PRECISION);
+        let (
+            spot_price_before,
+            spot_price_after,
+            new_first_token_reserve,
+            new_second_token_reserve,
+        ) = if self.is_concentrated_liquidity_active() {
+            let amount_in_with_fee =
+                &amount_in * (MAX_PERCENTAGE - total_fee_percent) / MAX_PERCENTAGE;
+            let result = self.simulate_concentrated_swap(
+                &first_token_reserve,
+                &second_token_reserve,
+                swap_tokens_order,
+                &amount_in_with_fee,
+                true,
+                total_fee_percent,
+                self.get_lp_fee_percent(total_fee_percent),
+            );
+            let swap_lp_fee_amount = &amount_in - &result.amount_in - &special_fee_amount;
+            let (new_first_token_reserve, new_second_token_reserve, new_sqrt_price) = self
+                .get_concentrated_swap_final_state(swap_tokens_order, &result, &swap_lp_fee_amount);
+            let sqrt_price = self.concentrated_sqrt_price().get();
+            let (spot_price_before, spot_price_after) = match swap_tokens_order {
+                SwapTokensOrder::PoolOrder => (
+                    self.get_second_token_quote(&price_unit, &sqrt_price),
+                    self.get_second_token_quote(&price_unit, &new_sqrt_price),
+                ),
+                SwapTokensOrder::ReverseOrder => (
+                    self.get_first_token_quote(&price_unit, &sqrt_price),
+                    self.get_first_token_quote(&price_unit, &new_sqrt_price),
+                ),
+            };
+
+            (
+                spot_price_before,
+                spot_price_after,
+                new_first_token_reserve,
+                new_second_token_reserve,
+            )
+        } else {
+            let new_reserve_in = reserve_in + &amount_in - &special_fee_amount;
+            let new_reserve_out = reserve_out - &amount_out;
+            let curve = self.get_curve();
+            let spot_price_before = self.quote_for_curve(
+                curve,
+                swap_tokens_order,
+                &price_unit,
+                reserve_in,
+                reserve_out,
+            );
+            let spot_price_after = self.quote_for_curve(
+                curve,
+                swap_tokens_order,
+                &price_unit,
+                &new_reserve_in,
+                &new_reserve_out,
+            );
+
+            match swap_tokens_order {
+                SwapTokensOrder::PoolOrder => (
+                    spot_price_before,
+                    spot_price_after,
+                    new_reserve_in,
+                    new_reserve_out,
+                ),
+                SwapTokensOrder::ReverseOrder => (
+                    spot_price_before,
+                    spot_price_after,
+                    new_reserve_out,
+                    new_reserve_in,
+                ),
+            }
+        };
+        let price_impact_bps = if spot_price_before > spot_price_after {
+            ((&spot_price_before - &spot_price_after) * BASIS_POINTS / &spot_price_before)
+                .to_u64()
+                .unwrap_or(BASIS_POINTS)
+        } else {
+            0
+        };
+
+        let config_mapper = self.circuit_breaker_config();
+        let round_window = if config_mapper.is_empty() {
+            DEFAULT_SAFE_PRICE_ROUNDS_OFFSET
+        } else {
+            config_mapper.get().round_window
+        };
+        let safe_price_deviation_bps = self
+            .get_price_deviation(
+                round_window,
+                &new_first_token_reserve,
+                &new_second_token_reserve,
+            )
+            .map(|(deviation_percent, _, _)| {
+                (deviation_percent as u128 * BASIS_POINTS as u128 / MAX_PERCENTAGE as u128) as u64
+            });
+
+        SwapQuote {
+            amount_out,
+            total_fee_percent,
+            lp_fee_amount,
+            special_fee_amount,
+            fees_collector_cut_amount,
+            spot_price_before,
+            spot_price_after,
+            price_impact_bps,
+            safe_price_deviation_bps,
+        }
+    }
+
     #[view(getEquivalent)]
     fn get_equivalent(&self, token_in: TokenIdentifier, amount_in: BigUint) -> BigUint {
         require!(amount_in > 0u64, ERROR_ZERO_AMOUNT);
@@ -102,10 +348,23 @@ pub trait ViewsModule:
             return zero;
         }
 
+        let curve = self.get_curve();
         if token_in == first_token_id {
-            self.quote(&amount_in, &first_token_reserve, &second_token_reserve)
+            self.quote_for_curve(
+                curve,
+                SwapTokensOrder::PoolOrder,
+                &amount_in,
+                &first_token_reserve,
+                &second_token_reserve,
+            )
         } else if token_in == second_token_id {
-            self.quote(&amount_in, &second_token_reserve, &first_token_reserve)
+            self.quote_for_curve(
+                curve,
+                SwapTokensOrder::ReverseOrder,
+                &amount_in,
+                &second_token_reserve,
+                &first_token_reserve,
+            )
         } else {
             sc_panic!(ERROR_UNKNOWN_TOKEN);
         }
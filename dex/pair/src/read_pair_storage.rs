@@ -1,6 +1,10 @@
 use multiversx_sc::storage::StorageKey;
 
-use crate::safe_price::PriceObservation;
+use crate::{
+    config::Curve,
+    safe_price::{CompactPriceObservation, PriceObservation},
+};
+use pausable::State;
 
 multiversx_sc::imports!();
 
@@ -9,7 +13,14 @@ pub static FIRST_TOKEN_ID_STORAGE_KEY: &[u8] = b"first_token_id";
 pub static SECOND_TOKEN_ID_STORAGE_KEY: &[u8] = b"second_token_id";
 pub static SAFE_PRICE_CURRENT_INDEX_STORAGE_KEY: &[u8] = b"safe_price_current_index";
 pub static PRICE_OBSERVATIONS_STORAGE_KEY: &[u8] = b"price_observations";
+pub static OBSERVATION_CARDINALITY_STORAGE_KEY: &[u8] = b"observationCardinality";
+pub static OBSERVATION_BASE_STORAGE_KEY: &[u8] = b"observationBase";
 pub static PAIR_RESERVE_BASE_STORAGE_KEY: &[u8] = b"reserve";
+pub static CURVE_STORAGE_KEY: &[u8] = b"curve";
+pub static TOTAL_FEE_PERCENT_STORAGE_KEY: &[u8] = b"total_fee_percent";
+pub static STATE_STORAGE_KEY: &[u8] = b"state";
+pub static CONCENTRATED_POSITIONS_COUNT_STORAGE_KEY: &[u8] = b"concentratedPositionsCount";
+pub static TEMPLATE_VERSION_STORAGE_KEY: &[u8] = b"templateVersion";
 
 #[multiversx_sc::module]
 pub trait ReadPairStorageModule {
@@ -53,16 +64,49 @@ pub trait ReadPairStorageModule {
         )
     }
 
+    fn get_observation_cardinality_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<usize, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(OBSERVATION_CARDINALITY_STORAGE_KEY),
+        )
+    }
+
     fn get_price_observation_mapper(
         &self,
         pair_address: ManagedAddress,
-    ) -> VecMapper<PriceObservation<Self::Api>, ManagedAddress> {
+    ) -> VecMapper<CompactPriceObservation<Self::Api>, ManagedAddress> {
         VecMapper::<_, _, ManagedAddress>::new_from_address(
             pair_address,
             StorageKey::new(PRICE_OBSERVATIONS_STORAGE_KEY),
         )
     }
 
+    fn get_observation_base_mapper(
+        &self,
+        pair_address: ManagedAddress,
+        base_slot: u8,
+    ) -> SingleValueMapper<PriceObservation<Self::Api>, ManagedAddress> {
+        let mut storage_key = StorageKey::new(OBSERVATION_BASE_STORAGE_KEY);
+        storage_key.append_item(&base_slot);
+
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(pair_address, storage_key)
+    }
+
+    fn get_pair_price_observation_at(
+        &self,
+        pair_address: &ManagedAddress,
+        price_observations: &VecMapper<CompactPriceObservation<Self::Api>, ManagedAddress>,
+        index: usize,
+    ) -> PriceObservation<Self::Api> {
+        price_observations.get(index).resolve(|base_slot| {
+            self.get_observation_base_mapper(pair_address.clone(), base_slot)
+                .get()
+        })
+    }
+
     fn get_pair_reserve_mapper(
         &self,
         pair_address: ManagedAddress,
@@ -73,4 +117,63 @@ pub trait ReadPairStorageModule {
 
         SingleValueMapper::<_, _, ManagedAddress>::new_from_address(pair_address, storage_key)
     }
+
+    fn get_curve_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<Curve, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(CURVE_STORAGE_KEY),
+        )
+    }
+
+    fn get_total_fee_percent_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<u64, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(TOTAL_FEE_PERCENT_STORAGE_KEY),
+        )
+    }
+
+    fn get_state_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<State, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(STATE_STORAGE_KEY),
+        )
+    }
+
+    fn get_concentrated_positions_count_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<u64, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(CONCENTRATED_POSITIONS_COUNT_STORAGE_KEY),
+        )
+    }
+
+    fn get_template_version_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<u64, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(TEMPLATE_VERSION_STORAGE_KEY),
+        )
+    }
+
+    fn get_pair_curve(&self, pair_address: ManagedAddress) -> Curve {
+        let curve_mapper = self.get_curve_mapper(pair_address);
+        if curve_mapper.is_empty() {
+            return Curve::ConstantProduct;
+        }
+
+        curve_mapper.get()
+    }
 }
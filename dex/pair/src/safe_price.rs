@@ -1,13 +1,24 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
-use multiversx_sc::codec::{NestedDecodeInput, TopDecodeInput};
+use multiversx_sc::codec::{EncodeError, NestedDecodeInput, TopDecodeInput, TopEncodeOutput};
 
-use crate::{amm, config, errors::ERROR_SAFE_PRICE_CURRENT_INDEX};
+use crate::{
+    amm,
+    concentrated_liquidity::tick_math,
+    config,
+    errors::{ERROR_BAD_OBSERVATION_CARDINALITY, ERROR_SAFE_PRICE_CURRENT_INDEX},
+};
 
 pub type Round = u64;
 
 pub const MAX_OBSERVATIONS: usize = 65_536; // 2^{16} records, to optimise binary search
+pub const MAX_OBSERVATIONS_REMOVED_PER_UPDATE: usize = 10;
+pub const MIN_OBSERVATIONS: usize = 2;
+pub const TWAP_PRICE_PRECISION: u64 = 1_000_000_000_000_000_000;
+
+const COMPACT_OBSERVATION_VERSION: u8 = 1;
+const COMPACT_BIG_UINT_MAX_LEN: usize = u8::MAX as usize;
 
 #[derive(ManagedVecItem, Clone, TopEncode, NestedEncode, TypeAbi, Debug)]
 pub struct PriceObservation<M: ManagedTypeApi> {
@@ -16,6 +27,20 @@ pub struct PriceObservation<M: ManagedTypeApi> {
     pub weight_accumulated: u64,
     pub recording_round: Round,
     pub lp_supply_accumulated: BigUint<M>,
+    /// Price of the first token in second tokens, scaled by `TWAP_PRICE_PRECISION`
+    pub first_token_price_accumulated: BigUint<M>,
+    /// Price of the second token in first tokens, scaled by `TWAP_PRICE_PRECISION`
+    pub second_token_price_accumulated: BigUint<M>,
+    /// Sum of the ticks of the first token price, used for the geometric mean
+    pub tick_accumulated: i64,
+}
+
+impl<M: ManagedTypeApi> PriceObservation<M> {
+    /// Observations recorded before the price accumulators were added
+    /// can't be used as the start of a TWAP window
+    pub fn has_price_accumulators(&self) -> bool {
+        self.first_token_price_accumulated > 0u64
+    }
 }
 
 impl<M: ManagedTypeApi> Default for PriceObservation<M> {
@@ -26,6 +51,9 @@ impl<M: ManagedTypeApi> Default for PriceObservation<M> {
             weight_accumulated: 0,
             recording_round: 0,
             lp_supply_accumulated: BigUint::zero(),
+            first_token_price_accumulated: BigUint::zero(),
+            second_token_price_accumulated: BigUint::zero(),
+            tick_accumulated: 0,
         }
     }
 }
@@ -53,6 +81,17 @@ impl<M: ManagedTypeApi> NestedDecode for PriceObservation<M> {
             BigUint::zero()
         };
 
+        let (first_token_price_accumulated, second_token_price_accumulated, tick_accumulated) =
+            if !input.is_depleted() {
+                (
+                    BigUint::dep_decode(input)?,
+                    BigUint::dep_decode(input)?,
+                    i64::dep_decode(input)?,
+                )
+            } else {
+                (BigUint::zero(), BigUint::zero(), 0)
+            };
+
         if !input.is_depleted() {
             return Result::Err(DecodeError::INPUT_TOO_LONG);
         }
@@ -63,18 +102,301 @@ impl<M: ManagedTypeApi> NestedDecode for PriceObservation<M> {
             weight_accumulated,
             recording_round,
             lp_supply_accumulated,
+            first_token_price_accumulated,
+            second_token_price_accumulated,
+            tick_accumulated,
+        })
+    }
+}
+
+impl<M: ManagedTypeApi> PriceObservation<M> {
+    fn delta_from(&self, base: &Self) -> Self {
+        PriceObservation {
+            first_token_reserve_accumulated: &self.first_token_reserve_accumulated
+                - &base.first_token_reserve_accumulated,
+            second_token_reserve_accumulated: &self.second_token_reserve_accumulated
+                - &base.second_token_reserve_accumulated,
+            weight_accumulated: self.weight_accumulated - base.weight_accumulated,
+            recording_round: self.recording_round - base.recording_round,
+            lp_supply_accumulated: &self.lp_supply_accumulated - &base.lp_supply_accumulated,
+            first_token_price_accumulated: &self.first_token_price_accumulated
+                - &base.first_token_price_accumulated,
+            second_token_price_accumulated: &self.second_token_price_accumulated
+                - &base.second_token_price_accumulated,
+            tick_accumulated: self.tick_accumulated - base.tick_accumulated,
+        }
+    }
+
+    fn add_delta(mut self, delta: Self) -> Self {
+        self.first_token_reserve_accumulated += delta.first_token_reserve_accumulated;
+        self.second_token_reserve_accumulated += delta.second_token_reserve_accumulated;
+        self.weight_accumulated += delta.weight_accumulated;
+        self.recording_round += delta.recording_round;
+        self.lp_supply_accumulated += delta.lp_supply_accumulated;
+        self.first_token_price_accumulated += delta.first_token_price_accumulated;
+        self.second_token_price_accumulated += delta.second_token_price_accumulated;
+        self.tick_accumulated += delta.tick_accumulated;
+
+        self
+    }
+}
+
+/// Storage representation of a `PriceObservation`.
+/// `Full` observations use the nested `PriceObservation` format, which every reader understands.
+/// `Delta` observations only store the difference to one of the two observation bases:
+/// a version byte, the base slot, LEB128 varints for the rounds, weight and tick
+/// and big integers with a single length byte.
+/// The nested format always starts with a zero byte, the high byte of a big integer length,
+/// so the two formats can't be mistaken for each other.
+#[derive(Clone)]
+pub enum CompactPriceObservation<M: ManagedTypeApi> {
+    Full(PriceObservation<M>),
+    Delta {
+        base_slot: u8,
+        delta: PriceObservation<M>,
+    },
+}
+
+impl<M: ManagedTypeApi> CompactPriceObservation<M> {
+    pub fn base_slot(&self) -> Option<u8> {
+        match self {
+            CompactPriceObservation::Full(_) => None,
+            CompactPriceObservation::Delta { base_slot, .. } => Some(*base_slot),
+        }
+    }
+
+    /// Rebuilds the full observation, reading the base of a delta with `get_base`
+    pub fn resolve<F>(self, get_base: F) -> PriceObservation<M>
+    where
+        F: FnOnce(u8) -> PriceObservation<M>,
+    {
+        match self {
+            CompactPriceObservation::Full(observation) => observation,
+            CompactPriceObservation::Delta { base_slot, delta } => {
+                get_base(base_slot).add_delta(delta)
+            }
+        }
+    }
+}
+
+impl<M: ManagedTypeApi> TopEncode for CompactPriceObservation<M> {
+    fn top_encode<O>(&self, output: O) -> Result<(), EncodeError>
+    where
+        O: TopEncodeOutput,
+    {
+        let (base_slot, delta) = match self {
+            CompactPriceObservation::Full(observation) => return observation.top_encode(output),
+            CompactPriceObservation::Delta { base_slot, delta } => (base_slot, delta),
+        };
+
+        let mut buffer = ManagedBuffer::<M>::new();
+        buffer.append_bytes(&[COMPACT_OBSERVATION_VERSION, *base_slot]);
+        append_varint(&mut buffer, delta.recording_round);
+        append_varint(
+            &mut buffer,
+            delta.recording_round.wrapping_sub(delta.weight_accumulated),
+        );
+        for value in [
+            &delta.first_token_reserve_accumulated,
+            &delta.second_token_reserve_accumulated,
+            &delta.lp_supply_accumulated,
+            &delta.first_token_price_accumulated,
+            &delta.second_token_price_accumulated,
+        ] {
+            let bytes = value.to_bytes_be_buffer();
+            if bytes.len() > COMPACT_BIG_UINT_MAX_LEN {
+                return Result::Err(EncodeError::from("accumulator too large"));
+            }
+
+            buffer.append_bytes(&[bytes.len() as u8]);
+            buffer.append(&bytes);
+        }
+        let tick_accumulated = delta.tick_accumulated;
+        append_varint(
+            &mut buffer,
+            ((tick_accumulated << 1) ^ (tick_accumulated >> 63)) as u64,
+        );
+
+        buffer.top_encode(output)
+    }
+}
+
+impl<M: ManagedTypeApi> TopDecode for CompactPriceObservation<M> {
+    fn top_decode<I>(input: I) -> Result<Self, DecodeError>
+    where
+        I: TopDecodeInput,
+    {
+        let buffer = ManagedBuffer::<M>::top_decode(input)?;
+        let mut reader = CompactReader {
+            buffer: &buffer,
+            position: 0,
+        };
+        if reader.read_byte()? != COMPACT_OBSERVATION_VERSION {
+            return Result::Ok(CompactPriceObservation::Full(PriceObservation::top_decode(
+                buffer.clone(),
+            )?));
+        }
+
+        let base_slot = reader.read_byte()?;
+        let recording_round = reader.read_varint()?;
+        let weight_accumulated = recording_round.wrapping_sub(reader.read_varint()?);
+        let first_token_reserve_accumulated = reader.read_big_uint()?;
+        let second_token_reserve_accumulated = reader.read_big_uint()?;
+        let lp_supply_accumulated = reader.read_big_uint()?;
+        let first_token_price_accumulated = reader.read_big_uint()?;
+        let second_token_price_accumulated = reader.read_big_uint()?;
+        let zigzag_tick = reader.read_varint()?;
+        let tick_accumulated = (zigzag_tick >> 1) as i64 ^ -((zigzag_tick & 1) as i64);
+
+        if reader.position != buffer.len() {
+            return Result::Err(DecodeError::INPUT_TOO_LONG);
+        }
+
+        Result::Ok(CompactPriceObservation::Delta {
+            base_slot,
+            delta: PriceObservation {
+                first_token_reserve_accumulated,
+                second_token_reserve_accumulated,
+                weight_accumulated,
+                recording_round,
+                lp_supply_accumulated,
+                first_token_price_accumulated,
+                second_token_price_accumulated,
+                tick_accumulated,
+            },
         })
     }
 }
 
+fn append_varint<M: ManagedTypeApi>(buffer: &mut ManagedBuffer<M>, mut value: u64) {
+    let mut bytes = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes[len] = byte;
+            len += 1;
+            break;
+        }
+
+        bytes[len] = byte | 0x80;
+        len += 1;
+    }
+
+    buffer.append_bytes(&bytes[..len]);
+}
+
+struct CompactReader<'a, M: ManagedTypeApi> {
+    buffer: &'a ManagedBuffer<M>,
+    position: usize,
+}
+
+impl<M: ManagedTypeApi> CompactReader<'_, M> {
+    fn read_byte(&mut self) -> Result<u8, DecodeError> {
+        let mut byte = [0u8; 1];
+        if self.buffer.load_slice(self.position, &mut byte).is_err() {
+            return Result::Err(DecodeError::INPUT_TOO_SHORT);
+        }
+
+        self.position += 1;
+        Result::Ok(byte[0])
+    }
+
+    fn read_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_byte()?;
+            if shift > 63 {
+                return Result::Err(DecodeError::INPUT_OUT_OF_RANGE);
+            }
+
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Result::Ok(value);
+            }
+
+            shift += 7;
+        }
+    }
+
+    fn read_big_uint(&mut self) -> Result<BigUint<M>, DecodeError> {
+        let len = self.read_byte()? as usize;
+        let bytes = match self.buffer.copy_slice(self.position, len) {
+            Some(bytes) => bytes,
+            None => return Result::Err(DecodeError::INPUT_TOO_SHORT),
+        };
+
+        self.position += len;
+        Result::Ok(BigUint::from_bytes_be_buffer(&bytes))
+    }
+}
+
 #[multiversx_sc::module]
 pub trait SafePriceModule:
     config::ConfigModule
     + token_send::TokenSendModule
     + amm::AmmModule
+    + tick_math::TickMathModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
 {
+    /// Sets how many price observations the pair keeps.
+    /// When the observations already wrapped around, a bigger cardinality is applied
+    /// once the write index reaches the end of the current ring, to keep the observations sorted.
+    /// A smaller cardinality is applied once the write index reaches it, dropping the older observations
+    /// past it. The dropped entries are then removed from storage a few at a time, on each new observation.
+    #[endpoint(setObservationCardinality)]
+    fn set_observation_cardinality(&self, cardinality: usize) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            (MIN_OBSERVATIONS..=MAX_OBSERVATIONS).contains(&cardinality),
+            ERROR_BAD_OBSERVATION_CARDINALITY
+        );
+
+        let current_cardinality = self.get_observation_cardinality();
+        let observations_len = self.price_observations().len();
+        let current_index = self.safe_price_current_index().get();
+        if cardinality < current_cardinality {
+            let ring_len = core::cmp::min(observations_len, current_cardinality);
+            if ring_len <= cardinality {
+                self.observation_cardinality().set(cardinality);
+            } else if observations_len < current_cardinality {
+                // the ring is not full yet, so it wraps at its current length until the shrink is applied
+                self.observation_cardinality().set(observations_len);
+            }
+        } else if current_index == observations_len {
+            self.observation_cardinality().set(cardinality);
+        }
+
+        self.observation_cardinality_next().set(cardinality);
+    }
+
+    #[view(getObservationCardinality)]
+    fn get_observation_cardinality_view(&self) -> MultiValue2<usize, usize> {
+        let cardinality = self.get_observation_cardinality();
+        let cardinality_next_mapper = self.observation_cardinality_next();
+        let cardinality_next = if cardinality_next_mapper.is_empty() {
+            cardinality
+        } else {
+            cardinality_next_mapper.get()
+        };
+
+        (cardinality, cardinality_next).into()
+    }
+
+    /// Starts storing new price observations as deltas, which is cheaper on every swap.
+    /// Readers that don't know the delta format fail to decode them, so the safe price view
+    /// and every contract embedding `SafePriceViewModule` must be upgraded before this is enabled.
+    /// Once enabled, it can't be disabled.
+    #[endpoint(enableCompactPriceObservations)]
+    fn enable_compact_price_observations(&self) {
+        self.require_caller_has_owner_permissions();
+
+        self.compact_price_observations_enabled().set(true);
+    }
+
     fn update_safe_price(
         &self,
         first_token_reserve: &BigUint,
@@ -94,16 +416,24 @@ pub trait SafePriceModule:
 
         let mut price_observations = self.price_observations();
         let mut last_price_observation = PriceObservation::default();
-        let mut new_index = 1;
+        let mut last_base_slot = None;
         if !price_observations.is_empty() {
-            last_price_observation = price_observations.get(safe_price_current_index);
-            new_index = (safe_price_current_index % MAX_OBSERVATIONS) + 1;
+            let last_entry = price_observations.get(safe_price_current_index);
+            last_base_slot = last_entry.base_slot();
+            last_price_observation = last_entry.resolve(|slot| self.observation_base(slot).get());
         }
 
         if last_price_observation.recording_round == current_round {
             return;
         }
 
+        let new_index = if price_observations.is_empty() {
+            1
+        } else {
+            let cardinality = self.apply_observation_cardinality_next(safe_price_current_index);
+            (safe_price_current_index % cardinality) + 1
+        };
+
         let new_price_observation = self.compute_new_observation(
             current_round,
             first_token_reserve,
@@ -112,13 +442,104 @@ pub trait SafePriceModule:
             &last_price_observation,
         );
 
-        if price_observations.len() == MAX_OBSERVATIONS {
-            price_observations.set(new_index, &new_price_observation);
+        let compact_price_observation = self.to_stored_price_observation(
+            new_index,
+            last_base_slot,
+            last_price_observation,
+            new_price_observation,
+        );
+        if new_index <= price_observations.len() {
+            price_observations.set(new_index, &compact_price_observation);
         } else {
-            price_observations.push(&new_price_observation);
+            price_observations.push(&compact_price_observation);
         }
 
         self.safe_price_current_index().set(new_index);
+        self.remove_dropped_price_observations(&mut price_observations);
+    }
+
+    /// Delta observations are relative to the observation recorded right before the current lap of the ring.
+    /// A lap rewrites every entry of the ring before the next one starts,
+    /// so only the bases of the current and of the previous lap are still referenced.
+    fn to_stored_price_observation(
+        &self,
+        new_index: usize,
+        last_base_slot: Option<u8>,
+        last_price_observation: PriceObservation<Self::Api>,
+        new_price_observation: PriceObservation<Self::Api>,
+    ) -> CompactPriceObservation<Self::Api> {
+        if !self.compact_price_observations_enabled().get() {
+            return CompactPriceObservation::Full(new_price_observation);
+        }
+
+        let (base_slot, base) = match last_base_slot {
+            Some(slot) if new_index != 1 => (slot, self.observation_base(slot).get()),
+            Some(slot) => (1 - slot, last_price_observation),
+            None => (0, last_price_observation),
+        };
+        if last_base_slot != Some(base_slot) {
+            self.observation_base(base_slot).set(&base);
+        }
+
+        CompactPriceObservation::Delta {
+            base_slot,
+            delta: new_price_observation.delta_from(&base),
+        }
+    }
+
+    fn get_price_observation_at(&self, index: usize) -> PriceObservation<Self::Api> {
+        self.price_observations()
+            .get(index)
+            .resolve(|slot| self.observation_base(slot).get())
+    }
+
+    fn get_observation_cardinality(&self) -> usize {
+        let cardinality_mapper = self.observation_cardinality();
+        if cardinality_mapper.is_empty() {
+            return MAX_OBSERVATIONS;
+        }
+
+        cardinality_mapper.get()
+    }
+
+    fn apply_observation_cardinality_next(&self, current_index: usize) -> usize {
+        let cardinality = self.get_observation_cardinality();
+        let cardinality_next_mapper = self.observation_cardinality_next();
+        if cardinality_next_mapper.is_empty() {
+            return cardinality;
+        }
+
+        // the observations past the write index are the oldest ones,
+        // so a smaller ring is applied by wrapping around right after it
+        let cardinality_next = cardinality_next_mapper.get();
+        if cardinality_next < cardinality && current_index == cardinality_next {
+            self.observation_cardinality().set(cardinality_next);
+            return cardinality_next;
+        }
+
+        if current_index != cardinality || cardinality_next <= cardinality {
+            return cardinality;
+        }
+
+        self.observation_cardinality().set(cardinality_next);
+        cardinality_next
+    }
+
+    /// Removes the entries left past the cardinality after it was lowered.
+    /// They are no longer read, so only a bounded number is removed per call.
+    fn remove_dropped_price_observations(
+        &self,
+        price_observations: &mut VecMapper<CompactPriceObservation<Self::Api>>,
+    ) {
+        let cardinality = self.get_observation_cardinality();
+        for _ in 0..MAX_OBSERVATIONS_REMOVED_PER_UPDATE {
+            let observations_len = price_observations.len();
+            if observations_len <= cardinality {
+                return;
+            }
+
+            price_observations.swap_remove(observations_len);
+        }
     }
 
     fn compute_new_observation(
@@ -141,6 +562,15 @@ pub trait SafePriceModule:
         new_price_observation.second_token_reserve_accumulated +=
             BigUint::from(new_weight) * new_second_reserve;
         new_price_observation.lp_supply_accumulated += BigUint::from(new_weight) * new_lp_supply;
+
+        let price_precision = BigUint::from(TWAP_PRICE_PRECISION);
+        new_price_observation.first_token_price_accumulated +=
+            BigUint::from(new_weight) * new_second_reserve * &price_precision / new_first_reserve;
+        new_price_observation.second_token_price_accumulated +=
+            BigUint::from(new_weight) * new_first_reserve * &price_precision / new_second_reserve;
+        let sqrt_price = self.get_sqrt_price_from_reserves(new_first_reserve, new_second_reserve);
+        let tick = self.get_tick_at_sqrt_price(&sqrt_price);
+        new_price_observation.tick_accumulated += new_weight as i64 * tick as i64;
         new_price_observation.weight_accumulated += new_weight;
         new_price_observation.recording_round = new_round;
 
@@ -148,9 +578,22 @@ pub trait SafePriceModule:
     }
 
     #[storage_mapper("price_observations")]
-    fn price_observations(&self) -> VecMapper<PriceObservation<Self::Api>>;
+    fn price_observations(&self) -> VecMapper<CompactPriceObservation<Self::Api>>;
 
     #[view(getSafePriceCurrentIndex)]
     #[storage_mapper("safe_price_current_index")]
     fn safe_price_current_index(&self) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("observationCardinality")]
+    fn observation_cardinality(&self) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("observationCardinalityNext")]
+    fn observation_cardinality_next(&self) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("observationBase")]
+    fn observation_base(&self, base_slot: u8) -> SingleValueMapper<PriceObservation<Self::Api>>;
+
+    #[view(areCompactPriceObservationsEnabled)]
+    #[storage_mapper("compactPriceObservationsEnabled")]
+    fn compact_price_observations_enabled(&self) -> SingleValueMapper<bool>;
 }
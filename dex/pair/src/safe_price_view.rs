@@ -4,10 +4,16 @@ use common_errors::{ERROR_BAD_INPUT_TOKEN, ERROR_PARAMETERS};
 use core::cmp::Ordering;
 
 use crate::{
-    amm, config,
-    errors::{ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST, ERROR_SAFE_PRICE_SAME_ROUNDS},
+    amm,
+    concentrated_liquidity::tick_math::{self, SQRT_PRICE_PRECISION},
+    config::{self, Curve},
+    contexts::base::SwapTokensOrder,
+    errors::{
+        ERROR_FAIR_PRICE_CURVE, ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST,
+        ERROR_SAFE_PRICE_SAME_ROUNDS, ERROR_TWAP_NOT_AVAILABLE,
+    },
     read_pair_storage,
-    safe_price::{self, PriceObservation, Round, MAX_OBSERVATIONS},
+    safe_price::{self, CompactPriceObservation, PriceObservation, Round, TWAP_PRICE_PRECISION},
 };
 
 pub const DEFAULT_SAFE_PRICE_ROUNDS_OFFSET: u64 = 10 * 60;
@@ -25,6 +31,7 @@ pub trait SafePriceViewModule:
     + config::ConfigModule
     + token_send::TokenSendModule
     + amm::AmmModule
+    + tick_math::TickMathModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
     + read_pair_storage::ReadPairStorageModule
@@ -99,8 +106,11 @@ pub trait SafePriceViewModule:
             .get();
         let price_observations = self.get_price_observation_mapper(pair_address.clone());
 
-        let oldest_price_observation =
-            self.get_oldest_price_observation(safe_price_current_index, &price_observations);
+        let oldest_price_observation = self.get_oldest_price_observation(
+            &pair_address,
+            safe_price_current_index,
+            &price_observations,
+        );
 
         require!(
             start_round >= oldest_price_observation.recording_round,
@@ -215,8 +225,11 @@ pub trait SafePriceViewModule:
             .get();
         let price_observations = self.get_price_observation_mapper(pair_address.clone());
 
-        let oldest_price_observation =
-            self.get_oldest_price_observation(safe_price_current_index, &price_observations);
+        let oldest_price_observation = self.get_oldest_price_observation(
+            &pair_address,
+            safe_price_current_index,
+            &price_observations,
+        );
         require!(
             oldest_price_observation.recording_round <= start_round,
             ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST
@@ -249,6 +262,188 @@ pub trait SafePriceViewModule:
         )
     }
 
+    /// Time weighted average of the spot price between the two rounds
+    #[label("safe-price-view")]
+    #[view(getArithmeticTwap)]
+    fn get_arithmetic_twap(
+        &self,
+        pair_address: ManagedAddress,
+        start_round: Round,
+        end_round: Round,
+        input_payment: EsdtTokenPayment,
+    ) -> EsdtTokenPayment {
+        let (first_price_observation, last_price_observation) =
+            self.get_twap_price_observations(&pair_address, start_round, end_round);
+        let weight_diff =
+            last_price_observation.weight_accumulated - first_price_observation.weight_accumulated;
+        require!(weight_diff > 0, ERROR_SAFE_PRICE_SAME_ROUNDS);
+
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address).get();
+        let (output_token_id, price_accumulated_diff) =
+            if input_payment.token_identifier == first_token_id {
+                (
+                    second_token_id,
+                    last_price_observation.first_token_price_accumulated
+                        - first_price_observation.first_token_price_accumulated,
+                )
+            } else if input_payment.token_identifier == second_token_id {
+                (
+                    first_token_id,
+                    last_price_observation.second_token_price_accumulated
+                        - first_price_observation.second_token_price_accumulated,
+                )
+            } else {
+                sc_panic!(ERROR_BAD_INPUT_TOKEN);
+            };
+
+        let output_amount =
+            input_payment.amount * price_accumulated_diff / weight_diff / TWAP_PRICE_PRECISION;
+        EsdtTokenPayment::new(output_token_id, 0, output_amount)
+    }
+
+    /// Time weighted geometric mean of the spot price between the two rounds,
+    /// with the precision of a price tick
+    #[label("safe-price-view")]
+    #[view(getGeometricTwap)]
+    fn get_geometric_twap(
+        &self,
+        pair_address: ManagedAddress,
+        start_round: Round,
+        end_round: Round,
+        input_payment: EsdtTokenPayment,
+    ) -> EsdtTokenPayment {
+        let (first_price_observation, last_price_observation) =
+            self.get_twap_price_observations(&pair_address, start_round, end_round);
+        let sqrt_price =
+            self.get_geometric_twap_sqrt_price(&first_price_observation, &last_price_observation);
+        let price_scaled = &sqrt_price * &sqrt_price;
+        let precision_scaled = BigUint::from(SQRT_PRICE_PRECISION).pow(2);
+
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address).get();
+        if input_payment.token_identifier == first_token_id {
+            let output_amount = input_payment.amount * price_scaled / precision_scaled;
+            EsdtTokenPayment::new(second_token_id, 0, output_amount)
+        } else if input_payment.token_identifier == second_token_id {
+            let output_amount = input_payment.amount * precision_scaled / price_scaled;
+            EsdtTokenPayment::new(first_token_id, 0, output_amount)
+        } else {
+            sc_panic!(ERROR_BAD_INPUT_TOKEN);
+        }
+    }
+
+    #[label("safe-price-view")]
+    #[view(getArithmeticTwapByTimestamps)]
+    fn get_arithmetic_twap_by_timestamps(
+        &self,
+        pair_address: ManagedAddress,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        input_payment: EsdtTokenPayment,
+    ) -> EsdtTokenPayment {
+        let start_round = self.get_round_at_timestamp(start_timestamp);
+        let end_round = self.get_round_at_timestamp(end_timestamp);
+        self.get_arithmetic_twap(pair_address, start_round, end_round, input_payment)
+    }
+
+    #[label("safe-price-view")]
+    #[view(getGeometricTwapByTimestamps)]
+    fn get_geometric_twap_by_timestamps(
+        &self,
+        pair_address: ManagedAddress,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        input_payment: EsdtTokenPayment,
+    ) -> EsdtTokenPayment {
+        let start_round = self.get_round_at_timestamp(start_timestamp);
+        let end_round = self.get_round_at_timestamp(end_timestamp);
+        self.get_geometric_twap(pair_address, start_round, end_round, input_payment)
+    }
+
+    /// Values the LP tokens at the geometric TWAP, using the current invariant.
+    /// Moving the reserves within the window does not change the result,
+    /// since the invariant stays the same.
+    #[label("safe-price-view")]
+    #[view(getLpTokensFairPrice)]
+    fn get_lp_tokens_fair_price(
+        &self,
+        pair_address: ManagedAddress,
+        start_round: Round,
+        end_round: Round,
+        liquidity: BigUint,
+    ) -> MultiValue2<EsdtTokenPayment, EsdtTokenPayment> {
+        self.require_constant_product_pair(&pair_address);
+
+        let (first_price_observation, last_price_observation) =
+            self.get_twap_price_observations(&pair_address, start_round, end_round);
+        let sqrt_price =
+            self.get_geometric_twap_sqrt_price(&first_price_observation, &last_price_observation);
+
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address.clone()).get();
+        let lp_supply = self.get_lp_token_supply_mapper(pair_address.clone()).get();
+        if lp_supply == 0 {
+            return (
+                EsdtTokenPayment::new(first_token_id, 0, BigUint::zero()),
+                EsdtTokenPayment::new(second_token_id, 0, BigUint::zero()),
+            )
+                .into();
+        }
+
+        let invariant_root = self.get_pair_invariant_root(&pair_address);
+
+        // the reserves the pool would have at the TWAP price, with the same invariant
+        let precision = BigUint::from(SQRT_PRICE_PRECISION);
+        let fair_first_token_reserve = &invariant_root * &precision / &sqrt_price;
+        let fair_second_token_reserve = &invariant_root * &sqrt_price / &precision;
+
+        let first_token_worth = &liquidity * &fair_first_token_reserve / &lp_supply;
+        let second_token_worth = &liquidity * &fair_second_token_reserve / &lp_supply;
+        let first_token_payment = EsdtTokenPayment::new(first_token_id, 0, first_token_worth);
+        let second_token_payment = EsdtTokenPayment::new(second_token_id, 0, second_token_worth);
+
+        (first_token_payment, second_token_payment).into()
+    }
+
+    #[label("safe-price-view")]
+    #[view(getLpTokensFairPriceByDefaultOffset)]
+    fn get_lp_tokens_fair_price_by_default_offset(
+        &self,
+        pair_address: ManagedAddress,
+        liquidity: BigUint,
+    ) -> MultiValue2<EsdtTokenPayment, EsdtTokenPayment> {
+        let current_round = self.blockchain().get_block_round();
+        let default_offset_rounds = self.get_default_offset_rounds(&pair_address, current_round);
+        let start_round = current_round - default_offset_rounds;
+
+        self.get_lp_tokens_fair_price(pair_address, start_round, current_round, liquidity)
+    }
+
+    /// 2 * sqrt(k * p0 * p1) * liquidity / lp_supply, with externally supplied token prices.
+    /// The prices can use any unit, as long as it is the same for both tokens.
+    #[label("safe-price-view")]
+    #[view(getLpTokensFairValue)]
+    fn get_lp_tokens_fair_value(
+        &self,
+        pair_address: ManagedAddress,
+        liquidity: BigUint,
+        first_token_price: BigUint,
+        second_token_price: BigUint,
+    ) -> BigUint {
+        self.require_constant_product_pair(&pair_address);
+
+        let lp_supply = self.get_lp_token_supply_mapper(pair_address.clone()).get();
+        if lp_supply == 0 {
+            return BigUint::zero();
+        }
+
+        let invariant_root = self.get_pair_invariant_root(&pair_address);
+        let prices_root = (first_token_price * second_token_price).sqrt();
+
+        liquidity * invariant_root * prices_root * 2u64 / lp_supply
+    }
+
     #[label("safe-price-view")]
     #[view(getPriceObservation)]
     fn get_price_observation_view(
@@ -263,8 +458,11 @@ pub trait SafePriceViewModule:
         let second_token_id = self.get_second_token_id_mapper(pair_address.clone()).get();
         let price_observations = self.get_price_observation_mapper(pair_address.clone());
 
-        let oldest_price_observation =
-            self.get_oldest_price_observation(safe_price_current_index, &price_observations);
+        let oldest_price_observation = self.get_oldest_price_observation(
+            &pair_address,
+            safe_price_current_index,
+            &price_observations,
+        );
         require!(
             oldest_price_observation.recording_round <= search_round,
             ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST
@@ -293,15 +491,25 @@ pub trait SafePriceViewModule:
         let weighted_amounts =
             self.compute_weighted_amounts(first_price_observation, last_price_observation);
 
+        let curve = self.get_pair_curve(pair_address.clone());
+
         if input_payment.token_identifier == first_token_id {
-            let output_amount = input_payment.amount
-                * weighted_amounts.weighted_second_token_reserve
-                / weighted_amounts.weighted_first_token_reserve;
+            let output_amount = self.quote_for_curve(
+                curve,
+                SwapTokensOrder::PoolOrder,
+                &input_payment.amount,
+                &weighted_amounts.weighted_first_token_reserve,
+                &weighted_amounts.weighted_second_token_reserve,
+            );
             EsdtTokenPayment::new(second_token_id, 0, output_amount)
         } else if input_payment.token_identifier == second_token_id {
-            let output_amount = input_payment.amount
-                * weighted_amounts.weighted_first_token_reserve
-                / weighted_amounts.weighted_second_token_reserve;
+            let output_amount = self.quote_for_curve(
+                curve,
+                SwapTokensOrder::ReverseOrder,
+                &input_payment.amount,
+                &weighted_amounts.weighted_second_token_reserve,
+                &weighted_amounts.weighted_first_token_reserve,
+            );
             EsdtTokenPayment::new(first_token_id, 0, output_amount)
         } else {
             sc_panic!(ERROR_BAD_INPUT_TOKEN);
@@ -314,7 +522,11 @@ pub trait SafePriceViewModule:
         first_token_id: &TokenIdentifier,
         second_token_id: &TokenIdentifier,
         current_index: usize,
-        price_observations: &VecMapper<Self::Api, PriceObservation<Self::Api>, ManagedAddress>,
+        price_observations: &VecMapper<
+            Self::Api,
+            CompactPriceObservation<Self::Api>,
+            ManagedAddress,
+        >,
         search_round: Round,
     ) -> PriceObservation<Self::Api> {
         require!(
@@ -323,7 +535,8 @@ pub trait SafePriceViewModule:
         );
 
         // Check if the requested price observation is the last one
-        let last_observation = price_observations.get(current_index);
+        let last_observation =
+            self.get_pair_price_observation_at(pair_address, price_observations, current_index);
         if last_observation.recording_round == search_round {
             return last_observation;
         }
@@ -354,9 +567,12 @@ pub trait SafePriceViewModule:
             );
         }
 
+        let observations_len = self.get_price_observations_len(pair_address, price_observations);
         let (mut price_observation, last_search_index) = self.price_observation_by_binary_search(
+            pair_address,
             current_index,
             price_observations,
+            observations_len,
             search_round,
         );
 
@@ -365,7 +581,9 @@ pub trait SafePriceViewModule:
         }
 
         price_observation = self.price_observation_by_linear_interpolation(
+            pair_address,
             price_observations,
+            observations_len,
             search_round,
             last_search_index,
         );
@@ -375,8 +593,13 @@ pub trait SafePriceViewModule:
 
     fn get_oldest_price_observation(
         &self,
+        pair_address: &ManagedAddress,
         current_index: usize,
-        price_observations: &VecMapper<Self::Api, PriceObservation<Self::Api>, ManagedAddress>,
+        price_observations: &VecMapper<
+            Self::Api,
+            CompactPriceObservation<Self::Api>,
+            ManagedAddress,
+        >,
     ) -> PriceObservation<Self::Api> {
         require!(
             !price_observations.is_empty(),
@@ -384,34 +607,66 @@ pub trait SafePriceViewModule:
         );
 
         // VecMapper index starts at 1
-        let mut oldest_observation_index = 1;
-        if price_observations.len() == MAX_OBSERVATIONS {
-            oldest_observation_index = (current_index % MAX_OBSERVATIONS) + 1
+        // The observation after the current one is the oldest, or the first one if the ring is not full
+        let observations_len = self.get_price_observations_len(pair_address, price_observations);
+        let oldest_observation_index = (current_index % observations_len) + 1;
+        self.get_pair_price_observation_at(
+            pair_address,
+            price_observations,
+            oldest_observation_index,
+        )
+    }
+
+    /// The number of observations in the ring.
+    /// After the cardinality is lowered, the entries past it are no longer part of the ring,
+    /// even if they were not removed from storage yet.
+    fn get_price_observations_len(
+        &self,
+        pair_address: &ManagedAddress,
+        price_observations: &VecMapper<
+            Self::Api,
+            CompactPriceObservation<Self::Api>,
+            ManagedAddress,
+        >,
+    ) -> usize {
+        let observations_len = price_observations.len();
+        let cardinality_mapper = self.get_observation_cardinality_mapper(pair_address.clone());
+        if cardinality_mapper.is_empty() {
+            return observations_len;
         }
-        price_observations.get(oldest_observation_index)
+
+        core::cmp::min(observations_len, cardinality_mapper.get())
     }
 
     fn price_observation_by_binary_search(
         &self,
+        pair_address: &ManagedAddress,
         current_index: usize,
-        price_observations: &VecMapper<Self::Api, PriceObservation<Self::Api>, ManagedAddress>,
+        price_observations: &VecMapper<
+            Self::Api,
+            CompactPriceObservation<Self::Api>,
+            ManagedAddress,
+        >,
+        observations_len: usize,
         search_round: Round,
     ) -> (PriceObservation<Self::Api>, usize) {
         let mut search_index = 1;
         let mut left_index;
         let mut right_index;
-        let observation_at_index_1 = price_observations.get(search_index);
+        let observation_at_index_1 =
+            self.get_pair_price_observation_at(pair_address, price_observations, search_index);
         if observation_at_index_1.recording_round <= search_round {
             left_index = search_index;
             right_index = current_index - 1;
         } else {
             left_index = current_index + 1;
-            right_index = price_observations.len();
+            right_index = observations_len;
         }
 
         while left_index <= right_index {
             search_index = (left_index + right_index) / 2;
-            let price_observation = price_observations.get(search_index);
+            let price_observation =
+                self.get_pair_price_observation_at(pair_address, price_observations, search_index);
             match price_observation.recording_round.cmp(&search_round) {
                 Ordering::Equal => return (price_observation, search_index),
                 Ordering::Less => left_index = search_index + 1,
@@ -424,24 +679,39 @@ pub trait SafePriceViewModule:
 
     fn price_observation_by_linear_interpolation(
         &self,
-        price_observations: &VecMapper<Self::Api, PriceObservation<Self::Api>, ManagedAddress>,
+        pair_address: &ManagedAddress,
+        price_observations: &VecMapper<
+            Self::Api,
+            CompactPriceObservation<Self::Api>,
+            ManagedAddress,
+        >,
+        observations_len: usize,
         search_round: Round,
         search_index: usize,
     ) -> PriceObservation<Self::Api> {
-        let last_found_observation = price_observations.get(search_index);
+        let last_found_observation =
+            self.get_pair_price_observation_at(pair_address, price_observations, search_index);
         let left_observation;
         let right_observation;
         if last_found_observation.recording_round < search_round {
             left_observation = last_found_observation;
-            let right_observation_index = (search_index % MAX_OBSERVATIONS) + 1;
-            right_observation = price_observations.get(right_observation_index);
+            let right_observation_index = (search_index % observations_len) + 1;
+            right_observation = self.get_pair_price_observation_at(
+                pair_address,
+                price_observations,
+                right_observation_index,
+            );
         } else {
             let left_observation_index = if search_index == 1 {
-                MAX_OBSERVATIONS
+                observations_len
             } else {
                 search_index - 1
             };
-            left_observation = price_observations.get(left_observation_index);
+            left_observation = self.get_pair_price_observation_at(
+                pair_address,
+                price_observations,
+                left_observation_index,
+            );
             right_observation = last_found_observation;
         };
 
@@ -452,6 +722,7 @@ pub trait SafePriceViewModule:
         let right_weight = search_round - left_observation.recording_round;
 
         let weight_sum = left_weight + right_weight;
+        let has_price_accumulators = left_observation.has_price_accumulators();
         let first_token_reserve_sum = BigUint::from(left_weight)
             * left_observation.first_token_reserve_accumulated
             + BigUint::from(right_weight) * right_observation.first_token_reserve_accumulated;
@@ -467,12 +738,38 @@ pub trait SafePriceViewModule:
         let weight_accumulated =
             left_observation.weight_accumulated + search_round - left_observation.recording_round;
 
+        // The price accumulators can only be interpolated if both observations have them
+        let (first_token_price_accumulated, second_token_price_accumulated, tick_accumulated) =
+            if has_price_accumulators {
+                let first_token_price_sum = BigUint::from(left_weight)
+                    * &left_observation.first_token_price_accumulated
+                    + BigUint::from(right_weight)
+                        * &right_observation.first_token_price_accumulated;
+                let second_token_price_sum = BigUint::from(left_weight)
+                    * &left_observation.second_token_price_accumulated
+                    + BigUint::from(right_weight)
+                        * &right_observation.second_token_price_accumulated;
+                let tick_sum = left_weight as i128 * left_observation.tick_accumulated as i128
+                    + right_weight as i128 * right_observation.tick_accumulated as i128;
+
+                (
+                    first_token_price_sum / weight_sum,
+                    second_token_price_sum / weight_sum,
+                    tick_sum.div_euclid(weight_sum as i128) as i64,
+                )
+            } else {
+                (BigUint::zero(), BigUint::zero(), 0)
+            };
+
         PriceObservation {
             first_token_reserve_accumulated,
             second_token_reserve_accumulated,
             weight_accumulated,
             recording_round: search_round,
             lp_supply_accumulated,
+            first_token_price_accumulated,
+            second_token_price_accumulated,
+            tick_accumulated,
         }
     }
 
@@ -517,13 +814,115 @@ pub trait SafePriceViewModule:
         }
     }
 
+    fn get_twap_price_observations(
+        &self,
+        pair_address: &ManagedAddress,
+        start_round: Round,
+        end_round: Round,
+    ) -> (PriceObservation<Self::Api>, PriceObservation<Self::Api>) {
+        require!(end_round > start_round, ERROR_PARAMETERS);
+
+        let safe_price_current_index = self
+            .get_safe_price_current_index_mapper(pair_address.clone())
+            .get();
+        let price_observations = self.get_price_observation_mapper(pair_address.clone());
+
+        let oldest_price_observation = self.get_oldest_price_observation(
+            pair_address,
+            safe_price_current_index,
+            &price_observations,
+        );
+        require!(
+            oldest_price_observation.recording_round <= start_round,
+            ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST
+        );
+
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address.clone()).get();
+        let first_price_observation = self.get_price_observation(
+            pair_address,
+            &first_token_id,
+            &second_token_id,
+            safe_price_current_index,
+            &price_observations,
+            start_round,
+        );
+        require!(
+            first_price_observation.has_price_accumulators(),
+            ERROR_TWAP_NOT_AVAILABLE
+        );
+
+        let last_price_observation = self.get_price_observation(
+            pair_address,
+            &first_token_id,
+            &second_token_id,
+            safe_price_current_index,
+            &price_observations,
+            end_round,
+        );
+
+        (first_price_observation, last_price_observation)
+    }
+
+    /// sqrt of the geometric TWAP of the first token price, scaled by `SQRT_PRICE_PRECISION`
+    fn get_geometric_twap_sqrt_price(
+        &self,
+        first_price_observation: &PriceObservation<Self::Api>,
+        last_price_observation: &PriceObservation<Self::Api>,
+    ) -> BigUint {
+        let weight_diff =
+            last_price_observation.weight_accumulated - first_price_observation.weight_accumulated;
+        require!(weight_diff > 0, ERROR_SAFE_PRICE_SAME_ROUNDS);
+
+        // rounded towards negative infinity, so both token directions use the same tick
+        let tick_diff =
+            last_price_observation.tick_accumulated - first_price_observation.tick_accumulated;
+        let mean_tick = tick_diff.div_euclid(weight_diff as i64) as i32;
+
+        self.get_sqrt_price_at_tick(mean_tick)
+    }
+
+    fn require_constant_product_pair(&self, pair_address: &ManagedAddress) {
+        require!(
+            self.get_pair_curve(pair_address.clone()) == Curve::ConstantProduct,
+            ERROR_FAIR_PRICE_CURVE
+        );
+    }
+
+    fn get_pair_invariant_root(&self, pair_address: &ManagedAddress) -> BigUint {
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address.clone()).get();
+        let first_token_reserve = self
+            .get_pair_reserve_mapper(pair_address.clone(), &first_token_id)
+            .get();
+        let second_token_reserve = self
+            .get_pair_reserve_mapper(pair_address.clone(), &second_token_id)
+            .get();
+
+        (first_token_reserve * second_token_reserve).sqrt()
+    }
+
+    fn get_round_at_timestamp(&self, timestamp: u64) -> Round {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let current_round = self.blockchain().get_block_round();
+        require!(timestamp <= current_timestamp, ERROR_PARAMETERS);
+
+        let round_offset = (current_timestamp - timestamp) / SECONDS_PER_ROUND;
+        require!(round_offset < current_round, ERROR_PARAMETERS);
+
+        current_round - round_offset
+    }
+
     fn get_default_offset_rounds(&self, pair_address: &ManagedAddress, end_round: Round) -> u64 {
         let safe_price_current_index = self
             .get_safe_price_current_index_mapper(pair_address.clone())
             .get();
         let price_observations = self.get_price_observation_mapper(pair_address.clone());
-        let oldest_price_observation =
-            self.get_oldest_price_observation(safe_price_current_index, &price_observations);
+        let oldest_price_observation = self.get_oldest_price_observation(
+            pair_address,
+            safe_price_current_index,
+            &price_observations,
+        );
 
         let mut default_offset_rounds = end_round - oldest_price_observation.recording_round;
         if default_offset_rounds > DEFAULT_SAFE_PRICE_ROUNDS_OFFSET {
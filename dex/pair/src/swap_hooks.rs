@@ -0,0 +1,158 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::config::{self, MAX_FEE_PERCENTAGE};
+use crate::contexts::swap::SwapContext;
+use crate::errors::{ERROR_BAD_SWAP_HOOK_FEE, ERROR_BAD_SWAP_HOOK_FLAGS};
+use crate::pair_actions::swap::SwapType;
+
+pub const BEFORE_SWAP_HOOK_FLAG: u8 = 1;
+pub const AFTER_SWAP_HOOK_FLAG: u8 = 2;
+pub const ALL_SWAP_HOOK_FLAGS: u8 = BEFORE_SWAP_HOOK_FLAG | AFTER_SWAP_HOOK_FLAG;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct SwapHook<M: ManagedTypeApi> {
+    pub address: ManagedAddress<M>,
+    pub flags: u8,
+}
+
+impl<M: ManagedTypeApi> SwapHook<M> {
+    pub fn has_flag(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+}
+
+/// Before the swap, the amounts are the ones requested by the user and the fee amount is zero.
+/// For a single token add liquidity, the amount in is the whole payment, since the swapped part depends on the fee.
+/// After the swap, they are the final ones.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone)]
+pub struct SwapHookPayload<M: ManagedTypeApi> {
+    pub user: ManagedAddress<M>,
+    pub swap_type: SwapType,
+    pub token_in: TokenIdentifier<M>,
+    pub amount_in: BigUint<M>,
+    pub token_out: TokenIdentifier<M>,
+    pub amount_out: BigUint<M>,
+    pub total_fee_percent: u64,
+    pub fee_amount: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> SwapHookPayload<M> {
+    pub fn from_context(
+        user: ManagedAddress<M>,
+        swap_type: SwapType,
+        context: &SwapContext<M>,
+    ) -> Self {
+        SwapHookPayload {
+            user,
+            swap_type,
+            token_in: context.input_token_id.clone(),
+            amount_in: context.final_input_amount.clone(),
+            token_out: context.output_token_id.clone(),
+            amount_out: context.final_output_amount.clone(),
+            total_fee_percent: context.total_fee_percent,
+            fee_amount: context.fee_amount.clone(),
+        }
+    }
+}
+
+pub mod swap_hook_proxy {
+    multiversx_sc::imports!();
+
+    use super::SwapHookPayload;
+
+    #[multiversx_sc::proxy]
+    pub trait SwapHookProxy {
+        /// Can veto the swap by failing, or override the total fee percent
+        #[endpoint(beforeSwap)]
+        fn before_swap(&self, payload: SwapHookPayload<Self::Api>) -> Option<u64>;
+
+        #[endpoint(afterSwap)]
+        fn after_swap(&self, payload: SwapHookPayload<Self::Api>);
+    }
+}
+
+#[multiversx_sc::module]
+pub trait SwapHooksModule:
+    config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    /// Registers a contract called before and/or after each swap, depending on the flags.
+    /// Pairs without a hook only pay for reading the hook storage.
+    #[endpoint(setSwapHook)]
+    fn set_swap_hook(&self, hook_address: ManagedAddress, flags: u8) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            self.blockchain().is_smart_contract(&hook_address),
+            "Invalid SC Address"
+        );
+        require!(
+            flags != 0 && flags & !ALL_SWAP_HOOK_FLAGS == 0,
+            ERROR_BAD_SWAP_HOOK_FLAGS
+        );
+
+        self.swap_hook().set(Some(SwapHook {
+            address: hook_address,
+            flags,
+        }));
+    }
+
+    #[endpoint(removeSwapHook)]
+    fn remove_swap_hook(&self) {
+        self.require_caller_has_owner_permissions();
+        self.swap_hook().clear();
+    }
+
+    /// Returns the total fee percent to use, which the hook may override
+    fn call_before_swap_hook(
+        &self,
+        swap_hook: &Option<SwapHook<Self::Api>>,
+        payload: SwapHookPayload<Self::Api>,
+    ) -> u64 {
+        let hook_address = match swap_hook {
+            Some(hook) if hook.has_flag(BEFORE_SWAP_HOOK_FLAG) => hook.address.clone(),
+            _ => return payload.total_fee_percent,
+        };
+
+        let default_fee_percent = payload.total_fee_percent;
+        let opt_fee_percent: Option<u64> = self
+            .swap_hook_proxy(hook_address)
+            .before_swap(payload)
+            .execute_on_dest_context();
+        match opt_fee_percent {
+            Some(fee_percent) => {
+                require!(
+                    fee_percent >= self.special_fee_percent().get()
+                        && fee_percent <= MAX_FEE_PERCENTAGE,
+                    ERROR_BAD_SWAP_HOOK_FEE
+                );
+                fee_percent
+            }
+            None => default_fee_percent,
+        }
+    }
+
+    fn call_after_swap_hook(
+        &self,
+        swap_hook: &Option<SwapHook<Self::Api>>,
+        payload: SwapHookPayload<Self::Api>,
+    ) {
+        if let Some(hook) = swap_hook {
+            if hook.has_flag(AFTER_SWAP_HOOK_FLAG) {
+                let _: IgnoreValue = self
+                    .swap_hook_proxy(hook.address.clone())
+                    .after_swap(payload)
+                    .execute_on_dest_context();
+            }
+        }
+    }
+
+    #[proxy]
+    fn swap_hook_proxy(&self, to: ManagedAddress) -> swap_hook_proxy::Proxy<Self::Api>;
+
+    #[view(getSwapHook)]
+    #[storage_mapper("swapHook")]
+    fn swap_hook(&self) -> SingleValueMapper<Option<SwapHook<Self::Api>>>;
+}
@@ -1,37 +1,60 @@
 #![allow(deprecated)]
 
 mod pair_setup;
+use egld_wrapper_mock::EgldWrapperMock;
+use energy_factory_mock::EnergyFactoryMock;
+use energy_query::EnergyQueryModule;
 use fees_collector::{
     config::ConfigModule, fees_accumulation::FeesAccumulationModule, FeesCollector,
 };
+use flash_loan_mock::FlashLoanMock;
 use multiversx_sc::codec::{self, TopDecode};
 use multiversx_sc::{
     api::ManagedTypeApi,
     codec::{
         derive::{NestedEncode, TopEncode},
-        multi_types::OptionalValue,
+        multi_types::{MultiValue2, OptionalValue},
         top_encode_to_vec_u8,
     },
     storage::mappers::StorageTokenWrapper,
-    types::{BigUint, EsdtLocalRole, MultiValueEncoded},
+    types::{BigUint, EsdtLocalRole, EsdtTokenPayment, MultiValueEncoded},
 };
 use multiversx_sc_scenario::{
-    managed_address, managed_biguint, managed_token_id, managed_token_id_wrapped, rust_biguint,
-    whitebox_legacy::TxTokenTransfer, DebugApi,
+    managed_address, managed_biguint, managed_buffer, managed_token_id, managed_token_id_wrapped,
+    rust_biguint, whitebox_legacy::TxTokenTransfer, DebugApi,
 };
 use pair::{
-    config::MAX_PERCENTAGE,
-    fee::FeeModule,
+    circuit_breaker::{CircuitBreakerAction, CircuitBreakerModule},
+    concentrated_liquidity::{
+        positions::{ConcentratedPositionAttributes, ConcentratedPositionsModule},
+        tick_math::TickMathModule,
+        ticks::TicksModule,
+    },
+    config::{ConfigModule as PairConfigModule, Curve, MAX_PERCENTAGE},
+    dynamic_fee::DynamicFeeModule,
+    epoch_stats::EpochStatsModule,
+    fee::{FeeMode, FeeModule},
+    fee_discount::FeeDiscountModule,
     locking_wrapper::LockingWrapperModule,
-    pair_actions::swap::SwapModule,
-    safe_price::{PriceObservation, Round, SafePriceModule},
+    native_egld::NativeEgldModule,
+    pair_actions::{
+        add_liq::AddLiquidityModule, add_liq_single_token::AddLiquiditySingleTokenModule,
+        flash_loan::FlashLoanModule, remove_liq::RemoveLiquidityModule,
+        remove_liq_single_token::RemoveLiquiditySingleTokenModule, skim_sync::SkimSyncModule,
+        swap::SwapModule, views::ViewsModule,
+    },
+    safe_price::{CompactPriceObservation, PriceObservation, Round, SafePriceModule},
+    safe_price_view::SafePriceViewModule,
+    swap_hooks::{SwapHooksModule, AFTER_SWAP_HOOK_FLAG, ALL_SWAP_HOOK_FLAGS},
 };
 use pair_setup::*;
+use pausable::{PausableModule, State};
 use simple_lock::{
     locked_token::{LockedTokenAttributes, LockedTokenModule},
     proxy_lp::{LpProxyTokenAttributes, ProxyLpModule},
     SimpleLock,
 };
+use swap_hook_mock::SwapHookMock;
 
 #[derive(TopEncode, NestedEncode, Clone, Debug)]
 pub struct OldPriceObservation<M: ManagedTypeApi> {
@@ -107,37 +130,1989 @@ fn test_perfect_swap_fixed_output() {
 }
 
 #[test]
-fn test_safe_price_observation_decoding() {
+fn test_add_liquidity_single_token() {
     let mut pair_setup = PairSetup::new(pair::contract_obj);
-    let _ = pair_setup.b_mock.execute_tx(
-        &pair_setup.owner_address,
-        &pair_setup.pair_wrapper,
-        &rust_biguint!(0),
-        |sc| {
-            let old_observation: OldPriceObservation<DebugApi> = OldPriceObservation {
-                first_token_reserve_accumulated: managed_biguint!(1u64),
-                second_token_reserve_accumulated: managed_biguint!(1u64),
-                weight_accumulated: 1u64,
-                recording_round: 1u64,
-            };
 
-            let buffer = top_encode_to_vec_u8(&old_observation).unwrap();
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.add_liquidity_single_token(managed_biguint!(1));
+            },
+        )
+        .assert_user_error("Initial liquidity was not added");
 
-            let mut new_observation = PriceObservation::<DebugApi>::top_decode(buffer).unwrap();
-            assert_eq!(
-                new_observation.lp_supply_accumulated,
-                managed_biguint!(0u64)
-            );
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
 
-            new_observation.lp_supply_accumulated = managed_biguint!(2u64);
-            sc.price_observations().push(&new_observation.clone());
-            let final_observation = sc.price_observations().get(1);
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.add_liquidity_single_token(managed_biguint!(50_000));
+            },
+        )
+        .assert_user_error("Slippage exceeded");
+
+    // 48_883 WEGLD are swapped for 46_473 MEX, and only 2 WEGLD are left
+    pair_setup.add_liquidity_single_token(WEGLD_TOKEN_ID, 100_000, 48_735, 2, 0);
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_WEGLD_TOKENS - 1_001_000 - 100_000 + 2),
+    );
+    pair_setup.check_lp_amount(1_049_735);
+}
+
+#[test]
+fn test_remove_liquidity_single_token() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.remove_liquidity_single_token(
+                    managed_token_id!(OTHER_TOKEN_ID),
+                    managed_biguint!(1),
+                );
+            },
+        )
+        .assert_user_error("Unknown token");
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.remove_liquidity_single_token(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(189_767),
+                );
+            },
+        )
+        .assert_user_error("Slippage amount does not match");
+
+    // 100_000 MEX are removed, and the removed 100_000 WEGLD are swapped for 89_766 MEX
+    pair_setup.remove_liquidity_single_token(100_000, MEX_TOKEN_ID, 189_766, 189_766);
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_WEGLD_TOKENS - 1_001_000),
+    );
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_MEX_TOKENS - 1_001_000 + 189_766),
+    );
+    pair_setup.check_lp_amount(901_000);
+}
+
+#[test]
+fn test_protocol_fee_mint() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let owner_address = pair_setup.owner_address.clone();
+    let treasury_address = pair_setup.b_mock.create_user_account(&rust_biguint!(0));
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_fee_mode(FeeMode::ProtocolFeeMint);
+            },
+        )
+        .assert_user_error("Protocol fee recipient not set");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_protocol_fee_recipient(managed_address!(&treasury_address));
+                sc.set_fee_mode(FeeMode::ProtocolFeeMint);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    // the whole fee stays in the pool
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 90_000, 90_669);
+    pair_setup.b_mock.check_esdt_balance(
+        pair_setup.pair_wrapper.address_ref(),
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(1_101_000),
+    );
+
+    // sqrt(k) grew from 1_001_000 to 1_001_136, and the special fee is 1/6 of the total fee
+    pair_setup.add_liquidity(110_000, 100_000, 100_000, 90_000, 100_010, 110_000, 90_950);
+    pair_setup
+        .b_mock
+        .check_esdt_balance(&treasury_address, LP_TOKEN_ID, &rust_biguint!(22));
+    pair_setup.check_lp_amount(1_001_000 + 22 + 100_010);
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
             assert_eq!(
-                new_observation.lp_supply_accumulated,
-                final_observation.lp_supply_accumulated
+                sc.k_last().get(),
+                managed_biguint!(1_211_000) * managed_biguint!(1_001_281)
             );
-        },
+        })
+        .assert_ok();
+
+    // the fees collector gets the LP tokens directly, so it does not need to know the LP token
+    let fees_collector = pair_setup.b_mock.create_sc_account(
+        &rust_biguint!(0),
+        Some(&owner_address),
+        pair::contract_obj,
+        "fees collector",
+    );
+    let fees_collector_address = fees_collector.address_ref().clone();
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.setup_fees_collector(managed_address!(&fees_collector_address), 50_000);
+                sc.set_protocol_fee_recipient(managed_address!(&fees_collector_address));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 1, 76_163);
+    pair_setup.add_liquidity(110_000, 1, 100_000, 1, 92_383, 110_000, 77_622);
+    pair_setup
+        .b_mock
+        .check_esdt_balance(&fees_collector_address, LP_TOKEN_ID, &rust_biguint!(20));
+
+    // switching back to the special fee transfers stops the minting
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_fee_mode(FeeMode::SpecialFeeTransfers);
+                assert!(sc.k_last().is_empty());
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn test_circuit_breaker() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let owner_address = pair_setup.owner_address.clone();
+
+    pair_setup.b_mock.set_block_round(1);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                // 5% deviation from the safe price over the last 10 rounds
+                sc.set_circuit_breaker_config(10, 5_000, CircuitBreakerAction::RevertSwap);
+            },
+        )
+        .assert_ok();
+
+    // the first observation is recorded, and the window is not covered yet
+    pair_setup.b_mock.set_block_round(5);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 996);
+
+    pair_setup.b_mock.set_block_round(20);
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("Circuit breaker tripped");
+
+    // small price moves are allowed
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 10_000, MEX_TOKEN_ID, 9_000, 9_852);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_circuit_breaker_config(10, 5_000, CircuitBreakerAction::PauseSwaps);
+            },
+        )
+        .assert_ok();
+
+    // the swap goes through, but the following ones are stopped
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 80_000, 88_799);
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("Swap is not enabled");
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let status = sc.get_circuit_breaker_status();
+            assert!(status.enabled);
+            assert!(status.swaps_paused);
+            assert_eq!(status.last_trip_round, 20);
+            assert!(status.current_deviation_percent.unwrap() > 5_000);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_withdraw_only_state() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let owner_address = pair_setup.owner_address.clone();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
     );
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_withdraw_only();
+                assert_eq!(sc.state().get(), State::WithdrawOnly);
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("Swap is not enabled");
+
+    let payments = vec![
+        TxTokenTransfer {
+            token_identifier: WEGLD_TOKEN_ID.to_vec(),
+            nonce: 0,
+            value: rust_biguint!(1_000),
+        },
+        TxTokenTransfer {
+            token_identifier: MEX_TOKEN_ID.to_vec(),
+            nonce: 0,
+            value: rust_biguint!(1_000),
+        },
+    ];
+    pair_setup
+        .b_mock
+        .execute_esdt_multi_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &payments,
+            |sc| {
+                sc.add_liquidity(managed_biguint!(1), managed_biguint!(1));
+            },
+        )
+        .assert_user_error("Not active");
+
+    // users can still take their liquidity out
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let MultiValue2((first_payment, second_payment)) =
+                    sc.remove_liquidity(managed_biguint!(1), managed_biguint!(1));
+                assert_eq!(first_payment.amount, managed_biguint!(100_000));
+                assert_eq!(second_payment.amount, managed_biguint!(100_000));
+            },
+        )
+        .assert_ok();
+    pair_setup.check_lp_amount(901_000);
+}
+
+#[test]
+fn test_stable_swap_curve() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+
+    pair_setup.set_curve(Curve::StableSwap {
+        amplification_coefficient: 100,
+    });
+
+    // initial liquidity is the invariant D, which is the sum of reserves for a balanced pool
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 2_001_000, 1_001_000, 1_001_000,
+    );
+
+    // a constant product pool with the same reserves would only give 90_669
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 99_000, 99_650);
+    pair_setup.swap_fixed_output(WEGLD_TOKEN_ID, 1_100, MEX_TOKEN_ID, 1_000, 95);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_curve(Curve::ConstantProduct);
+            },
+        )
+        .assert_user_error("Curve cannot be changed after liquidity was added");
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(
+                sc.get_curve(),
+                Curve::StableSwap {
+                    amplification_coefficient: 100
+                }
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_weighted_curve() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+
+    // 51/49 would need reserves raised to the 51st power and a 100th root on every swap
+    for first_token_weight in [99, 51, 30] {
+        pair_setup
+            .b_mock
+            .execute_tx(
+                &pair_setup.owner_address,
+                &pair_setup.pair_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_curve(Curve::Weighted { first_token_weight });
+                },
+            )
+            .assert_user_error("Bad curve weight");
+    }
+    for first_token_weight in [20, 25, 40, 50, 60, 75] {
+        pair_setup.set_curve(Curve::Weighted { first_token_weight });
+    }
+
+    pair_setup.set_curve(Curve::Weighted {
+        first_token_weight: 80,
+    });
+
+    // 80/20 pool, with both tokens valued at 1
+    // initial liquidity is the weighted geometric mean of the reserves
+    pair_setup.add_liquidity(
+        4_000_000, 4_000_000, 1_000_000, 1_000_000, 3_030_433, 4_000_000, 1_000_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let equivalent =
+                sc.get_equivalent(managed_token_id!(WEGLD_TOKEN_ID), managed_biguint!(1_000));
+            assert_eq!(equivalent, managed_biguint!(1_000));
+        })
+        .assert_ok();
+
+    // a constant product pool with the same reserves would only give 2_486
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 10_000, MEX_TOKEN_ID, 9_900, 9_908);
+    pair_setup.swap_fixed_output(MEX_TOKEN_ID, 11_000, WEGLD_TOKEN_ID, 10_000, 1_031);
+
+    // proportional deposits work the same as for the constant product curve
+    pair_setup.add_liquidity(400_000, 390_000, 110_000, 90_000, 303_142, 400_000, 100_006);
+
+    // most of a MEX deposit has to be swapped, as MEX is only 20% of the pool value
+    pair_setup.add_liquidity_single_token(MEX_TOKEN_ID, 100_000, 58_392, 0, 2);
+}
+
+#[test]
+fn test_concentrated_liquidity_positions() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+    pair_setup.enable_concentrated_liquidity(10);
+
+    // range between prices 0.99 and 1.01, around the current price of 1
+    let position = pair_setup.add_concentrated_liquidity(-100, 100, 1_000_000, 1_000_000);
+    assert_eq!(position, (1, 1_000_000, 1_000_000));
+    pair_setup.check_concentrated_liquidity_state(0, 200_510_416);
+
+    // a constant product pool with the same full range reserves would only give 9_871
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 10_000, MEX_TOKEN_ID, 1, 9_968);
+    pair_setup.check_concentrated_liquidity_state(-1, 200_510_416);
+    // the full range fees are reinvested, the ranged price is rebased to follow them
+    pair_setup.check_concentrated_price_drift(1_000_000_000_000);
+
+    // crosses the lower tick, the rest of the swap only uses the full range reserves
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_500_000, MEX_TOKEN_ID, 1, 1_323_671);
+    pair_setup.check_concentrated_liquidity_state(-8_120, 0);
+    pair_setup.check_concentrated_price_drift(1_000_000_000_000);
+
+    // crosses the lower tick back into the range
+    pair_setup.swap_fixed_output(
+        MEX_TOKEN_ID,
+        2_000_000,
+        WEGLD_TOKEN_ID,
+        1_000_000,
+        1_168_549,
+    );
+    pair_setup.check_concentrated_liquidity_state(-51, 200_510_416);
+    pair_setup.check_concentrated_price_drift(1_000_000_000_000);
+
+    let position_attributes: Vec<u8> = pair_setup
+        .b_mock
+        .get_nft_attributes(&pair_setup.user_address, POSITION_TOKEN_ID, 1)
+        .unwrap();
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let attributes =
+                ConcentratedPositionAttributes::<DebugApi>::top_decode(position_attributes.clone())
+                    .unwrap();
+            assert_eq!(attributes.tick_lower, -100);
+            assert_eq!(attributes.tick_upper, 100);
+
+            let (first_fee, second_fee) =
+                sc.get_concentrated_position_fees(attributes).into_tuple();
+            assert_eq!(first_fee, managed_biguint!(3_022));
+            assert_eq!(second_fee, managed_biguint!(1_494));
+        })
+        .assert_ok();
+
+    // fees can not be collected while a flash loan is ongoing
+    let user_address = pair_setup.user_address.clone();
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            POSITION_TOKEN_ID,
+            1,
+            &rust_biguint!(1),
+            |sc| {
+                sc.flash_loan_repayment().set(EsdtTokenPayment::new(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    0,
+                    managed_biguint!(0),
+                ));
+                let _ = sc.collect_concentrated_fees();
+            },
+        )
+        .assert_user_error("Flash loan ongoing");
+
+    let removed_amounts = pair_setup.remove_concentrated_liquidity(1);
+    assert_eq!(removed_amounts, (1_506_470, 499_306));
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.concentrated_positions_count().get(), 0);
+            assert!(sc.tick_info(-100).is_empty());
+            assert!(sc.tick_info(100).is_empty());
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let attributes =
+                ConcentratedPositionAttributes::<DebugApi>::top_decode(position_attributes)
+                    .unwrap();
+            let _ = sc.get_concentrated_position_fees(attributes);
+        })
+        .assert_user_error("Tick not initialized");
+}
+
+#[test]
+fn test_concentrated_price_drift_stays_bounded() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+    pair_setup.enable_concentrated_liquidity(10);
+    pair_setup.add_concentrated_liquidity(-1_000, 1_000, 1_000_000, 1_000_000);
+
+    // without the rebase, every swap would move the full range price a bit further
+    let user_address = pair_setup.user_address.clone();
+    for (payment_token_id, desired_token_id) in [
+        (WEGLD_TOKEN_ID, MEX_TOKEN_ID),
+        (MEX_TOKEN_ID, WEGLD_TOKEN_ID),
+    ] {
+        for _ in 0..10 {
+            pair_setup
+                .b_mock
+                .execute_esdt_transfer(
+                    &user_address,
+                    &pair_setup.pair_wrapper,
+                    payment_token_id,
+                    0,
+                    &rust_biguint!(50_000),
+                    |sc| {
+                        sc.swap_tokens_fixed_input(
+                            managed_token_id!(desired_token_id),
+                            managed_biguint!(1),
+                            OptionalValue::None,
+                        );
+                    },
+                )
+                .assert_ok();
+            pair_setup.check_concentrated_price_drift(1_000_000_000_000);
+        }
+    }
+}
+
+#[test]
+fn test_dynamic_fee() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    // the static fee applies until the dynamic mode is enabled
+    pair_setup.check_current_total_fee_percent(300);
+    pair_setup.set_dynamic_fee_config(100, 1_000, 10, 10_000);
+    pair_setup.check_current_total_fee_percent(100);
+
+    // without price history, the minimum fee applies
+    pair_setup.b_mock.set_block_round(1);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 998);
+    pair_setup.check_current_total_fee_percent(100);
+
+    // a large swap moves the price
+    pair_setup.b_mock.set_block_round(2);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 20_000, MEX_TOKEN_ID, 1, 19_550);
+    pair_setup.check_current_total_fee_percent(462);
+
+    pair_setup.b_mock.set_block_round(3);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 1, 953);
+    pair_setup.check_current_total_fee_percent(480);
+
+    // a shorter observations window no longer sees the price before the large swap
+    pair_setup.set_dynamic_fee_config(100, 1_000, 1, 10_000);
+    pair_setup.check_current_total_fee_percent(117);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_dynamic_fee_config(10, 1_000, 10, 10_000);
+            },
+        )
+        .assert_user_error("Bad percents");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.disable_dynamic_fee();
+            },
+        )
+        .assert_ok();
+    pair_setup.check_current_total_fee_percent(300);
+}
+
+#[test]
+fn test_energy_fee_discount_tiers() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    let rust_zero = rust_biguint!(0);
+    let energy_factory_wrapper = pair_setup.b_mock.create_sc_account(
+        &rust_zero,
+        Some(&pair_setup.owner_address),
+        energy_factory_mock::contract_obj,
+        "energy factory mock",
+    );
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_energy_factory_address(managed_address!(
+                    energy_factory_wrapper.address_ref()
+                ));
+                sc.add_fee_discount_tier(managed_biguint!(10_000), 100_000);
+                sc.add_fee_discount_tier(managed_biguint!(1_000), 50_000);
+            },
+        )
+        .assert_ok();
+
+    let user_address = pair_setup.user_address.clone();
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &energy_factory_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_user_energy(
+                    managed_address!(&user_address),
+                    managed_biguint!(5_000),
+                    managed_biguint!(5_000),
+                );
+            },
+        )
+        .assert_ok();
+
+    // half of the fee is discounted, while users without energy pay the full fee
+    let owner_address = pair_setup.owner_address.clone();
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(
+                sc.get_effective_fee_percent(managed_address!(&user_address)),
+                150
+            );
+            assert_eq!(
+                sc.get_effective_fee_percent(managed_address!(&owner_address)),
+                300
+            );
+
+            let tiers = sc.fee_discount_tiers().get();
+            assert_eq!(tiers.len(), 2);
+            assert_eq!(tiers.get(0).min_energy, managed_biguint!(1_000));
+            assert_eq!(tiers.get(1).min_energy, managed_biguint!(10_000));
+        })
+        .assert_ok();
+
+    // a constant product swap with the full fee would give 996
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 997);
+
+    // the special fee is never discounted
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &energy_factory_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_user_energy(
+                    managed_address!(&user_address),
+                    managed_biguint!(20_000),
+                    managed_biguint!(20_000),
+                );
+            },
+        )
+        .assert_ok();
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(
+                sc.get_effective_fee_percent(managed_address!(&user_address)),
+                50
+            );
+        })
+        .assert_ok();
+
+    // only the router can swap on behalf of the original caller
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                let _ = sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::Some(managed_address!(&user_address)),
+                );
+            },
+        )
+        .assert_user_error("Permission denied");
+
+    pair_setup
+        .b_mock
+        .set_esdt_balance(&owner_address, WEGLD_TOKEN_ID, &rust_biguint!(1_000));
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                let ret = sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::Some(managed_address!(&user_address)),
+                );
+                // the discount of the original caller applies, the full fee would give 994
+                assert_eq!(ret.amount, managed_biguint!(996));
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.remove_fee_discount_tier(managed_biguint!(10_000));
+                assert_eq!(
+                    sc.get_effective_fee_percent(managed_address!(&user_address)),
+                    150
+                );
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn test_flash_loan() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    let rust_zero = rust_biguint!(0);
+    let borrower_wrapper = pair_setup.b_mock.create_sc_account(
+        &rust_zero,
+        Some(&pair_setup.owner_address),
+        flash_loan_mock::contract_obj,
+        "flash loan mock",
+    );
+    pair_setup.b_mock.set_esdt_balance(
+        borrower_wrapper.address_ref(),
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(1_000),
+    );
+
+    // the special fee is burned, as the fee destination requested the borrowed token
+    let owner_address = pair_setup.owner_address.clone();
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_fee_on(
+                    true,
+                    managed_address!(&owner_address),
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                );
+            },
+        )
+        .assert_ok();
+    pair_setup.b_mock.set_esdt_local_roles(
+        pair_setup.pair_wrapper.address_ref(),
+        WEGLD_TOKEN_ID,
+        &[EsdtLocalRole::Burn],
+    );
+
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+    let user_address = pair_setup.user_address.clone();
+
+    // a fee of 299 is lower than the required 300
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &borrower_wrapper, &rust_zero, |sc| {
+            sc.borrow(
+                managed_address!(&pair_address),
+                managed_token_id!(WEGLD_TOKEN_ID),
+                managed_biguint!(100_000),
+                managed_biguint!(299),
+            );
+        })
+        .assert_user_error("Flash loan not repaid");
+
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &borrower_wrapper, &rust_zero, |sc| {
+            sc.borrow(
+                managed_address!(&pair_address),
+                managed_token_id!(WEGLD_TOKEN_ID),
+                managed_biguint!(100_000),
+                managed_biguint!(300),
+            );
+        })
+        .assert_ok();
+
+    // 50 of the 300 fee is the special fee, the rest goes to the liquidity providers
+    pair_setup.b_mock.check_esdt_balance(
+        borrower_wrapper.address_ref(),
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(700),
+    );
+    pair_setup
+        .b_mock
+        .check_esdt_balance(&pair_address, WEGLD_TOKEN_ID, &rust_biguint!(1_001_250));
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(
+                sc.pair_reserve(&managed_token_id!(WEGLD_TOKEN_ID)).get(),
+                managed_biguint!(1_001_250)
+            );
+            assert!(sc.flash_loan_repayment().is_empty());
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &pair_setup.pair_wrapper, &rust_zero, |sc| {
+            sc.flash_loan(
+                managed_token_id!(WEGLD_TOKEN_ID),
+                managed_biguint!(100_000),
+                managed_buffer!(b"onFlashLoan"),
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_user_error("Flash loans are only available to smart contracts");
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                sc.repay_flash_loan();
+            },
+        )
+        .assert_user_error("No flash loan ongoing");
+}
+
+#[test]
+fn test_native_egld() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    let rust_zero = rust_biguint!(0);
+    let wrapper = pair_setup.b_mock.create_sc_account(
+        &rust_biguint!(1_000_000),
+        Some(&pair_setup.owner_address),
+        egld_wrapper_mock::contract_obj,
+        "egld wrapper mock",
+    );
+    pair_setup
+        .b_mock
+        .execute_tx(&pair_setup.owner_address, &wrapper, &rust_zero, |sc| {
+            sc.init(managed_token_id!(WEGLD_TOKEN_ID));
+        })
+        .assert_ok();
+    pair_setup.b_mock.set_esdt_balance(
+        wrapper.address_ref(),
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(1_000_000),
+    );
+
+    let user_address = pair_setup.user_address.clone();
+    pair_setup
+        .b_mock
+        .set_egld_balance(&user_address, &rust_biguint!(10_000));
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(1_000),
+            |sc| {
+                sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("EGLD wrapper not set");
+
+    let wrapper_address = wrapper.address_ref().clone();
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &pair_setup.pair_wrapper, &rust_zero, |sc| {
+            sc.set_egld_wrapper(
+                managed_address!(&wrapper_address),
+                managed_token_id!(WEGLD_TOKEN_ID),
+            );
+        })
+        .assert_user_error("Permission denied");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_egld_wrapper(
+                    managed_address!(&wrapper_address),
+                    managed_token_id!(OTHER_TOKEN_ID),
+                );
+            },
+        )
+        .assert_user_error("Wrapped EGLD is not one of the pair tokens");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_egld_wrapper(
+                    managed_address!(&wrapper_address),
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                );
+            },
+        )
+        .assert_ok();
+
+    // EGLD is wrapped before the swap
+    let user_mex_before = pair_setup
+        .b_mock
+        .get_esdt_balance(&user_address, MEX_TOKEN_ID, 0);
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(1_000),
+            |sc| {
+                let output = sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::None,
+                );
+                assert_eq!(output.token_identifier, managed_token_id!(MEX_TOKEN_ID));
+                assert_eq!(output.amount, managed_biguint!(996));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &(user_mex_before + rust_biguint!(996)),
+    );
+    pair_setup
+        .b_mock
+        .check_egld_balance(&user_address, &rust_biguint!(9_000));
+    pair_setup
+        .b_mock
+        .check_egld_balance(&wrapper_address, &rust_biguint!(1_001_000));
+    pair_setup.b_mock.check_esdt_balance(
+        pair_setup.pair_wrapper.address_ref(),
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(1_002_000),
+    );
+
+    // wrapped EGLD is unwrapped when EGLD is requested
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                let output = sc.swap_tokens_fixed_input(
+                    managed_token_id!(b"EGLD"),
+                    managed_biguint!(1),
+                    OptionalValue::None,
+                );
+                assert_eq!(output.token_identifier, managed_token_id!(b"EGLD"));
+                assert_eq!(output.amount, managed_biguint!(997));
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .check_egld_balance(&user_address, &rust_biguint!(9_997));
+    pair_setup
+        .b_mock
+        .check_egld_balance(&wrapper_address, &rust_biguint!(1_000_003));
+    pair_setup
+        .b_mock
+        .check_esdt_balance(&wrapper_address, WEGLD_TOKEN_ID, &rust_biguint!(999_997));
+    pair_setup.b_mock.check_esdt_balance(
+        pair_setup.pair_wrapper.address_ref(),
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(1_001_003),
+    );
+
+    // single token liquidity can be added with EGLD
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(2_000),
+            |sc| {
+                let output = sc.add_liquidity_single_token(managed_biguint!(1));
+                let (lp_payment, _, _) = output.into_tuple();
+                assert_eq!(lp_payment.token_identifier, managed_token_id!(LP_TOKEN_ID));
+                assert!(lp_payment.amount > 0u64);
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .check_egld_balance(&user_address, &rust_biguint!(7_997));
+}
+
+#[test]
+fn test_skim_and_sync() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    let rust_zero = rust_biguint!(0);
+    let user_address = pair_setup.user_address.clone();
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+
+    // tokens sent directly to the pair are not part of the reserves
+    pair_setup
+        .b_mock
+        .set_esdt_balance(&pair_address, WEGLD_TOKEN_ID, &rust_biguint!(1_001_500));
+
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &pair_setup.pair_wrapper, &rust_zero, |sc| {
+            sc.skim(managed_address!(&user_address));
+        })
+        .assert_user_error("Permission denied");
+
+    let user_wegld_before = pair_setup
+        .b_mock
+        .get_esdt_balance(&user_address, WEGLD_TOKEN_ID, 0);
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let (first_payment, second_payment) =
+                    sc.skim(managed_address!(&user_address)).into_tuple();
+                assert_eq!(first_payment.amount, managed_biguint!(500));
+                assert_eq!(second_payment.amount, managed_biguint!(0));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.check_esdt_balance(
+        &user_address,
+        WEGLD_TOKEN_ID,
+        &(user_wegld_before + rust_biguint!(500)),
+    );
+    pair_setup
+        .b_mock
+        .check_esdt_balance(&pair_address, WEGLD_TOKEN_ID, &rust_biguint!(1_001_000));
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.skim(managed_address!(&user_address));
+            },
+        )
+        .assert_user_error("Nothing to skim");
+
+    // sync adds the extra balance to the reserves, after recording the previous price
+    pair_setup
+        .b_mock
+        .set_esdt_balance(&pair_address, MEX_TOKEN_ID, &rust_biguint!(1_002_000));
+    pair_setup.b_mock.set_block_round(10);
+
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &pair_setup.pair_wrapper, &rust_zero, |sc| {
+            sc.sync();
+        })
+        .assert_user_error("Permission denied");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.sync();
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(
+                sc.pair_reserve(&managed_token_id!(WEGLD_TOKEN_ID)).get(),
+                managed_biguint!(1_001_000)
+            );
+            assert_eq!(
+                sc.pair_reserve(&managed_token_id!(MEX_TOKEN_ID)).get(),
+                managed_biguint!(1_002_000)
+            );
+        })
+        .assert_ok();
+    pair_setup.check_price_observation_rounds(1, &[10]);
+}
+
+#[test]
+fn test_swap_hooks() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    let rust_zero = rust_biguint!(0);
+    let hook_wrapper = pair_setup.b_mock.create_sc_account(
+        &rust_zero,
+        Some(&pair_setup.owner_address),
+        swap_hook_mock::contract_obj,
+        "swap hook mock",
+    );
+    let hook_address = hook_wrapper.address_ref().clone();
+    let user_address = pair_setup.user_address.clone();
+
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &pair_setup.pair_wrapper, &rust_zero, |sc| {
+            sc.set_swap_hook(managed_address!(&hook_address), ALL_SWAP_HOOK_FLAGS);
+        })
+        .assert_user_error("Permission denied");
+
+    pair_setup.set_swap_hook(&hook_address, 4, Some("Bad swap hook flags"));
+    pair_setup.set_swap_hook(&hook_address, ALL_SWAP_HOOK_FLAGS, None);
+
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 1, 996);
+    pair_setup
+        .b_mock
+        .execute_query(&hook_wrapper, |sc| {
+            assert_eq!(sc.before_swap_count().get(), 1);
+            assert_eq!(sc.after_swap_count().get(), 1);
+
+            let payload = sc.last_payload().get();
+            assert_eq!(payload.user, managed_address!(&user_address));
+            assert_eq!(payload.amount_in, managed_biguint!(1_000));
+            assert_eq!(payload.amount_out, managed_biguint!(996));
+            assert_eq!(payload.total_fee_percent, 300);
+        })
+        .assert_ok();
+
+    // the before swap hook can override the fee
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &hook_wrapper, &rust_zero, |sc| {
+            sc.set_fee_override(Some(10));
+        })
+        .assert_ok();
+    pair_setup.swap_fixed_input_expect_error(
+        WEGLD_TOKEN_ID,
+        1_000,
+        MEX_TOKEN_ID,
+        "Bad swap hook fee",
+    );
+
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &hook_wrapper, &rust_zero, |sc| {
+            sc.set_fee_override(Some(5_000));
+        })
+        .assert_ok();
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 1, 947);
+
+    // or veto the swap
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &hook_wrapper, &rust_zero, |sc| {
+            sc.set_veto(true);
+        })
+        .assert_ok();
+    pair_setup.swap_fixed_input_expect_error(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, "Swap vetoed");
+
+    // only the flagged hooks are called
+    pair_setup.set_swap_hook(&hook_address, AFTER_SWAP_HOOK_FLAG, None);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 1, 992);
+    pair_setup
+        .b_mock
+        .execute_query(&hook_wrapper, |sc| {
+            assert_eq!(sc.before_swap_count().get(), 2);
+            assert_eq!(sc.after_swap_count().get(), 3);
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.remove_swap_hook();
+            },
+        )
+        .assert_ok();
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 1, 990);
+    pair_setup
+        .b_mock
+        .execute_query(&hook_wrapper, |sc| {
+            assert_eq!(sc.after_swap_count().get(), 3);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_swap_hooks_on_single_token_liquidity() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    let rust_zero = rust_biguint!(0);
+    let hook_wrapper = pair_setup.b_mock.create_sc_account(
+        &rust_zero,
+        Some(&pair_setup.owner_address),
+        swap_hook_mock::contract_obj,
+        "swap hook mock",
+    );
+    let hook_address = hook_wrapper.address_ref().clone();
+    let user_address = pair_setup.user_address.clone();
+    pair_setup.set_swap_hook(&hook_address, ALL_SWAP_HOOK_FLAGS, None);
+
+    pair_setup.add_liquidity_single_token(WEGLD_TOKEN_ID, 100_000, 48_735, 2, 0);
+    pair_setup
+        .b_mock
+        .execute_query(&hook_wrapper, |sc| {
+            assert_eq!(sc.before_swap_count().get(), 1);
+            assert_eq!(sc.after_swap_count().get(), 1);
+
+            let payload = sc.last_payload().get();
+            assert_eq!(payload.user, managed_address!(&user_address));
+            assert_eq!(payload.token_in, managed_token_id!(WEGLD_TOKEN_ID));
+            assert_eq!(payload.amount_in, managed_biguint!(48_883));
+            assert_eq!(payload.amount_out, managed_biguint!(46_473));
+        })
+        .assert_ok();
+
+    pair_setup.remove_liquidity_single_token(10_000, MEX_TOKEN_ID, 1, 18_951);
+    pair_setup
+        .b_mock
+        .execute_query(&hook_wrapper, |sc| {
+            assert_eq!(sc.before_swap_count().get(), 2);
+            assert_eq!(sc.after_swap_count().get(), 2);
+
+            let payload = sc.last_payload().get();
+            assert_eq!(payload.token_in, managed_token_id!(WEGLD_TOKEN_ID));
+            assert_eq!(payload.token_out, managed_token_id!(MEX_TOKEN_ID));
+            assert_eq!(payload.total_fee_percent, 300);
+        })
+        .assert_ok();
+
+    // the fee override applies to the swap inside the zap
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &hook_wrapper, &rust_zero, |sc| {
+            sc.set_fee_override(Some(5_000));
+        })
+        .assert_ok();
+    pair_setup.remove_liquidity_single_token(10_000, MEX_TOKEN_ID, 1, 18_335);
+
+    // and the hook can veto both zaps
+    pair_setup
+        .b_mock
+        .execute_tx(&user_address, &hook_wrapper, &rust_zero, |sc| {
+            sc.set_veto(true);
+        })
+        .assert_ok();
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.add_liquidity_single_token(managed_biguint!(1));
+            },
+        )
+        .assert_user_error("Swap vetoed");
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                sc.remove_liquidity_single_token(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                );
+            },
+        )
+        .assert_user_error("Swap vetoed");
+}
+
+#[test]
+fn test_safe_price_observation_decoding() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let _ = pair_setup.b_mock.execute_tx(
+        &pair_setup.owner_address,
+        &pair_setup.pair_wrapper,
+        &rust_biguint!(0),
+        |sc| {
+            let old_observation: OldPriceObservation<DebugApi> = OldPriceObservation {
+                first_token_reserve_accumulated: managed_biguint!(1u64),
+                second_token_reserve_accumulated: managed_biguint!(1u64),
+                weight_accumulated: 1u64,
+                recording_round: 1u64,
+            };
+
+            let buffer = top_encode_to_vec_u8(&old_observation).unwrap();
+
+            let mut new_observation =
+                PriceObservation::<DebugApi>::top_decode(buffer.clone()).unwrap();
+            assert_eq!(
+                new_observation.lp_supply_accumulated,
+                managed_biguint!(0u64)
+            );
+            assert!(!new_observation.has_price_accumulators());
+            assert_eq!(new_observation.tick_accumulated, 0);
+
+            let legacy_observation =
+                CompactPriceObservation::<DebugApi>::top_decode(buffer).unwrap();
+            assert_eq!(legacy_observation.base_slot(), None);
+            let legacy_observation = legacy_observation.resolve(|_| unreachable!());
+            assert_eq!(legacy_observation.recording_round, 1u64);
+            assert_eq!(legacy_observation.weight_accumulated, 1u64);
+
+            new_observation.lp_supply_accumulated = managed_biguint!(2u64);
+            new_observation.tick_accumulated = -12_345;
+            let full_buffer =
+                top_encode_to_vec_u8(&CompactPriceObservation::Full(new_observation.clone()))
+                    .unwrap();
+            let nested_buffer = top_encode_to_vec_u8(&new_observation).unwrap();
+            assert_eq!(full_buffer, nested_buffer);
+
+            let delta_observation = CompactPriceObservation::Delta {
+                base_slot: 1,
+                delta: new_observation.clone(),
+            };
+            let delta_buffer = top_encode_to_vec_u8(&delta_observation).unwrap();
+            assert!(delta_buffer.len() < nested_buffer.len());
+
+            sc.observation_base(1).set(PriceObservation::default());
+            sc.price_observations().push(&delta_observation);
+            assert_eq!(sc.price_observations().get(1).base_slot(), Some(1));
+            let final_observation = sc.get_price_observation_at(1);
+            assert_eq!(
+                new_observation.lp_supply_accumulated,
+                final_observation.lp_supply_accumulated
+            );
+            assert_eq!(final_observation.tick_accumulated, -12_345);
+            assert_eq!(final_observation.weight_accumulated, 1u64);
+        },
+    );
+}
+
+#[test]
+fn test_twap() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+
+    pair_setup.b_mock.set_block_round(10);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup.b_mock.set_block_round(20);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 996);
+
+    pair_setup.b_mock.set_block_round(30);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 994);
+
+    pair_setup.b_mock.set_block_round(40);
+    pair_setup.b_mock.set_block_timestamp(240);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let observation = sc.get_price_observation_at(2);
+            assert!(observation.has_price_accumulators());
+            assert_eq!(observation.tick_accumulated, -200);
+
+            let wegld_payment = EsdtTokenPayment::new(
+                managed_token_id!(WEGLD_TOKEN_ID),
+                0,
+                managed_biguint!(1_000_000),
+            );
+            let mex_payment = EsdtTokenPayment::new(
+                managed_token_id!(MEX_TOKEN_ID),
+                0,
+                managed_biguint!(1_000_000),
+            );
+
+            let arithmetic_twap = sc.get_arithmetic_twap(
+                managed_address!(&pair_address),
+                20,
+                40,
+                wegld_payment.clone(),
+            );
+            assert_eq!(
+                arithmetic_twap.token_identifier,
+                managed_token_id!(MEX_TOKEN_ID)
+            );
+            assert_eq!(arithmetic_twap.amount, managed_biguint!(997_014));
+            let arithmetic_twap = sc.get_arithmetic_twap(
+                managed_address!(&pair_address),
+                20,
+                40,
+                mex_payment.clone(),
+            );
+            assert_eq!(arithmetic_twap.amount, managed_biguint!(1_002_994));
+
+            // the mean tick is -30
+            let geometric_twap = sc.get_geometric_twap(
+                managed_address!(&pair_address),
+                20,
+                40,
+                wegld_payment.clone(),
+            );
+            assert_eq!(geometric_twap.amount, managed_biguint!(997_004));
+            let geometric_twap =
+                sc.get_geometric_twap(managed_address!(&pair_address), 20, 40, mex_payment);
+            assert_eq!(geometric_twap.amount, managed_biguint!(1_003_004));
+
+            // timestamp 120 is 20 rounds before the current one
+            let arithmetic_twap = sc.get_arithmetic_twap_by_timestamps(
+                managed_address!(&pair_address),
+                120,
+                240,
+                wegld_payment.clone(),
+            );
+            assert_eq!(arithmetic_twap.amount, managed_biguint!(997_014));
+            let geometric_twap = sc.get_geometric_twap_by_timestamps(
+                managed_address!(&pair_address),
+                120,
+                240,
+                wegld_payment,
+            );
+            assert_eq!(geometric_twap.amount, managed_biguint!(997_004));
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_lp_tokens_fair_price() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+
+    pair_setup.b_mock.set_block_round(10);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup.b_mock.set_block_round(20);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 996);
+
+    pair_setup.b_mock.set_block_round(30);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 994);
+
+    pair_setup.b_mock.set_block_round(40);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            // the reserves are 1_003_000 and 999_010, so the spot value would be 100_199 and 99_801
+            let (first_token_worth, second_token_worth) = sc
+                .get_lp_tokens_fair_price(
+                    managed_address!(&pair_address),
+                    20,
+                    40,
+                    managed_biguint!(100_000),
+                )
+                .into_tuple();
+            assert_eq!(
+                first_token_worth.token_identifier,
+                managed_token_id!(WEGLD_TOKEN_ID)
+            );
+            assert_eq!(first_token_worth.amount, managed_biguint!(100_150));
+            assert_eq!(
+                second_token_worth.token_identifier,
+                managed_token_id!(MEX_TOKEN_ID)
+            );
+            assert_eq!(second_token_worth.amount, managed_biguint!(99_850));
+
+            let fair_value = sc.get_lp_tokens_fair_value(
+                managed_address!(&pair_address),
+                managed_biguint!(100_000),
+                managed_biguint!(2_000_000_000_000_000_000),
+                managed_biguint!(3_000_000_000_000_000_000),
+            );
+            assert_eq!(
+                fair_value,
+                BigUint::from(489_899_416_782_255_669_457_001u128)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_swap_quote() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let fees_collector_address = pair_setup.second_pair_wrapper.address_ref().clone();
+
+    pair_setup.b_mock.set_block_round(10);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let quote =
+                sc.get_swap_quote(managed_token_id!(WEGLD_TOKEN_ID), managed_biguint!(1_000));
+            assert_eq!(quote.amount_out, managed_biguint!(996));
+            assert_eq!(quote.total_fee_percent, 300);
+            assert_eq!(quote.lp_fee_amount, managed_biguint!(3));
+            assert_eq!(quote.special_fee_amount, managed_biguint!(0));
+            assert_eq!(quote.fees_collector_cut_amount, managed_biguint!(0));
+            assert_eq!(quote.safe_price_deviation_bps, None);
+        })
+        .assert_ok();
+
+    pair_setup.b_mock.set_block_round(20);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 996);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.fees_collector_address()
+                    .set(managed_address!(&fees_collector_address));
+                sc.fees_collector_cut_percentage().set(50_000);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.set_block_round(700);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let quote =
+                sc.get_swap_quote(managed_token_id!(WEGLD_TOKEN_ID), managed_biguint!(100_000));
+            assert_eq!(quote.amount_out, managed_biguint!(90_496));
+            assert_eq!(quote.lp_fee_amount, managed_biguint!(250));
+            assert_eq!(quote.special_fee_amount, managed_biguint!(50));
+            assert_eq!(quote.fees_collector_cut_amount, managed_biguint!(25));
+            assert_eq!(
+                quote.spot_price_before,
+                managed_biguint!(998_007_984_031_936_127)
+            );
+            assert_eq!(
+                quote.spot_price_after,
+                managed_biguint!(825_362_312_264_621_806)
+            );
+            assert_eq!(quote.price_impact_bps, 1_729);
+            assert_eq!(quote.safe_price_deviation_bps, Some(1_729));
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_swap_quote_with_concentrated_liquidity() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+    pair_setup.enable_concentrated_liquidity(10);
+    pair_setup.add_concentrated_liquidity(-100, 100, 1_000_000, 1_000_000);
+
+    // the output is larger than the full range reserve, the rest comes from the position
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let quote = sc.get_swap_quote(
+                managed_token_id!(WEGLD_TOKEN_ID),
+                managed_biguint!(1_500_000),
+            );
+            assert_eq!(quote.amount_out, managed_biguint!(1_329_179));
+            assert_eq!(
+                quote.spot_price_before,
+                managed_biguint!(1_000_000_000_000_000_000)
+            );
+            assert_eq!(
+                quote.spot_price_after,
+                managed_biguint!(449_990_455_235_052_394)
+            );
+            assert_eq!(quote.price_impact_bps, 5_500);
+        })
+        .assert_ok();
+
+    // the quote matches the state left by the swap
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_500_000, MEX_TOKEN_ID, 1, 1_329_179);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let price_unit = managed_biguint!(1_000_000_000_000_000_000);
+            assert_eq!(
+                sc.get_second_token_quote(&price_unit, &sc.concentrated_sqrt_price().get()),
+                managed_biguint!(449_990_455_235_052_394)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_epoch_stats() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+
+    pair_setup.b_mock.set_block_epoch(5);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 996);
+
+    pair_setup.b_mock.set_block_epoch(6);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 994);
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let mut epoch_stats = sc.get_epoch_stats(0, 10).into_iter();
+            let (epoch, stats) = epoch_stats.next().unwrap().into_tuple();
+            assert_eq!(epoch, 5);
+            assert_eq!(stats.first_token_volume, managed_biguint!(1_000));
+            assert_eq!(stats.second_token_volume, managed_biguint!(996));
+            assert_eq!(stats.first_token_lp_fees, managed_biguint!(3));
+            assert_eq!(stats.second_token_lp_fees, managed_biguint!(0));
+            assert_eq!(stats.first_token_special_fees, managed_biguint!(0));
+            assert_eq!(stats.swaps_count, 1);
+            assert_eq!(stats.first_token_reserve, managed_biguint!(1_002_000));
+            assert_eq!(stats.second_token_reserve, managed_biguint!(1_000_004));
+            assert_eq!(stats.lp_token_supply, managed_biguint!(1_001_000));
+
+            let (epoch, stats) = epoch_stats.next().unwrap().into_tuple();
+            assert_eq!(epoch, 6);
+            assert_eq!(stats.second_token_volume, managed_biguint!(994));
+            assert_eq!(stats.swaps_count, 1);
+            assert_eq!(stats.first_token_reserve, managed_biguint!(1_003_000));
+            assert_eq!(stats.second_token_reserve, managed_biguint!(999_010));
+            assert!(epoch_stats.next().is_none());
+
+            let aggregated_stats = sc.get_aggregated_stats(5, 6);
+            assert_eq!(aggregated_stats.first_token_volume, managed_biguint!(2_000));
+            assert_eq!(
+                aggregated_stats.second_token_volume,
+                managed_biguint!(1_990)
+            );
+            assert_eq!(aggregated_stats.first_token_lp_fees, managed_biguint!(6));
+            assert_eq!(aggregated_stats.swaps_count, 2);
+            assert_eq!(
+                aggregated_stats.first_token_reserve,
+                managed_biguint!(1_003_000)
+            );
+            assert_eq!(
+                aggregated_stats.lp_token_supply,
+                managed_biguint!(1_001_000)
+            );
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let _ = sc.get_aggregated_stats(6, 5);
+        })
+        .assert_user_error("Invalid epoch range");
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let _ = sc.get_epoch_stats(0, 365);
+        })
+        .assert_user_error("Invalid epoch range");
+}
+
+#[test]
+fn test_observation_cardinality() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_observation_cardinality(1);
+            },
+        )
+        .assert_user_error(
+            "The observation cardinality must be between 2 and the maximum number of observations",
+        );
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_observation_cardinality(3);
+            },
+        )
+        .assert_user_error("Permission denied");
+
+    pair_setup.check_observation_cardinality(65_536, 65_536);
+    pair_setup.set_observation_cardinality(3);
+    pair_setup.check_observation_cardinality(3, 3);
+
+    for round in [10, 20, 30, 40, 50] {
+        pair_setup.record_price_observation(round, 1_000, 2_000, 1_000);
+    }
+    pair_setup.check_price_observation_rounds(2, &[40, 50, 30]);
+    pair_setup.check_price_observation(&pair_address, 35, 26, 26_000, 52_000);
+    pair_setup.check_price_observation(&pair_address, 45, 36, 36_000, 72_000);
+
+    // Growing a wrapped ring waits for the write index to reach its end
+    pair_setup.set_observation_cardinality(5);
+    pair_setup.check_observation_cardinality(3, 5);
+    pair_setup.record_price_observation(60, 1_000, 2_000, 1_000);
+    pair_setup.check_price_observation_rounds(3, &[40, 50, 60]);
+    pair_setup.record_price_observation(70, 1_000, 2_000, 1_000);
+    pair_setup.check_observation_cardinality(5, 5);
+    pair_setup.record_price_observation(80, 1_000, 2_000, 1_000);
+    pair_setup.check_price_observation_rounds(5, &[40, 50, 60, 70, 80]);
+
+    // Shrinking waits for the write index to reach the new cardinality
+    pair_setup.set_observation_cardinality(3);
+    pair_setup.check_observation_cardinality(5, 3);
+    pair_setup.record_price_observation(90, 1_000, 2_000, 1_000);
+    pair_setup.check_price_observation_rounds(1, &[90, 50, 60, 70, 80]);
+    pair_setup.record_price_observation(100, 1_000, 2_000, 1_000);
+    pair_setup.record_price_observation(110, 1_000, 2_000, 1_000);
+    pair_setup.check_price_observation_rounds(3, &[90, 100, 110, 70, 80]);
+    pair_setup.check_price_observation(&pair_address, 75, 66, 66_000, 132_000);
+
+    pair_setup.record_price_observation(120, 1_000, 2_000, 1_000);
+    pair_setup.check_observation_cardinality(3, 3);
+    pair_setup.check_price_observation_rounds(1, &[120, 100, 110]);
+
+    pair_setup.set_observation_cardinality(2);
+    pair_setup.record_price_observation(130, 1_000, 2_000, 1_000);
+    pair_setup.check_price_observation_rounds(2, &[120, 130, 110]);
+    pair_setup.record_price_observation(140, 1_000, 2_000, 1_000);
+    pair_setup.check_observation_cardinality(2, 2);
+    pair_setup.check_price_observation_rounds(1, &[140, 130]);
+    pair_setup.check_price_observation(&pair_address, 135, 126, 126_000, 252_000);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let _ = sc.get_price_observation_view(managed_address!(&pair_address), 125);
+        })
+        .assert_user_error("The price observation does not exist");
+
+    // The dropped observations are removed a few at a time, and are not read in the meantime
+    pair_setup.set_observation_cardinality(20);
+    for round in (150..=330).step_by(10) {
+        pair_setup.record_price_observation(round, 1_000, 2_000, 1_000);
+    }
+    pair_setup.check_observation_cardinality(20, 20);
+    pair_setup.set_observation_cardinality(2);
+    for round in [340, 350, 360] {
+        pair_setup.record_price_observation(round, 1_000, 2_000, 1_000);
+    }
+    pair_setup.check_observation_cardinality(2, 2);
+    pair_setup
+        .check_price_observation_rounds(1, &[360, 350, 160, 170, 180, 190, 200, 210, 220, 230]);
+    pair_setup.check_price_observation(&pair_address, 355, 346, 346_000, 692_000);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let _ = sc.get_price_observation_view(managed_address!(&pair_address), 345);
+        })
+        .assert_user_error("The price observation does not exist");
+
+    pair_setup.record_price_observation(370, 1_000, 2_000, 1_000);
+    pair_setup.check_price_observation_rounds(2, &[360, 370]);
+}
+
+#[test]
+fn test_compact_price_observations() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.enable_compact_price_observations();
+            },
+        )
+        .assert_user_error("Permission denied");
+
+    // Observations keep the nested format until compact observations are enabled
+    pair_setup.set_observation_cardinality(3);
+    for round in [10, 20] {
+        pair_setup.record_price_observation(round, 1_000, 2_000, 1_000);
+    }
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert!(!sc.compact_price_observations_enabled().get());
+            assert_eq!(sc.price_observations().get(2).base_slot(), None);
+        })
+        .assert_ok();
+
+    // Deltas start from the last full observation, and each lap of the ring switches base
+    pair_setup.enable_compact_price_observations();
+    pair_setup.record_price_observation(30, 1_000, 2_000, 1_000);
+    pair_setup.record_price_observation(40, 1_000, 2_000, 1_000);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let price_observations = sc.price_observations();
+            assert_eq!(price_observations.get(1).base_slot(), Some(1));
+            assert_eq!(price_observations.get(2).base_slot(), None);
+            assert_eq!(price_observations.get(3).base_slot(), Some(0));
+            assert_eq!(sc.observation_base(0).get().recording_round, 20);
+            assert_eq!(sc.observation_base(1).get().recording_round, 30);
+        })
+        .assert_ok();
+    pair_setup.check_price_observation_rounds(1, &[40, 20, 30]);
+    pair_setup.check_price_observation(&pair_address, 25, 16, 16_000, 32_000);
+    pair_setup.check_price_observation(&pair_address, 35, 26, 26_000, 52_000);
+
+    for round in [50, 60, 70] {
+        pair_setup.record_price_observation(round, 1_000, 2_000, 1_000);
+    }
+    pair_setup.check_price_observation_rounds(1, &[70, 50, 60]);
+    pair_setup.check_price_observation(&pair_address, 55, 46, 46_000, 92_000);
+    pair_setup.check_price_observation(&pair_address, 65, 56, 56_000, 112_000);
+
+    // Growing and shrinking the ring keeps the deltas readable
+    pair_setup.set_observation_cardinality(5);
+    for round in [80, 90, 100, 110, 120] {
+        pair_setup.record_price_observation(round, 1_000, 2_000, 1_000);
+    }
+    pair_setup.check_price_observation_rounds(1, &[120, 80, 90, 100, 110]);
+    pair_setup.check_price_observation(&pair_address, 85, 76, 76_000, 152_000);
+    pair_setup.check_price_observation(&pair_address, 115, 106, 106_000, 212_000);
+
+    pair_setup.set_observation_cardinality(2);
+    for round in [130, 140, 150] {
+        pair_setup.record_price_observation(round, 1_000, 2_000, 1_000);
+    }
+    pair_setup.check_price_observation_rounds(2, &[140, 150]);
+    pair_setup.check_price_observation(&pair_address, 145, 136, 136_000, 272_000);
 }
 
 #[test]
@@ -1203,6 +3178,7 @@ fn test_locked_asset() {
                 let ret = sc.swap_tokens_fixed_input(
                     managed_token_id!(WEGLD_TOKEN_ID),
                     managed_biguint!(10),
+                    OptionalValue::None,
                 );
 
                 assert_eq!(ret.token_identifier, managed_token_id!(LOCKED_TOKEN_ID));
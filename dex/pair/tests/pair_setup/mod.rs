@@ -1,4 +1,4 @@
-use multiversx_sc::codec::multi_types::MultiValue3;
+use multiversx_sc::codec::multi_types::{MultiValue3, OptionalValue};
 use multiversx_sc::types::{
     Address, BigUint, EsdtLocalRole, EsdtTokenPayment, ManagedAddress, MultiValueEncoded,
 };
@@ -12,6 +12,7 @@ pub const MEX_TOKEN_ID: &[u8] = b"MEX-abcdef";
 pub const OTHER_TOKEN_ID: &[u8] = b"OTHER-abcdef";
 pub const WEGLD_TOKEN_ID: &[u8] = b"WEGLD-abcdef";
 pub const LP_TOKEN_ID: &[u8] = b"LPTOK-abcdef";
+pub const POSITION_TOKEN_ID: &[u8] = b"POSITION-abcdef";
 
 pub const LOCKED_TOKEN_ID: &[u8] = b"LOCKED-abcdef";
 pub const LP_PROXY_TOKEN_ID: &[u8] = b"LPPROXY-abcdef";
@@ -19,11 +20,18 @@ pub const LP_PROXY_TOKEN_ID: &[u8] = b"LPPROXY-abcdef";
 pub const USER_TOTAL_MEX_TOKENS: u64 = 5_000_000_000;
 pub const USER_TOTAL_WEGLD_TOKENS: u64 = 5_000_000_000;
 
-use pair::config::ConfigModule as PairConfigModule;
+use pair::concentrated_liquidity::positions::ConcentratedPositionsModule;
+use pair::concentrated_liquidity::tick_math::TickMathModule;
+use pair::concentrated_liquidity::ticks::TicksModule;
+use pair::config::{ConfigModule as PairConfigModule, Curve};
+use pair::dynamic_fee::DynamicFeeModule;
 use pair::pair_actions::add_liq::AddLiquidityModule;
+use pair::pair_actions::add_liq_single_token::AddLiquiditySingleTokenModule;
+use pair::pair_actions::remove_liq_single_token::RemoveLiquiditySingleTokenModule;
 use pair::pair_actions::swap::SwapModule;
-use pair::safe_price::SafePriceModule;
+use pair::safe_price::{CompactPriceObservation, SafePriceModule};
 use pair::safe_price_view::*;
+use pair::swap_hooks::SwapHooksModule;
 use pair::*;
 use pausable::{PausableModule, State};
 
@@ -180,6 +188,68 @@ where
             .assert_ok();
     }
 
+    pub fn add_liquidity_single_token(
+        &mut self,
+        payment_token_id: &[u8],
+        payment_amount: u64,
+        expected_lp_amount: u64,
+        expected_first_amount_left: u64,
+        expected_second_amount_left: u64,
+    ) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pair_wrapper,
+                payment_token_id,
+                0,
+                &rust_biguint!(payment_amount),
+                |sc| {
+                    let (lp_payment, first_payment, second_payment) = sc
+                        .add_liquidity_single_token(managed_biguint!(expected_lp_amount))
+                        .into_tuple();
+
+                    assert_eq!(lp_payment.token_identifier, managed_token_id!(LP_TOKEN_ID));
+                    assert_eq!(lp_payment.amount, managed_biguint!(expected_lp_amount));
+                    assert_eq!(
+                        first_payment.amount,
+                        managed_biguint!(expected_first_amount_left)
+                    );
+                    assert_eq!(
+                        second_payment.amount,
+                        managed_biguint!(expected_second_amount_left)
+                    );
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn remove_liquidity_single_token(
+        &mut self,
+        lp_amount: u64,
+        token_wanted: &[u8],
+        min_out: u64,
+        expected_amount: u64,
+    ) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pair_wrapper,
+                LP_TOKEN_ID,
+                0,
+                &rust_biguint!(lp_amount),
+                |sc| {
+                    let payment = sc.remove_liquidity_single_token(
+                        managed_token_id!(token_wanted),
+                        managed_biguint!(min_out),
+                    );
+
+                    assert_eq!(payment.token_identifier, managed_token_id!(token_wanted));
+                    assert_eq!(payment.amount, managed_biguint!(expected_amount));
+                },
+            )
+            .assert_ok();
+    }
+
     pub fn swap_fixed_input(
         &mut self,
         payment_token_id: &[u8],
@@ -199,6 +269,7 @@ where
                     let ret = sc.swap_tokens_fixed_input(
                         managed_token_id!(desired_token_id),
                         managed_biguint!(desired_amount_min),
+                        OptionalValue::None,
                     );
 
                     assert_eq!(ret.token_identifier, managed_token_id!(desired_token_id));
@@ -209,6 +280,51 @@ where
             .assert_ok();
     }
 
+    pub fn swap_fixed_input_expect_error(
+        &mut self,
+        payment_token_id: &[u8],
+        payment_amount: u64,
+        desired_token_id: &[u8],
+        expected_error: &str,
+    ) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pair_wrapper,
+                payment_token_id,
+                0,
+                &rust_biguint!(payment_amount),
+                |sc| {
+                    sc.swap_tokens_fixed_input(
+                        managed_token_id!(desired_token_id),
+                        managed_biguint!(1),
+                        OptionalValue::None,
+                    );
+                },
+            )
+            .assert_user_error(expected_error);
+    }
+
+    pub fn set_swap_hook(
+        &mut self,
+        hook_address: &Address,
+        flags: u8,
+        opt_expected_error: Option<&str>,
+    ) {
+        let result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_swap_hook(managed_address!(hook_address), flags);
+            },
+        );
+        match opt_expected_error {
+            Some(expected_error) => result.assert_user_error(expected_error),
+            None => result.assert_ok(),
+        }
+    }
+
     pub fn swap_fixed_output(
         &mut self,
         payment_token_id: &[u8],
@@ -238,6 +354,7 @@ where
                     let ret = sc.swap_tokens_fixed_output(
                         managed_token_id!(desired_token_id),
                         managed_biguint!(desired_amount),
+                        OptionalValue::None,
                     );
 
                     let (desired_token_output, payment_token_residuum) = ret.into_tuple();
@@ -275,6 +392,182 @@ where
         );
     }
 
+    pub fn set_curve(&mut self, curve: Curve) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.pair_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_curve(curve);
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn enable_concentrated_liquidity(&mut self, tick_spacing: i32) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.pair_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_position_token_identifier(managed_token_id!(POSITION_TOKEN_ID));
+                    sc.set_tick_spacing(tick_spacing);
+                },
+            )
+            .assert_ok();
+
+        let position_token_roles = [EsdtLocalRole::NftCreate, EsdtLocalRole::NftBurn];
+        self.b_mock.set_esdt_local_roles(
+            self.pair_wrapper.address_ref(),
+            POSITION_TOKEN_ID,
+            &position_token_roles[..],
+        );
+    }
+
+    pub fn add_concentrated_liquidity(
+        &mut self,
+        tick_lower: i32,
+        tick_upper: i32,
+        first_token_amount: u64,
+        second_token_amount: u64,
+    ) -> (u64, u64, u64) {
+        let payments = vec![
+            TxTokenTransfer {
+                token_identifier: WEGLD_TOKEN_ID.to_vec(),
+                nonce: 0,
+                value: rust_biguint!(first_token_amount),
+            },
+            TxTokenTransfer {
+                token_identifier: MEX_TOKEN_ID.to_vec(),
+                nonce: 0,
+                value: rust_biguint!(second_token_amount),
+            },
+        ];
+
+        let mut result = (0, 0, 0);
+        self.b_mock
+            .execute_esdt_multi_transfer(&self.user_address, &self.pair_wrapper, &payments, |sc| {
+                let (position, first_payment, second_payment) = sc
+                    .add_concentrated_liquidity(
+                        tick_lower,
+                        tick_upper,
+                        managed_biguint!(1u64),
+                        managed_biguint!(1u64),
+                    )
+                    .into_tuple();
+
+                assert_eq!(
+                    position.token_identifier,
+                    managed_token_id!(POSITION_TOKEN_ID)
+                );
+                result = (
+                    position.token_nonce,
+                    first_payment.amount.to_u64().unwrap(),
+                    second_payment.amount.to_u64().unwrap(),
+                );
+            })
+            .assert_ok();
+
+        result
+    }
+
+    pub fn remove_concentrated_liquidity(&mut self, position_nonce: u64) -> (u64, u64) {
+        let mut result = (0, 0);
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.user_address,
+                &self.pair_wrapper,
+                POSITION_TOKEN_ID,
+                position_nonce,
+                &rust_biguint!(1),
+                |sc| {
+                    let (first_payment, second_payment) = sc
+                        .remove_concentrated_liquidity(
+                            managed_biguint!(0u64),
+                            managed_biguint!(0u64),
+                        )
+                        .into_tuple();
+
+                    result = (
+                        first_payment.amount.to_u64().unwrap(),
+                        second_payment.amount.to_u64().unwrap(),
+                    );
+                },
+            )
+            .assert_ok();
+
+        result
+    }
+
+    pub fn check_concentrated_liquidity_state(
+        &mut self,
+        expected_tick: i32,
+        expected_active_liquidity: u64,
+    ) {
+        self.b_mock
+            .execute_query(&self.pair_wrapper, |sc| {
+                assert_eq!(sc.current_tick().get(), expected_tick);
+                assert_eq!(
+                    sc.active_concentrated_liquidity().get(),
+                    managed_biguint!(expected_active_liquidity)
+                );
+            })
+            .assert_ok();
+    }
+
+    /// Compares the ranged square root price with the one of the full range reserves
+    pub fn check_concentrated_price_drift(&mut self, max_sqrt_price_drift: u64) {
+        self.b_mock
+            .execute_query(&self.pair_wrapper, |sc| {
+                let first_token_reserve = sc.pair_reserve(&sc.first_token_id().get()).get();
+                let second_token_reserve = sc.pair_reserve(&sc.second_token_id().get()).get();
+                let full_range_sqrt_price =
+                    sc.get_sqrt_price_from_reserves(&first_token_reserve, &second_token_reserve);
+                let ranged_sqrt_price = sc.concentrated_sqrt_price().get();
+                let drift = if full_range_sqrt_price > ranged_sqrt_price {
+                    full_range_sqrt_price - ranged_sqrt_price
+                } else {
+                    ranged_sqrt_price - full_range_sqrt_price
+                };
+                assert!(drift <= managed_biguint!(max_sqrt_price_drift));
+            })
+            .assert_ok();
+    }
+
+    pub fn set_dynamic_fee_config(
+        &mut self,
+        min_fee_percent: u64,
+        max_fee_percent: u64,
+        observations_window: usize,
+        max_volatility: u64,
+    ) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.pair_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_dynamic_fee_config(
+                        min_fee_percent,
+                        max_fee_percent,
+                        observations_window,
+                        max_volatility,
+                    );
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn check_current_total_fee_percent(&mut self, expected_fee_percent: u64) {
+        self.b_mock
+            .execute_query(&self.pair_wrapper, |sc| {
+                assert_eq!(sc.get_current_total_fee_percent(), expected_fee_percent);
+            })
+            .assert_ok();
+    }
+
     pub fn check_lp_amount(&mut self, expected_amount: u64) {
         self.b_mock
             .execute_query(&self.pair_wrapper, |sc| {
@@ -411,12 +704,97 @@ where
         let _ = self.b_mock.execute_query(&self.pair_wrapper, |sc| {
             let mut price_observations = sc.price_observations();
 
-            let mut price_observation = price_observations.get(observation_index);
+            let mut price_observation = sc.get_price_observation_at(observation_index);
             price_observation.lp_supply_accumulated = BigUint::zero();
-            price_observations.set(observation_index, &price_observation);
+            price_observations.set(
+                observation_index,
+                &CompactPriceObservation::Full(price_observation),
+            );
         });
     }
 
+    pub fn set_observation_cardinality(&mut self, cardinality: usize) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.pair_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_observation_cardinality(cardinality);
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn enable_compact_price_observations(&mut self) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.pair_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.enable_compact_price_observations();
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn check_observation_cardinality(
+        &mut self,
+        expected_cardinality: usize,
+        expected_cardinality_next: usize,
+    ) {
+        self.b_mock
+            .execute_query(&self.pair_wrapper, |sc| {
+                let (cardinality, cardinality_next) =
+                    sc.get_observation_cardinality_view().into_tuple();
+                assert_eq!(cardinality, expected_cardinality);
+                assert_eq!(cardinality_next, expected_cardinality_next);
+            })
+            .assert_ok();
+    }
+
+    pub fn record_price_observation(
+        &mut self,
+        round: u64,
+        first_token_reserve: u64,
+        second_token_reserve: u64,
+        lp_supply: u64,
+    ) {
+        self.b_mock.set_block_round(round);
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.pair_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.update_safe_price(
+                        &managed_biguint!(first_token_reserve),
+                        &managed_biguint!(second_token_reserve),
+                        &managed_biguint!(lp_supply),
+                    );
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn check_price_observation_rounds(
+        &mut self,
+        expected_current_index: usize,
+        expected_rounds: &[u64],
+    ) {
+        self.b_mock
+            .execute_query(&self.pair_wrapper, |sc| {
+                assert_eq!(sc.safe_price_current_index().get(), expected_current_index);
+                assert_eq!(sc.price_observations().len(), expected_rounds.len());
+                for (index, expected_round) in expected_rounds.iter().enumerate() {
+                    let price_observation = sc.get_price_observation_at(index + 1);
+                    assert_eq!(price_observation.recording_round, *expected_round);
+                }
+            })
+            .assert_ok();
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn check_safe_price_from_second_pair(
         &mut self,
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           63
+// Endpoints:                          129
 // Async Callback (empty):               1
-// Total number of exported functions:  66
+// Total number of exported functions: 132
 
 #![no_std]
 
@@ -22,6 +22,8 @@ multiversx_sc_wasm_adapter::endpoints! {
         upgrade => upgrade
         setLpTokenIdentifier => set_lp_token_identifier
         getFeeState => is_fee_enabled
+        setFeeMode => set_fee_mode
+        setProtocolFeeRecipient => set_protocol_fee_recipient
         whitelist => whitelist_endpoint
         removeWhitelist => remove_whitelist
         addTrustedSwapPair => add_trusted_swap_pair
@@ -33,18 +35,28 @@ multiversx_sc_wasm_adapter::endpoints! {
         getWhitelistedManagedAddresses => get_whitelisted_managed_addresses
         getFeesCollectorAddress => fees_collector_address
         getFeesCollectorCutPercentage => fees_collector_cut_percentage
+        getFeeMode => fee_mode
+        getProtocolFeeRecipient => protocol_fee_recipient
+        getKLast => k_last
         setStateActiveNoSwaps => set_state_active_no_swaps
         setFeePercents => set_fee_percent
+        setCurve => set_curve
+        getCurve => get_curve
         getLpTokenIdentifier => get_lp_token_identifier
         getTotalFeePercent => total_fee_percent
         getSpecialFee => special_fee_percent
         getRouterManagedAddress => router_address
+        getTemplateVersion => template_version
         getFirstTokenId => first_token_id
         getSecondTokenId => second_token_id
         getTotalSupply => lp_token_supply
         getInitialLiquidtyAdder => initial_liquidity_adder
         getReserve => pair_reserve
+        setObservationCardinality => set_observation_cardinality
+        getObservationCardinality => get_observation_cardinality_view
+        enableCompactPriceObservations => enable_compact_price_observations
         getSafePriceCurrentIndex => safe_price_current_index
+        areCompactPriceObservationsEnabled => compact_price_observations_enabled
         updateAndGetTokensForGivenPositionWithSafePrice => update_and_get_tokens_for_given_position_with_safe_price
         updateAndGetSafePrice => update_and_get_safe_price
         setLockingDeadlineEpoch => set_locking_deadline_epoch
@@ -61,19 +73,66 @@ multiversx_sc_wasm_adapter::endpoints! {
         removeFromPauseWhitelist => remove_from_pause_whitelist
         pause => pause
         resume => resume
+        setWithdrawOnly => set_withdraw_only
         getState => state
         addInitialLiquidity => add_initial_liquidity
         addLiquidity => add_liquidity
+        addLiquiditySingleToken => add_liquidity_single_token
         removeLiquidity => remove_liquidity
         removeLiquidityAndBuyBackAndBurnToken => remove_liquidity_and_burn_token
+        removeLiquiditySingleToken => remove_liquidity_single_token
         swapNoFeeAndForward => swap_no_fee
         swapTokensFixedInput => swap_tokens_fixed_input
         swapTokensFixedOutput => swap_tokens_fixed_output
+        flashLoan => flash_loan
+        repayFlashLoan => repay_flash_loan
+        skim => skim
+        sync => sync
         getTokensForGivenPosition => get_tokens_for_given_position
         getReservesAndTotalSupply => get_reserves_and_total_supply
         getAmountOut => get_amount_out_view
         getAmountIn => get_amount_in_view
+        getSwapQuote => get_swap_quote
         getEquivalent => get_equivalent
+        getConcentratedPositionsCount => concentrated_positions_count
+        getTickSpacing => tick_spacing
+        getConcentratedSqrtPrice => concentrated_sqrt_price
+        getCurrentTick => current_tick
+        getActiveConcentratedLiquidity => active_concentrated_liquidity
+        getTickInfo => tick_info
+        getFeeGrowthGlobalFirst => fee_growth_global_first
+        getFeeGrowthGlobalSecond => fee_growth_global_second
+        getConcentratedReserve => concentrated_reserve
+        setPositionTokenIdentifier => set_position_token_identifier
+        setTickSpacing => set_tick_spacing
+        addConcentratedLiquidity => add_concentrated_liquidity
+        removeConcentratedLiquidity => remove_concentrated_liquidity
+        collectConcentratedFees => collect_concentrated_fees
+        getConcentratedPositionFees => get_concentrated_position_fees
+        getConcentratedPositionAmounts => get_concentrated_position_amounts
+        getPositionTokenIdentifier => position_token_identifier
+        setDynamicFeeConfig => set_dynamic_fee_config
+        disableDynamicFee => disable_dynamic_fee
+        getDynamicFeeConfig => get_dynamic_fee_config
+        getCurrentTotalFeePercent => get_current_total_fee_percent
+        getEpochStats => get_epoch_stats
+        getAggregatedStats => get_aggregated_stats
+        setEgldWrapper => set_egld_wrapper
+        getEgldWrapperAddress => egld_wrapper_address
+        getWrappedEgldTokenId => wrapped_egld_token_id
+        setSwapHook => set_swap_hook
+        removeSwapHook => remove_swap_hook
+        getSwapHook => swap_hook
+        setCircuitBreakerConfig => set_circuit_breaker_config
+        disableCircuitBreaker => disable_circuit_breaker
+        getCircuitBreakerConfig => get_circuit_breaker_config
+        getCircuitBreakerStatus => get_circuit_breaker_status
+        addFeeDiscountTier => add_fee_discount_tier
+        removeFeeDiscountTier => remove_fee_discount_tier
+        getEffectiveFeePercent => get_effective_fee_percent
+        getFeeDiscountTiers => fee_discount_tiers
+        setEnergyFactoryAddress => set_energy_factory_address
+        getEnergyFactoryAddress => energy_factory_address
         getLpTokensSafePriceByDefaultOffset => get_lp_tokens_safe_price_by_default_offset
         getLpTokensSafePriceByRoundOffset => get_lp_tokens_safe_price_by_round_offset
         getLpTokensSafePriceByTimestampOffset => get_lp_tokens_safe_price_by_timestamp_offset
@@ -82,6 +141,13 @@ multiversx_sc_wasm_adapter::endpoints! {
         getSafePriceByRoundOffset => get_safe_price_by_round_offset
         getSafePriceByTimestampOffset => get_safe_price_by_timestamp_offset
         getSafePrice => get_safe_price
+        getArithmeticTwap => get_arithmetic_twap
+        getGeometricTwap => get_geometric_twap
+        getArithmeticTwapByTimestamps => get_arithmetic_twap_by_timestamps
+        getGeometricTwapByTimestamps => get_geometric_twap_by_timestamps
+        getLpTokensFairPrice => get_lp_tokens_fair_price
+        getLpTokensFairPriceByDefaultOffset => get_lp_tokens_fair_price_by_default_offset
+        getLpTokensFairValue => get_lp_tokens_fair_value
         getPriceObservation => get_price_observation_view
     )
 }
@@ -5,9 +5,9 @@
 ////////////////////////////////////////////////////
 
 // Init:                                 1
-// Endpoints:                            9
+// Endpoints:                           16
 // Async Callback (empty):               1
-// Total number of exported functions:  11
+// Total number of exported functions:  18
 
 #![no_std]
 
@@ -27,6 +27,13 @@ multiversx_sc_wasm_adapter::external_view_endpoints! {
         getSafePriceByRoundOffset => get_safe_price_by_round_offset
         getSafePriceByTimestampOffset => get_safe_price_by_timestamp_offset
         getSafePrice => get_safe_price
+        getArithmeticTwap => get_arithmetic_twap
+        getGeometricTwap => get_geometric_twap
+        getArithmeticTwapByTimestamps => get_arithmetic_twap_by_timestamps
+        getGeometricTwapByTimestamps => get_geometric_twap_by_timestamps
+        getLpTokensFairPrice => get_lp_tokens_fair_price
+        getLpTokensFairPriceByDefaultOffset => get_lp_tokens_fair_price_by_default_offset
+        getLpTokensFairValue => get_lp_tokens_fair_value
         getPriceObservation => get_price_observation_view
     )
 }
@@ -0,0 +1,44 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+/// Accepts any payment and records it, together with the call argument
+#[multiversx_sc::contract]
+pub trait PaymentReceiverMock {
+    #[init]
+    fn init(&self) {}
+
+    #[payable("*")]
+    #[endpoint(receivePayment)]
+    fn receive_payment(&self, tag: ManagedBuffer) {
+        let payment = self.call_value().egld_or_single_esdt();
+        require!(payment.amount > 0u64, "Payment must be more than 0");
+
+        self.last_payment().set(payment);
+        self.last_tag().set(tag);
+    }
+
+    /// Same as `receivePayment`, then sends `refund_amount` of the received token back to the caller
+    #[payable("*")]
+    #[endpoint(receivePaymentWithRefund)]
+    fn receive_payment_with_refund(&self, tag: ManagedBuffer, refund_amount: BigUint) {
+        self.receive_payment(tag);
+
+        let payment = self.last_payment().get();
+        let caller = self.blockchain().get_caller();
+        self.send().direct(
+            &caller,
+            &payment.token_identifier,
+            payment.token_nonce,
+            &refund_amount,
+        );
+    }
+
+    #[view(getLastPayment)]
+    #[storage_mapper("lastPayment")]
+    fn last_payment(&self) -> SingleValueMapper<EgldOrEsdtTokenPayment>;
+
+    #[view(getLastTag)]
+    #[storage_mapper("lastTag")]
+    fn last_tag(&self) -> SingleValueMapper<ManagedBuffer>;
+}
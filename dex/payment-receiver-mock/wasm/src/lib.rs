@@ -0,0 +1,28 @@
+// Code generated by the multiversx-sc build system. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+// Init:                                 1
+// Endpoints:                            4
+// Async Callback (empty):               1
+// Total number of exported functions:   6
+
+#![no_std]
+
+multiversx_sc_wasm_adapter::allocator!();
+multiversx_sc_wasm_adapter::panic_handler!();
+
+multiversx_sc_wasm_adapter::endpoints! {
+    payment_receiver_mock
+    (
+        init => init
+        receivePayment => receive_payment
+        receivePaymentWithRefund => receive_payment_with_refund
+        getLastPayment => last_payment
+        getLastTag => last_tag
+    )
+}
+
+multiversx_sc_wasm_adapter::async_callback_empty! {}
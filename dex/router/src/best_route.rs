@@ -0,0 +1,318 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{config, events, factory, factory::PairTokens, multi_pair_swap};
+use pair::{
+    config::{Curve, MAX_PERCENTAGE},
+    read_pair_storage,
+};
+use pausable::State;
+
+pub const MAX_ROUTE_HOPS: usize = 3;
+pub const MAX_ROUTE_TOKENS: usize = 5;
+
+#[derive(
+    ManagedVecItem,
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    Clone,
+    PartialEq,
+    Debug,
+)]
+pub struct RouteStep<M: ManagedTypeApi> {
+    pub pair_address: ManagedAddress<M>,
+    pub token_out: TokenIdentifier<M>,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct SwapRoute<M: ManagedTypeApi> {
+    pub steps: ManagedVec<M, RouteStep<M>>,
+    pub amount_out: BigUint<M>,
+}
+
+#[derive(ManagedVecItem)]
+pub struct RoutePool<M: ManagedTypeApi> {
+    pub address: ManagedAddress<M>,
+    pub first_token_id: TokenIdentifier<M>,
+    pub second_token_id: TokenIdentifier<M>,
+    pub first_token_reserve: BigUint<M>,
+    pub second_token_reserve: BigUint<M>,
+    pub total_fee_percent: u64,
+}
+
+struct RouteSearch<M: ManagedTypeApi> {
+    token_in: TokenIdentifier<M>,
+    token_out: TokenIdentifier<M>,
+    max_hops: usize,
+    steps: ManagedVec<M, RouteStep<M>>,
+    best_route: SwapRoute<M>,
+}
+
+impl<M: ManagedTypeApi> RouteSearch<M> {
+    fn is_visited(&self, token_id: &TokenIdentifier<M>) -> bool {
+        if token_id == &self.token_in {
+            return true;
+        }
+
+        self.steps.iter().any(|step| &step.token_out == token_id)
+    }
+}
+
+#[multiversx_sc::module]
+pub trait BestRouteModule:
+    config::ConfigModule
+    + read_pair_storage::ReadPairStorageModule
+    + factory::FactoryModule
+    + token_send::TokenSendModule
+    + events::EventsModule
+    + multi_pair_swap::MultiPairSwap
+{
+    /// Adds a token that routes can go through, besides the input and output tokens.
+    /// Their number is capped, so that finding a route has a bounded cost.
+    #[only_owner]
+    #[endpoint(addRouteToken)]
+    fn add_route_token(&self, token_id: TokenIdentifier) {
+        require!(token_id.is_valid_esdt_identifier(), "Invalid token ID");
+        require!(
+            self.route_tokens().len() < MAX_ROUTE_TOKENS,
+            "Too many route tokens"
+        );
+
+        let is_new = self.route_tokens().insert(token_id);
+        require!(is_new, "Route token already added");
+    }
+
+    #[only_owner]
+    #[endpoint(removeRouteToken)]
+    fn remove_route_token(&self, token_id: TokenIdentifier) {
+        let is_removed = self.route_tokens().swap_remove(&token_id);
+        require!(is_removed, "Unknown route token");
+    }
+
+    /// Finds the path of at most `max_hops` pairs with the highest amount out.
+    /// Only the pairs between the input token, the output token and the route tokens are used,
+    /// which are looked up directly instead of going through all the pairs.
+    /// Only active constant product pairs are used, quoted with their base total fee,
+    /// so the actual amount out can be lower if the pair charges a dynamic fee.
+    #[view(getBestRoute)]
+    fn get_best_route(
+        &self,
+        token_in: TokenIdentifier,
+        token_out: TokenIdentifier,
+        amount_in: BigUint,
+        max_hops: usize,
+    ) -> SwapRoute<Self::Api> {
+        require!(token_in != token_out, "Tokens should differ");
+        require!(amount_in > 0u64, "Invalid amount. Should not be zero");
+        require!(
+            max_hops > 0 && max_hops <= MAX_ROUTE_HOPS,
+            "Invalid number of hops"
+        );
+
+        let pools = self.get_route_pools(&token_in, &token_out);
+        let mut search = RouteSearch {
+            token_in: token_in.clone(),
+            token_out,
+            max_hops,
+            steps: ManagedVec::new(),
+            best_route: SwapRoute {
+                steps: ManagedVec::new(),
+                amount_out: BigUint::zero(),
+            },
+        };
+        self.explore_routes(&pools, &mut search, &token_in, &amount_in);
+        require!(!search.best_route.steps.is_empty(), "No route found");
+
+        search.best_route
+    }
+
+    /// Swaps the payment through the best route, see `getBestRoute`
+    #[payable("*")]
+    #[endpoint(swapBestRoute)]
+    fn swap_best_route(
+        &self,
+        token_out: TokenIdentifier,
+        amount_out_min: BigUint,
+        max_hops: usize,
+    ) -> EsdtTokenPayment {
+        require!(self.is_active(), "Not active");
+        require!(amount_out_min > 0u64, "Invalid amount. Should not be zero");
+
+        let payment = self.call_value().single_esdt();
+        require!(payment.token_nonce == 0, "Invalid nonce. Should be zero");
+
+        let route = self.get_best_route(
+            payment.token_identifier.clone(),
+            token_out,
+            payment.amount.clone(),
+            max_hops,
+        );
+
+        let caller = self.blockchain().get_caller();
+        let last_step_index = route.steps.len() - 1;
+        let mut last_payment = payment.clone();
+        for (step_index, step) in route.steps.iter().enumerate() {
+            // only the final amount out is checked, so that intermediate rounding can't fail the swap
+            let step_amount_out_min = if step_index == last_step_index {
+                amount_out_min.clone()
+            } else {
+                BigUint::from(1u32)
+            };
+            last_payment = self.actual_swap_fixed_input(
+                step.pair_address.clone(),
+                &caller,
+                last_payment.token_identifier,
+                last_payment.amount,
+                step.token_out.clone(),
+                step_amount_out_min,
+            );
+        }
+        require!(last_payment.amount >= amount_out_min, "Slippage exceeded");
+
+        self.send()
+            .direct_non_zero_esdt_payment(&caller, &last_payment);
+        self.emit_multi_pair_swap_event(
+            caller,
+            payment.token_identifier,
+            payment.amount,
+            ManagedVec::from_single_item(last_payment.clone()),
+        );
+
+        last_payment
+    }
+
+    fn explore_routes(
+        &self,
+        pools: &ManagedVec<RoutePool<Self::Api>>,
+        search: &mut RouteSearch<Self::Api>,
+        token_in: &TokenIdentifier,
+        amount_in: &BigUint,
+    ) {
+        for pool in pools.iter() {
+            let (token_out, reserve_in, reserve_out) = if &pool.first_token_id == token_in {
+                (
+                    &pool.second_token_id,
+                    &pool.first_token_reserve,
+                    &pool.second_token_reserve,
+                )
+            } else if &pool.second_token_id == token_in {
+                (
+                    &pool.first_token_id,
+                    &pool.second_token_reserve,
+                    &pool.first_token_reserve,
+                )
+            } else {
+                continue;
+            };
+            if search.is_visited(token_out) {
+                continue;
+            }
+
+            let amount_in_with_fee = amount_in * (MAX_PERCENTAGE - pool.total_fee_percent);
+            let amount_out = &amount_in_with_fee * reserve_out
+                / (reserve_in * MAX_PERCENTAGE + &amount_in_with_fee);
+            if amount_out == 0u64 || &amount_out >= reserve_out {
+                continue;
+            }
+
+            search.steps.push(RouteStep {
+                pair_address: pool.address.clone(),
+                token_out: token_out.clone(),
+            });
+            if token_out == &search.token_out {
+                if amount_out > search.best_route.amount_out {
+                    search.best_route = SwapRoute {
+                        steps: search.steps.clone(),
+                        amount_out,
+                    };
+                }
+            } else if search.steps.len() < search.max_hops {
+                self.explore_routes(pools, search, token_out, &amount_out);
+            }
+            search.steps.remove(search.steps.len() - 1);
+        }
+    }
+
+    /// The usable pairs between any two of the input token, the output token and the route tokens
+    fn get_route_pools(
+        &self,
+        token_in: &TokenIdentifier,
+        token_out: &TokenIdentifier,
+    ) -> ManagedVec<RoutePool<Self::Api>> {
+        let mut tokens = ManagedVec::<Self::Api, TokenIdentifier>::new();
+        tokens.push(token_in.clone());
+        tokens.push(token_out.clone());
+        for token_id in self.route_tokens().iter() {
+            if &token_id != token_in && &token_id != token_out {
+                tokens.push(token_id);
+            }
+        }
+
+        let mut pools = ManagedVec::new();
+        for (index, first_token_id) in tokens.iter().enumerate() {
+            for second_token_id in tokens.iter().skip(index + 1) {
+                if let Some(pool) = self.get_route_pool(&first_token_id, &second_token_id) {
+                    pools.push(pool);
+                }
+            }
+        }
+
+        pools
+    }
+
+    fn get_route_pool(
+        &self,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+    ) -> Option<RoutePool<Self::Api>> {
+        let mut pair_tokens = PairTokens {
+            first_token_id: first_token_id.clone(),
+            second_token_id: second_token_id.clone(),
+        };
+        let mut opt_address = self.pair_map().get(&pair_tokens);
+        if opt_address.is_none() {
+            pair_tokens = PairTokens {
+                first_token_id: second_token_id.clone(),
+                second_token_id: first_token_id.clone(),
+            };
+            opt_address = self.pair_map().get(&pair_tokens);
+        }
+
+        let address = opt_address?;
+        if self.get_state_mapper(address.clone()).get() != State::Active
+            || self.get_pair_curve(address.clone()) != Curve::ConstantProduct
+            || self
+                .get_concentrated_positions_count_mapper(address.clone())
+                .get()
+                > 0
+        {
+            return None;
+        }
+
+        let first_token_reserve = self
+            .get_pair_reserve_mapper(address.clone(), &pair_tokens.first_token_id)
+            .get();
+        let second_token_reserve = self
+            .get_pair_reserve_mapper(address.clone(), &pair_tokens.second_token_id)
+            .get();
+        if first_token_reserve == 0u64 || second_token_reserve == 0u64 {
+            return None;
+        }
+
+        Some(RoutePool {
+            total_fee_percent: self.get_total_fee_percent_mapper(address.clone()).get(),
+            address,
+            first_token_id: pair_tokens.first_token_id,
+            second_token_id: pair_tokens.second_token_id,
+            first_token_reserve,
+            second_token_reserve,
+        })
+    }
+
+    #[view(getRouteTokens)]
+    #[storage_mapper("routeTokens")]
+    fn route_tokens(&self) -> UnorderedSetMapper<TokenIdentifier>;
+}
@@ -11,6 +11,15 @@ pub trait ConfigModule: read_pair_storage::ReadPairStorageModule {
     }
 
     fn check_is_pair_sc(&self, pair_address: &ManagedAddress) {
+        require!(self.is_pair_sc(pair_address), "Not a pair SC");
+    }
+
+    /// Pairs from the pair map and registered multi-asset pools
+    fn is_pair_sc(&self, pair_address: &ManagedAddress) -> bool {
+        if self.multi_asset_pools().contains(pair_address) {
+            return true;
+        }
+
         let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
         let second_token_id = self.get_second_token_id_mapper(pair_address.clone()).get();
 
@@ -28,11 +37,9 @@ pub trait ConfigModule: read_pair_storage::ReadPairStorageModule {
             pair_map_address_opt = self.pair_map().get(&reverse_pair_tokens);
         }
 
-        require!(pair_map_address_opt.is_some(), "Not a pair SC");
-
-        unsafe {
-            let pair_map_address = pair_map_address_opt.unwrap_unchecked();
-            require!(&pair_map_address == pair_address, "Not a pair SC");
+        match pair_map_address_opt {
+            Some(pair_map_address) => &pair_map_address == pair_address,
+            None => false,
         }
     }
 
@@ -57,16 +64,67 @@ pub trait ConfigModule: read_pair_storage::ReadPairStorageModule {
     #[only_owner]
     #[endpoint(setPairTemplateAddress)]
     fn set_pair_template_address(&self, address: ManagedAddress) {
-        self.pair_template_address().set(&address);
+        self.set_pair_template(&address);
+    }
+
+    /// Every new template gets a new version, so pairs still running an older one can be found
+    fn set_pair_template(&self, address: &ManagedAddress) {
+        self.pair_template_address().set(address);
+        self.pair_template_version().update(|version| *version += 1);
+    }
+
+    /// EGLD paid to `multiPairSwap` is wrapped through the given contract,
+    /// and `EGLD` can be given as a wanted token to receive unwrapped EGLD
+    #[only_owner]
+    #[endpoint(setEgldWrapper)]
+    fn set_egld_wrapper(
+        &self,
+        wrapper_address: ManagedAddress,
+        wrapped_egld_token_id: TokenIdentifier,
+    ) {
+        require!(
+            self.blockchain().is_smart_contract(&wrapper_address),
+            "Invalid SC Address"
+        );
+        require!(
+            wrapped_egld_token_id.is_valid_esdt_identifier(),
+            "Invalid token ID"
+        );
+
+        self.egld_wrapper_address().set(&wrapper_address);
+        self.wrapped_egld_token_id().set(&wrapped_egld_token_id);
     }
 
     #[storage_mapper("pair_map")]
     fn pair_map(&self) -> MapMapper<PairTokens<Self::Api>, ManagedAddress>;
 
+    /// The keys of `pair_map`, which can be iterated starting from a given key.
+    /// Only meant for reading, pairs are added and removed through `pair_map`.
+    #[storage_mapper("pair_map")]
+    fn pair_map_keys(&self) -> SetMapper<PairTokens<Self::Api>>;
+
     #[view(getPairTemplateAddress)]
     #[storage_mapper("pair_template_address")]
     fn pair_template_address(&self) -> SingleValueMapper<ManagedAddress>;
 
+    #[view(getPairTemplateVersion)]
+    #[storage_mapper("pairTemplateVersion")]
+    fn pair_template_version(&self) -> SingleValueMapper<u64>;
+
+    /// The template version a pair was deployed with, or upgraded to.
+    /// Upgrades are async calls without a result, so the upgraded pair records the version itself.
+    #[view(getPairVersion)]
+    fn get_pair_version(&self, pair_address: &ManagedAddress) -> u64 {
+        let upgrade_version = self.get_template_version_mapper(pair_address.clone()).get();
+        core::cmp::max(
+            self.pair_deploy_version(pair_address).get(),
+            upgrade_version,
+        )
+    }
+
+    #[storage_mapper("pairDeployVersion")]
+    fn pair_deploy_version(&self, pair_address: &ManagedAddress) -> SingleValueMapper<u64>;
+
     #[view(getTemporaryOwnerPeriod)]
     #[storage_mapper("temporary_owner_period")]
     fn temporary_owner_period(&self) -> SingleValueMapper<u64>;
@@ -83,4 +141,16 @@ pub trait ConfigModule: read_pair_storage::ReadPairStorageModule {
     #[view(getCommonTokensForUserPairs)]
     #[storage_mapper("commonTokensForUserPairs")]
     fn common_tokens_for_user_pairs(&self) -> UnorderedSetMapper<TokenIdentifier>;
+
+    #[view(getEgldWrapperAddress)]
+    #[storage_mapper("egldWrapperAddress")]
+    fn egld_wrapper_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getWrappedEgldTokenId)]
+    #[storage_mapper("wrappedEgldTokenId")]
+    fn wrapped_egld_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
+
+    #[view(getAllMultiAssetPools)]
+    #[storage_mapper("multiAssetPools")]
+    fn multi_asset_pools(&self) -> UnorderedSetMapper<ManagedAddress>;
 }
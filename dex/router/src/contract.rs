@@ -4,15 +4,20 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
+pub mod best_route;
 pub mod config;
 pub mod enable_swap_by_user;
 mod events;
 pub mod factory;
 pub mod multi_pair_swap;
+pub mod pair_upgrades;
+pub mod split_route_swap;
 
+use energy_query::ProxyTrait as _;
 use factory::PairTokens;
 use pair::config::ProxyTrait as _;
 use pair::fee::ProxyTrait as _;
+use pair::swap_hooks::ProxyTrait as _;
 use pair::{read_pair_storage, ProxyTrait as _};
 use pausable::ProxyTrait as _;
 
@@ -31,6 +36,10 @@ pub trait Router:
     + factory::FactoryModule
     + events::EventsModule
     + multi_pair_swap::MultiPairSwap
+    + best_route::BestRouteModule
+    + split_route_swap::SplitRouteSwapModule
+    + pair_upgrades::PairUpgradesModule
+    + multiversx_sc_modules::ongoing_operation::OngoingOperationModule
     + token_send::TokenSendModule
     + enable_swap_by_user::EnableSwapByUserModule
 {
@@ -76,6 +85,16 @@ pub trait Router:
         }
     }
 
+    #[only_owner]
+    #[endpoint(setPairWithdrawOnly)]
+    fn set_pair_withdraw_only(&self, address: ManagedAddress) {
+        self.check_is_pair_sc(&address);
+        let _: IgnoreValue = self
+            .pair_contract_proxy(address)
+            .set_withdraw_only()
+            .execute_on_dest_context();
+    }
+
     #[allow_multiple_var_args]
     #[endpoint(createPair)]
     fn create_pair_endpoint(
@@ -297,10 +316,35 @@ pub trait Router:
                 })
                 .unwrap_or_else(ManagedAddress::zero);
         }
+        self.pair_deploy_version(&pair_address).clear();
 
         pair_address
     }
 
+    /// Registers a multi-asset pool, so that it can be used in `multiPairSwap`.
+    /// Such pools hold more than two tokens, so they are not part of the pair map.
+    #[only_owner]
+    #[endpoint(addMultiAssetPool)]
+    fn add_multi_asset_pool(&self, pool_address: ManagedAddress) {
+        require!(self.is_active(), "Not active");
+        require!(
+            self.blockchain().is_smart_contract(&pool_address),
+            "Invalid pool address"
+        );
+
+        let is_new = self.multi_asset_pools().insert(pool_address);
+        require!(is_new, "Pool already added");
+    }
+
+    #[only_owner]
+    #[endpoint(removeMultiAssetPool)]
+    fn remove_multi_asset_pool(&self, pool_address: ManagedAddress) {
+        require!(self.is_active(), "Not active");
+
+        let was_removed = self.multi_asset_pools().swap_remove(&pool_address);
+        require!(was_removed, "Pool not found");
+    }
+
     #[only_owner]
     #[endpoint(setFeeOn)]
     fn set_fee_on(
@@ -335,6 +379,51 @@ pub trait Router:
             .execute_on_dest_context();
     }
 
+    #[only_owner]
+    #[endpoint(setPairEnergyFactoryAddress)]
+    fn set_pair_energy_factory_address(
+        &self,
+        pair_address: ManagedAddress,
+        energy_factory_address: ManagedAddress,
+    ) {
+        require!(self.is_active(), "Not active");
+        self.check_is_pair_sc(&pair_address);
+
+        let _: IgnoreValue = self
+            .pair_contract_proxy(pair_address)
+            .set_energy_factory_address(energy_factory_address)
+            .execute_on_dest_context();
+    }
+
+    #[only_owner]
+    #[endpoint(setPairSwapHook)]
+    fn set_pair_swap_hook(
+        &self,
+        pair_address: ManagedAddress,
+        hook_address: ManagedAddress,
+        flags: u8,
+    ) {
+        require!(self.is_active(), "Not active");
+        self.check_is_pair_sc(&pair_address);
+
+        let _: IgnoreValue = self
+            .pair_contract_proxy(pair_address)
+            .set_swap_hook(hook_address, flags)
+            .execute_on_dest_context();
+    }
+
+    #[only_owner]
+    #[endpoint(removePairSwapHook)]
+    fn remove_pair_swap_hook(&self, pair_address: ManagedAddress) {
+        require!(self.is_active(), "Not active");
+        self.check_is_pair_sc(&pair_address);
+
+        let _: IgnoreValue = self
+            .pair_contract_proxy(pair_address)
+            .remove_swap_hook()
+            .execute_on_dest_context();
+    }
+
     #[callback]
     fn lp_token_issue_callback(
         &self,
@@ -6,7 +6,7 @@ use pair::read_pair_storage;
 
 const TEMPORARY_OWNER_PERIOD_BLOCKS: u64 = 50;
 
-#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, TypeAbi)]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, TypeAbi)]
 pub struct PairTokens<M: ManagedTypeApi> {
     pub first_token_id: TokenIdentifier<M>,
     pub second_token_id: TokenIdentifier<M>,
@@ -26,7 +26,7 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
 
     fn init_factory(&self, pair_template_address_opt: Option<ManagedAddress>) {
         if let Some(addr) = pair_template_address_opt {
-            self.pair_template_address().set(&addr);
+            self.set_pair_template(&addr);
         }
 
         self.temporary_owner_period()
@@ -72,6 +72,8 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
             },
             new_address.clone(),
         );
+        self.pair_deploy_version(&new_address)
+            .set(self.pair_template_version().get());
         self.pair_temporary_owner().insert(
             new_address.clone(),
             (
@@ -83,6 +85,7 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
     }
 
     fn upgrade_pair(&self, pair_address: ManagedAddress) {
+        let template_version = self.pair_template_version().get();
         let pair_template_address = self.pair_template_address().get();
         let code_metadata =
             CodeMetadata::UPGRADEABLE | CodeMetadata::READABLE | CodeMetadata::PAYABLE_BY_SC;
@@ -91,6 +94,7 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
             .raw_upgrade()
             .from_source(pair_template_address)
             .code_metadata(code_metadata)
+            .argument(&template_version)
             .upgrade_async_call_and_exit();
     }
 
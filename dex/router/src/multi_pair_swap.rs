@@ -3,7 +3,11 @@ multiversx_sc::derive_imports!();
 
 use super::factory;
 use crate::{config, events};
-use pair::{pair_actions::swap::ProxyTrait as _, read_pair_storage};
+use pair::{
+    native_egld::{egld_wrapper_proxy, native_egld_token_id},
+    pair_actions::swap::ProxyTrait as _,
+    read_pair_storage,
+};
 
 type SwapOperationType<M> =
     MultiValue4<ManagedAddress<M>, ManagedBuffer<M>, TokenIdentifier<M>, BigUint<M>>;
@@ -11,6 +15,14 @@ type SwapOperationType<M> =
 pub const SWAP_TOKENS_FIXED_INPUT_FUNC_NAME: &[u8] = b"swapTokensFixedInput";
 pub const SWAP_TOKENS_FIXED_OUTPUT_FUNC_NAME: &[u8] = b"swapTokensFixedOutput";
 
+pub struct SwapOperationsOutput<M: ManagedTypeApi> {
+    pub token_in: TokenIdentifier<M>,
+    pub amount_in: BigUint<M>,
+    pub residuals: ManagedVec<M, EsdtTokenPayment<M>>,
+    pub last_payment: EsdtTokenPayment<M>,
+    pub unwrap_egld: bool,
+}
+
 #[multiversx_sc::module]
 pub trait MultiPairSwap:
     config::ConfigModule
@@ -25,9 +37,130 @@ pub trait MultiPairSwap:
         &self,
         swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
     ) -> ManagedVec<EsdtTokenPayment> {
+        let caller = self.blockchain().get_caller();
+        self.perform_multi_pair_swap(&caller, &BigUint::zero(), swap_operations)
+    }
+
+    /// Same as `multiPairSwap`, but the whole swap fails if the final output is lower than
+    /// `amount_out_min` or if the block timestamp is past `deadline`.
+    /// The output is sent to the recipient, if given, instead of the caller.
+    #[payable("*")]
+    #[endpoint(multiPairSwapWithOptions)]
+    fn multi_pair_swap_with_options(
+        &self,
+        amount_out_min: BigUint,
+        deadline: u64,
+        opt_recipient: Option<ManagedAddress>,
+        swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        require!(amount_out_min > 0u64, "Invalid amount. Should not be zero");
+        require!(
+            self.blockchain().get_block_timestamp() <= deadline,
+            "Deadline passed"
+        );
+
+        let recipient = opt_recipient.unwrap_or_else(|| self.blockchain().get_caller());
+        self.perform_multi_pair_swap(&recipient, &amount_out_min, swap_operations)
+    }
+
+    /// Swaps through the operations like `multiPairSwap`, then sends the output of the last
+    /// operation to `destination` through a synchronous call to `endpoint_name`,
+    /// so the destination has to be in the same shard. Leftovers are sent back to the caller,
+    /// together with anything the destination transfers back to the router.
+    /// The destination cannot be a pair, a multi-asset pool or the router itself,
+    /// since pairs trust the router to pass the original caller.
+    #[payable("*")]
+    #[endpoint(multiPairSwapAndCall)]
+    fn multi_pair_swap_and_call(
+        &self,
+        destination: ManagedAddress,
+        endpoint_name: ManagedBuffer,
+        endpoint_args: ManagedVec<ManagedBuffer>,
+        swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        require!(
+            self.blockchain().is_smart_contract(&destination),
+            "Invalid SC Address"
+        );
+        require!(
+            destination != self.blockchain().get_sc_address() && !self.is_pair_sc(&destination),
+            "Invalid destination"
+        );
+        require!(!endpoint_name.is_empty(), "Invalid endpoint name");
+
+        let caller = self.blockchain().get_caller();
+        let output = self.swap_through_operations(&caller, swap_operations);
+        let mut payments = if output.unwrap_egld {
+            self.send_payments_unwrapping_egld(&caller, &output.residuals)
+        } else {
+            if !output.residuals.is_empty() {
+                self.send().direct_multi(&caller, &output.residuals);
+            }
+            output.residuals
+        };
+
+        let (forwarded_payment, back_transfers) = self.forward_payment_with_call(
+            &destination,
+            endpoint_name,
+            endpoint_args,
+            output.last_payment,
+            output.unwrap_egld,
+        );
+        payments.push(forwarded_payment);
+        payments.append_vec(self.send_back_transfers(&caller, back_transfers));
+
+        self.emit_multi_pair_swap_event(
+            caller,
+            output.token_in,
+            output.amount_in,
+            payments.clone(),
+        );
+
+        payments
+    }
+
+    fn perform_multi_pair_swap(
+        &self,
+        recipient: &ManagedAddress,
+        amount_out_min: &BigUint,
+        swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let caller = self.blockchain().get_caller();
+        let output = self.swap_through_operations(&caller, swap_operations);
+        require!(
+            &output.last_payment.amount >= amount_out_min,
+            "Slippage exceeded"
+        );
+
+        let mut payments = output.residuals;
+        payments.push(output.last_payment);
+        let payments = if output.unwrap_egld {
+            self.send_payments_unwrapping_egld(recipient, &payments)
+        } else {
+            self.send().direct_multi(recipient, &payments);
+            payments
+        };
+
+        self.emit_multi_pair_swap_event(
+            caller,
+            output.token_in,
+            output.amount_in,
+            payments.clone(),
+        );
+
+        payments
+    }
+
+    /// Swaps the payment through the operations, with `caller` forwarded to the pairs
+    fn swap_through_operations(
+        &self,
+        caller: &ManagedAddress,
+        swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
+    ) -> SwapOperationsOutput<Self::Api> {
         require!(self.is_active(), "Not active");
 
-        let (token_id, nonce, amount) = self.call_value().single_esdt().into_tuple();
+        let (payment_token_id, nonce, amount) =
+            self.call_value().egld_or_single_esdt().into_tuple();
         require!(nonce == 0, "Invalid nonce. Should be zero");
         require!(amount > 0u64, "Invalid amount. Should not be zero");
         require!(
@@ -38,17 +171,34 @@ pub trait MultiPairSwap:
         let swap_fixed_input_endpoint = ManagedBuffer::from(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME);
         let swap_fixed_output_endpoint = ManagedBuffer::from(SWAP_TOKENS_FIXED_OUTPUT_FUNC_NAME);
 
-        let caller = self.blockchain().get_caller();
-        let mut payments = ManagedVec::new();
-        let mut last_payment = EsdtTokenPayment::new(token_id.clone(), nonce, amount.clone());
+        let mut residuals = ManagedVec::new();
+        let native_egld_in = payment_token_id.is_egld();
+        let (token_id, mut last_payment) = if native_egld_in {
+            (native_egld_token_id(), self.wrap_egld(amount.clone()))
+        } else {
+            let token_id = payment_token_id.unwrap_esdt();
+            (
+                token_id.clone(),
+                EsdtTokenPayment::new(token_id, nonce, amount.clone()),
+            )
+        };
 
+        let mut native_egld_out = false;
         for entry in swap_operations.into_iter() {
             let (pair_address, function, token_wanted, amount_wanted) = entry.into_tuple();
             self.check_is_pair_sc(&pair_address);
 
+            let token_wanted = if token_wanted == native_egld_token_id::<Self::Api>() {
+                native_egld_out = true;
+                self.get_wrapped_egld_token_id()
+            } else {
+                token_wanted
+            };
+
             if function == swap_fixed_input_endpoint {
                 last_payment = self.actual_swap_fixed_input(
                     pair_address,
+                    caller,
                     last_payment.token_identifier,
                     last_payment.amount,
                     token_wanted,
@@ -57,6 +207,7 @@ pub trait MultiPairSwap:
             } else if function == swap_fixed_output_endpoint {
                 let (payment, residuum) = self.actual_swap_fixed_output(
                     pair_address,
+                    caller,
                     last_payment.token_identifier,
                     last_payment.amount,
                     token_wanted,
@@ -66,31 +217,100 @@ pub trait MultiPairSwap:
                 last_payment = payment;
 
                 if residuum.amount > 0 {
-                    payments.push(residuum);
+                    residuals.push(residuum);
                 }
             } else {
                 sc_panic!("Invalid function to call");
             }
         }
 
-        payments.push(last_payment);
-        self.send().direct_multi(&caller, &payments);
+        SwapOperationsOutput {
+            token_in: token_id,
+            amount_in: amount,
+            residuals,
+            last_payment,
+            unwrap_egld: native_egld_in || native_egld_out,
+        }
+    }
+
+    /// Returns the forwarded payment, shown as `EGLD` if it was unwrapped,
+    /// and the payments the destination transferred back
+    fn forward_payment_with_call(
+        &self,
+        destination: &ManagedAddress,
+        endpoint_name: ManagedBuffer,
+        endpoint_args: ManagedVec<ManagedBuffer>,
+        payment: EsdtTokenPayment,
+        unwrap_egld: bool,
+    ) -> (EsdtTokenPayment, BackTransfers<Self::Api>) {
+        let call = self
+            .tx()
+            .to(destination)
+            .raw_call(endpoint_name)
+            .arguments_raw(endpoint_args.into())
+            .returns(ReturnsBackTransfers);
+        let unwrap_payment =
+            unwrap_egld && payment.token_identifier == self.wrapped_egld_token_id().get();
+        if unwrap_payment {
+            self.unwrap_egld(&payment);
+        }
 
-        self.emit_multi_pair_swap_event(caller, token_id, amount, payments.clone());
+        // back-transfers add up over all the sync calls until they are read,
+        // so the ones from the swaps and the unwrap are read here and discarded
+        let _ = self.blockchain().get_back_transfers();
+        if unwrap_payment {
+            let back_transfers = call.egld(&payment.amount).sync_call();
 
-        payments
+            return (
+                EsdtTokenPayment::new(native_egld_token_id(), 0, payment.amount),
+                back_transfers,
+            );
+        }
+
+        let back_transfers = call
+            .single_esdt(&payment.token_identifier, 0, &payment.amount)
+            .sync_call();
+
+        (payment, back_transfers)
+    }
+
+    /// Returns the payments sent, with EGLD shown as `EGLD`
+    fn send_back_transfers(
+        &self,
+        destination: &ManagedAddress,
+        back_transfers: BackTransfers<Self::Api>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let mut sent_payments = ManagedVec::new();
+        if back_transfers.total_egld_amount > 0u64 {
+            self.send()
+                .direct_egld(destination, &back_transfers.total_egld_amount);
+            sent_payments.push(EsdtTokenPayment::new(
+                native_egld_token_id(),
+                0,
+                back_transfers.total_egld_amount,
+            ));
+        }
+        if !back_transfers.esdt_payments.is_empty() {
+            self.send()
+                .direct_multi(destination, &back_transfers.esdt_payments);
+            sent_payments.append_vec(back_transfers.esdt_payments);
+        }
+
+        sent_payments
     }
 
     fn actual_swap_fixed_input(
         &self,
         pair_address: ManagedAddress,
+        original_caller: &ManagedAddress,
         token_in: TokenIdentifier,
         amount_in: BigUint,
         token_out: TokenIdentifier,
         amount_out_min: BigUint,
     ) -> EsdtTokenPayment<Self::Api> {
+        let opt_original_caller = self.original_caller_arg(&pair_address, original_caller);
         self.pair_contract_proxy(pair_address)
-            .swap_tokens_fixed_input(token_out, amount_out_min)
+            .swap_tokens_fixed_input(token_out, amount_out_min, opt_original_caller)
             .with_esdt_transfer((token_in, 0, amount_in))
             .execute_on_dest_context()
     }
@@ -98,20 +318,100 @@ pub trait MultiPairSwap:
     fn actual_swap_fixed_output(
         &self,
         pair_address: ManagedAddress,
+        original_caller: &ManagedAddress,
         token_in: TokenIdentifier,
         amount_in_max: BigUint,
         token_out: TokenIdentifier,
         amount_out: BigUint,
     ) -> (EsdtTokenPayment<Self::Api>, EsdtTokenPayment<Self::Api>) {
+        let opt_original_caller = self.original_caller_arg(&pair_address, original_caller);
         let call_result: MultiValue2<EsdtTokenPayment<Self::Api>, EsdtTokenPayment<Self::Api>> =
             self.pair_contract_proxy(pair_address)
-                .swap_tokens_fixed_output(token_out, amount_out)
+                .swap_tokens_fixed_output(token_out, amount_out, opt_original_caller)
                 .with_esdt_transfer((token_in, 0, amount_in_max))
                 .execute_on_dest_context();
 
         call_result.into_tuple()
     }
 
+    /// Only the pairs created or upgraded from a template set through this router version,
+    /// and the multi-asset pools, accept the original caller argument
+    fn original_caller_arg(
+        &self,
+        pair_address: &ManagedAddress,
+        original_caller: &ManagedAddress,
+    ) -> OptionalValue<ManagedAddress> {
+        if self.multi_asset_pools().contains(pair_address)
+            || self.get_pair_version(pair_address) > 0
+        {
+            OptionalValue::Some(original_caller.clone())
+        } else {
+            OptionalValue::None
+        }
+    }
+
+    fn get_wrapped_egld_token_id(&self) -> TokenIdentifier {
+        require!(
+            !self.egld_wrapper_address().is_empty(),
+            "EGLD wrapper not set"
+        );
+
+        self.wrapped_egld_token_id().get()
+    }
+
+    fn wrap_egld(&self, amount: BigUint) -> EsdtTokenPayment<Self::Api> {
+        self.get_wrapped_egld_token_id();
+
+        let wrapper_address = self.egld_wrapper_address().get();
+        self.egld_wrapper_proxy(wrapper_address)
+            .wrap_egld()
+            .with_egld_transfer(amount)
+            .execute_on_dest_context()
+    }
+
+    /// Wrapped EGLD is sent back as EGLD, and shown as `EGLD` in the returned payments
+    fn send_payments_unwrapping_egld(
+        &self,
+        destination: &ManagedAddress,
+        payments: &ManagedVec<EsdtTokenPayment>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let wrapped_egld_token_id = self.wrapped_egld_token_id().get();
+        let mut esdt_payments = ManagedVec::new();
+        let mut sent_payments = ManagedVec::new();
+        for payment in payments {
+            if payment.token_identifier != wrapped_egld_token_id {
+                esdt_payments.push(payment.clone());
+                sent_payments.push(payment);
+                continue;
+            }
+
+            self.unwrap_egld(&payment);
+            self.send().direct_egld(destination, &payment.amount);
+            sent_payments.push(EsdtTokenPayment::new(
+                native_egld_token_id(),
+                0,
+                payment.amount,
+            ));
+        }
+        if !esdt_payments.is_empty() {
+            self.send().direct_multi(destination, &esdt_payments);
+        }
+
+        sent_payments
+    }
+
+    fn unwrap_egld(&self, payment: &EsdtTokenPayment) {
+        let wrapper_address = self.egld_wrapper_address().get();
+        let _: IgnoreValue = self
+            .egld_wrapper_proxy(wrapper_address)
+            .unwrap_egld()
+            .with_esdt_transfer(payment.clone())
+            .execute_on_dest_context();
+    }
+
     #[proxy]
     fn pair_contract_proxy(&self, to: ManagedAddress) -> pair::Proxy<Self::Api>;
+
+    #[proxy]
+    fn egld_wrapper_proxy(&self, to: ManagedAddress) -> egld_wrapper_proxy::Proxy<Self::Api>;
 }
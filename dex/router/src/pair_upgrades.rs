@@ -0,0 +1,193 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{config, factory};
+use multiversx_sc_modules::ongoing_operation::{CONTINUE_OP, STOP_OP};
+use pair::read_pair_storage;
+
+pub const MIN_GAS_TO_SAVE_PROGRESS: u64 = 10_000_000;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub enum PairUpgradeFilter<M: ManagedTypeApi> {
+    Token(TokenIdentifier<M>),
+    Version(u64),
+}
+
+#[derive(TopEncode, TopDecode, Default)]
+pub enum OngoingUpgradeOperation<M: ManagedTypeApi> {
+    #[default]
+    None,
+    UpgradeAll {
+        last_pair_tokens: Option<factory::PairTokens<M>>,
+    },
+    UpgradeByFilter {
+        filter: PairUpgradeFilter<M>,
+        last_pair_tokens: Option<factory::PairTokens<M>>,
+    },
+}
+
+#[multiversx_sc::module]
+pub trait PairUpgradesModule:
+    config::ConfigModule
+    + read_pair_storage::ReadPairStorageModule
+    + factory::FactoryModule
+    + multiversx_sc_modules::ongoing_operation::OngoingOperationModule
+{
+    /// Upgrades the next pair that does not run the current template version.
+    /// An upgrade from source is an async call that ends the transaction,
+    /// so this has to be called again until it returns "completed".
+    /// Scanning the pairs is gas bounded, and returns "interrupted" when it runs low on gas.
+    #[only_owner]
+    #[endpoint(upgradeAllPairs)]
+    fn upgrade_all_pairs(&self) -> OperationCompletionStatus {
+        require!(self.is_active(), "Not active");
+
+        let last_pair_tokens = self.load_upgrade_all_operation();
+        self.upgrade_next_pair(
+            last_pair_tokens,
+            |_, _| true,
+            |last_pair_tokens| OngoingUpgradeOperation::UpgradeAll { last_pair_tokens },
+        )
+    }
+
+    /// Same as `upgradeAllPairs`, but only for the pairs that contain the given token,
+    /// or that run the given template version.
+    /// An ongoing operation can only be resumed with the same filter.
+    #[only_owner]
+    #[endpoint(upgradePairsByFilter)]
+    fn upgrade_pairs_by_filter(
+        &self,
+        filter: PairUpgradeFilter<Self::Api>,
+    ) -> OperationCompletionStatus {
+        require!(self.is_active(), "Not active");
+
+        let last_pair_tokens = self.load_upgrade_by_filter_operation(&filter);
+        self.upgrade_next_pair(
+            last_pair_tokens,
+            |pair_tokens, pair_address| match &filter {
+                PairUpgradeFilter::Token(token_id) => {
+                    &pair_tokens.first_token_id == token_id
+                        || &pair_tokens.second_token_id == token_id
+                }
+                PairUpgradeFilter::Version(version) => {
+                    self.get_pair_version(pair_address) == *version
+                }
+            },
+            |last_pair_tokens| OngoingUpgradeOperation::UpgradeByFilter {
+                filter: filter.clone(),
+                last_pair_tokens,
+            },
+        )
+    }
+
+    #[view(getPairsNotUpgraded)]
+    fn get_pairs_not_upgraded(&self) -> MultiValueEncoded<ManagedAddress> {
+        let template_version = self.pair_template_version().get();
+        let mut result = MultiValueEncoded::new();
+        for pair_address in self.pair_map().values() {
+            if self.get_pair_version(&pair_address) != template_version {
+                result.push(pair_address);
+            }
+        }
+        result
+    }
+
+    /// Goes through the pair map after `last_pair_tokens`, skipping the pairs
+    /// already on the current template version, and upgrades the first one that matches.
+    /// Progress is saved before the upgrade, since nothing runs after it.
+    /// If the last visited pair was removed in the meantime, the scan starts over,
+    /// which only costs gas, as the upgraded pairs are skipped.
+    fn upgrade_next_pair<Filter, Operation>(
+        &self,
+        mut last_pair_tokens: Option<factory::PairTokens<Self::Api>>,
+        filter: Filter,
+        operation: Operation,
+    ) -> OperationCompletionStatus
+    where
+        Filter: Fn(&factory::PairTokens<Self::Api>, &ManagedAddress) -> bool,
+        Operation: Fn(Option<factory::PairTokens<Self::Api>>) -> OngoingUpgradeOperation<Self::Api>,
+    {
+        let template_version = self.pair_template_version().get();
+        let pair_map = self.pair_map();
+        let pair_map_keys = self.pair_map_keys();
+        let mut pairs = match &last_pair_tokens {
+            Some(pair_tokens) if pair_map_keys.contains(pair_tokens) => {
+                let mut pairs = pair_map_keys.iter_from(pair_tokens);
+                let _ = pairs.next();
+                pairs
+            }
+            _ => {
+                last_pair_tokens = None;
+                pair_map_keys.iter()
+            }
+        };
+        let mut pair_to_upgrade = None;
+        let run_result = self.run_while_it_has_gas(MIN_GAS_TO_SAVE_PROGRESS, || {
+            let Some(pair_tokens) = pairs.next() else {
+                return STOP_OP;
+            };
+            let Some(pair_address) = pair_map.get(&pair_tokens) else {
+                sc_panic!("Pair does not exists");
+            };
+
+            let should_upgrade = self.get_pair_version(&pair_address) != template_version
+                && filter(&pair_tokens, &pair_address);
+            last_pair_tokens = Some(pair_tokens);
+            if should_upgrade {
+                pair_to_upgrade = Some(pair_address);
+                return STOP_OP;
+            }
+
+            CONTINUE_OP
+        });
+
+        if run_result == OperationCompletionStatus::InterruptedBeforeOutOfGas {
+            self.save_progress(&operation(last_pair_tokens));
+            return run_result;
+        }
+
+        match pair_to_upgrade {
+            Some(pair_address) => {
+                self.save_progress(&operation(last_pair_tokens));
+                self.upgrade_pair(pair_address);
+
+                OperationCompletionStatus::InterruptedBeforeOutOfGas
+            }
+            None => run_result,
+        }
+    }
+
+    fn load_upgrade_all_operation(&self) -> Option<factory::PairTokens<Self::Api>> {
+        let current_op: OngoingUpgradeOperation<Self::Api> = self.load_operation();
+        match current_op {
+            OngoingUpgradeOperation::None => None,
+            OngoingUpgradeOperation::UpgradeAll { last_pair_tokens } => last_pair_tokens,
+            OngoingUpgradeOperation::UpgradeByFilter { .. } => {
+                sc_panic!("Filtered upgrade in progress")
+            }
+        }
+    }
+
+    fn load_upgrade_by_filter_operation(
+        &self,
+        filter: &PairUpgradeFilter<Self::Api>,
+    ) -> Option<factory::PairTokens<Self::Api>> {
+        let current_op: OngoingUpgradeOperation<Self::Api> = self.load_operation();
+        match current_op {
+            OngoingUpgradeOperation::None => None,
+            OngoingUpgradeOperation::UpgradeAll { .. } => {
+                sc_panic!("Upgrade of all pairs in progress")
+            }
+            OngoingUpgradeOperation::UpgradeByFilter {
+                filter: ongoing_filter,
+                last_pair_tokens,
+            } => {
+                require!(
+                    &ongoing_filter == filter,
+                    "Upgrade with another filter in progress"
+                );
+                last_pair_tokens
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{best_route::RouteStep, config, events, factory, multi_pair_swap};
+use pair::read_pair_storage;
+
+pub const TOTAL_ROUTE_WEIGHT: u64 = 10_000;
+
+#[derive(
+    ManagedVecItem,
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    Clone,
+    PartialEq,
+    Debug,
+)]
+pub struct WeightedRoute<M: ManagedTypeApi> {
+    pub weight: u64,
+    pub steps: ManagedVec<M, RouteStep<M>>,
+}
+
+#[multiversx_sc::module]
+pub trait SplitRouteSwapModule:
+    config::ConfigModule
+    + read_pair_storage::ReadPairStorageModule
+    + factory::FactoryModule
+    + token_send::TokenSendModule
+    + events::EventsModule
+    + multi_pair_swap::MultiPairSwap
+{
+    /// Splits the payment across the routes by their weights, out of `TOTAL_ROUTE_WEIGHT`,
+    /// and sends back the sum of their outputs. Only the total amount out is checked.
+    #[payable("*")]
+    #[endpoint(splitRouteSwap)]
+    fn split_route_swap(
+        &self,
+        token_out: TokenIdentifier,
+        amount_out_min: BigUint,
+        routes: MultiValueEncoded<WeightedRoute<Self::Api>>,
+    ) -> EsdtTokenPayment {
+        require!(self.is_active(), "Not active");
+        require!(amount_out_min > 0u64, "Invalid amount. Should not be zero");
+        require!(!routes.is_empty(), "Invalid routes. Should not be empty");
+
+        let payment = self.call_value().single_esdt();
+        require!(payment.token_nonce == 0, "Invalid nonce. Should be zero");
+
+        let routes = routes.to_vec();
+        let mut total_weight = 0u64;
+        for route in routes.iter() {
+            require!(
+                route.weight > 0 && route.weight <= TOTAL_ROUTE_WEIGHT,
+                "Invalid route weight"
+            );
+            require!(
+                !route.steps.is_empty(),
+                "Invalid route. Should not be empty"
+            );
+            require!(
+                route.steps.get(route.steps.len() - 1).token_out == token_out,
+                "Invalid route. Should end in the wanted token"
+            );
+            total_weight += route.weight;
+        }
+        require!(total_weight == TOTAL_ROUTE_WEIGHT, "Invalid route weights");
+
+        let caller = self.blockchain().get_caller();
+        let last_route_index = routes.len() - 1;
+        let mut amount_left = payment.amount.clone();
+        let mut route_payments = ManagedVec::new();
+        let mut total_amount_out = BigUint::zero();
+        for (route_index, route) in routes.iter().enumerate() {
+            // the last route gets the rounding leftover
+            let route_amount_in = if route_index == last_route_index {
+                amount_left.clone()
+            } else {
+                &payment.amount * route.weight / TOTAL_ROUTE_WEIGHT
+            };
+            require!(
+                route_amount_in > 0u64,
+                "Invalid route amount. Should not be zero"
+            );
+            amount_left -= &route_amount_in;
+
+            let mut last_payment =
+                EsdtTokenPayment::new(payment.token_identifier.clone(), 0, route_amount_in);
+            for step in route.steps.iter() {
+                self.check_is_pair_sc(&step.pair_address);
+                last_payment = self.actual_swap_fixed_input(
+                    step.pair_address.clone(),
+                    &caller,
+                    last_payment.token_identifier,
+                    last_payment.amount,
+                    step.token_out.clone(),
+                    BigUint::from(1u32),
+                );
+            }
+
+            total_amount_out += &last_payment.amount;
+            route_payments.push(last_payment);
+        }
+        require!(total_amount_out >= amount_out_min, "Slippage exceeded");
+
+        let output_payment = EsdtTokenPayment::new(token_out, 0, total_amount_out);
+        self.send()
+            .direct_non_zero_esdt_payment(&caller, &output_payment);
+        self.emit_multi_pair_swap_event(
+            caller,
+            payment.token_identifier,
+            payment.amount,
+            route_payments,
+        );
+
+        output_payment
+    }
+}
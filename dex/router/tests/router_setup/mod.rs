@@ -8,11 +8,14 @@ use multiversx_sc_scenario::{
 
 pub const PAIR_WASM_PATH: &str = "pair/output/pair.wasm";
 pub const ROUTER_WASM_PATH: &str = "router/output/router.wasm";
+pub const MULTI_ASSET_POOL_WASM_PATH: &str = "multi-asset-pool/output/multi-asset-pool.wasm";
 pub const MEX_TOKEN_ID: &[u8] = b"MEX-abcdef";
 pub const WEGLD_TOKEN_ID: &[u8] = b"WEGLD-abcdef";
 pub const USDC_TOKEN_ID: &[u8] = b"USDC-abcdef";
 pub const LPMEX_TOKEN_ID: &[u8] = b"LPMEX-abcdef";
 pub const LPUSDC_TOKEN_ID: &[u8] = b"LPUSDC-abcdef";
+pub const LPPOOL_TOKEN_ID: &[u8] = b"LPPOOL-abcdef";
+pub const LPDIRECT_TOKEN_ID: &[u8] = b"LPDIRECT-abcdef";
 
 pub const USER_TOTAL_MEX_TOKENS: u64 = 5_001_001_000;
 pub const USER_TOTAL_WEGLD_TOKENS: u64 = 5_002_002_000;
@@ -34,6 +37,7 @@ use pausable::{PausableModule, State};
 use router::config::ConfigModule;
 use router::factory::*;
 use router::multi_pair_swap::*;
+use router::pair_upgrades::PairUpgradesModule;
 use router::*;
 
 #[allow(dead_code)]
@@ -85,7 +89,7 @@ where
             .execute_tx(&owner_addr, &mex_pair_wrapper, &rust_zero, |sc| {
                 let first_token_id = managed_token_id!(WEGLD_TOKEN_ID);
                 let second_token_id = managed_token_id!(MEX_TOKEN_ID);
-                let router_address = managed_address!(&owner_addr);
+                let router_address = managed_address!(router_wrapper.address_ref());
                 let router_owner_address = managed_address!(&owner_addr);
                 let total_fee_percent = 300u64;
                 let special_fee_percent = 50u64;
@@ -112,7 +116,7 @@ where
             .execute_tx(&owner_addr, &usdc_pair_wrapper, &rust_zero, |sc| {
                 let first_token_id = managed_token_id!(WEGLD_TOKEN_ID);
                 let second_token_id = managed_token_id!(USDC_TOKEN_ID);
-                let router_address = managed_address!(&owner_addr);
+                let router_address = managed_address!(router_wrapper.address_ref());
                 let router_owner_address = managed_address!(&owner_addr);
                 let total_fee_percent = 300u64;
                 let special_fee_percent = 50u64;
@@ -253,6 +257,79 @@ where
             .assert_ok();
     }
 
+    /// Registers a MEX/USDC pair with the given reserves, set directly in storage
+    pub fn add_mex_usdc_pair(&mut self, pair_builder: PairObjBuilder, reserve: u64) -> Address {
+        let rust_zero = rust_biguint!(0);
+        let owner_address = self.owner_address.clone();
+        let router_address = self.router_wrapper.address_ref().clone();
+        let pair_wrapper = self.blockchain_wrapper.create_sc_account(
+            &rust_zero,
+            Some(&owner_address),
+            pair_builder,
+            PAIR_WASM_PATH,
+        );
+        self.blockchain_wrapper
+            .execute_tx(&owner_address, &pair_wrapper, &rust_zero, |sc| {
+                sc.init(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_address!(&router_address),
+                    managed_address!(&owner_address),
+                    300,
+                    50,
+                    ManagedAddress::<DebugApi>::zero(),
+                    MultiValueEncoded::<DebugApi, ManagedAddress<DebugApi>>::new(),
+                );
+                sc.lp_token_identifier()
+                    .set(managed_token_id!(LPDIRECT_TOKEN_ID));
+                sc.state().set(State::Active);
+
+                sc.pair_reserve(&managed_token_id!(MEX_TOKEN_ID))
+                    .set(managed_biguint!(reserve));
+                sc.pair_reserve(&managed_token_id!(USDC_TOKEN_ID))
+                    .set(managed_biguint!(reserve));
+                sc.lp_token_supply().set(managed_biguint!(reserve));
+            })
+            .assert_ok();
+
+        let pair_address = pair_wrapper.address_ref().clone();
+        self.blockchain_wrapper.set_esdt_balance(
+            &pair_address,
+            MEX_TOKEN_ID,
+            &rust_biguint!(reserve),
+        );
+        self.blockchain_wrapper.set_esdt_balance(
+            &pair_address,
+            USDC_TOKEN_ID,
+            &rust_biguint!(reserve),
+        );
+        self.blockchain_wrapper
+            .execute_tx(&owner_address, &self.router_wrapper, &rust_zero, |sc| {
+                sc.pair_map().insert(
+                    PairTokens {
+                        first_token_id: managed_token_id!(MEX_TOKEN_ID),
+                        second_token_id: managed_token_id!(USDC_TOKEN_ID),
+                    },
+                    managed_address!(&pair_address),
+                );
+            })
+            .assert_ok();
+
+        pair_address
+    }
+
+    pub fn check_pairs_not_upgraded(&mut self, expected: &[&Address]) {
+        self.blockchain_wrapper
+            .execute_query(&self.router_wrapper, |sc| {
+                let pairs = sc.get_pairs_not_upgraded().to_vec();
+                assert_eq!(pairs.len(), expected.len());
+                for (pair_address, expected_address) in pairs.iter().zip(expected.iter()) {
+                    assert_eq!(*pair_address, managed_address!(*expected_address));
+                }
+            })
+            .assert_ok();
+    }
+
     pub fn multi_pair_swap(
         &mut self,
         payment_token: &[u8],
@@ -1,28 +1,41 @@
 #![allow(deprecated)]
 
 mod router_setup;
+use egld_wrapper_mock::EgldWrapperMock;
+use multi_asset_pool::{liquidity::LiquidityModule, MultiAssetPool};
 use multiversx_sc::{
     codec::multi_types::OptionalValue,
     storage::mappers::StorageTokenWrapper,
     types::{
         Address, EgldOrEsdtTokenIdentifier, EsdtLocalRole, ManagedAddress, ManagedVec,
-        MultiValueEncoded,
+        MultiValueEncoded, OperationCompletionStatus,
     },
 };
+use multiversx_sc_modules::ongoing_operation::OngoingOperationModule;
 use pair::{
     config::ConfigModule as PairConfigModule, pair_actions::initial_liq::InitialLiquidityModule,
     Pair,
 };
 use pausable::{PausableModule, State};
+use payment_receiver_mock::PaymentReceiverMock;
 use router::{
-    config::ConfigModule, enable_swap_by_user::EnableSwapByUserModule, factory::PairTokens,
-    multi_pair_swap::SWAP_TOKENS_FIXED_INPUT_FUNC_NAME, Router,
+    best_route::{BestRouteModule, RouteStep, MAX_ROUTE_HOPS},
+    config::ConfigModule,
+    enable_swap_by_user::EnableSwapByUserModule,
+    factory::PairTokens,
+    multi_pair_swap::{
+        MultiPairSwap, SWAP_TOKENS_FIXED_INPUT_FUNC_NAME, SWAP_TOKENS_FIXED_OUTPUT_FUNC_NAME,
+    },
+    pair_upgrades::{OngoingUpgradeOperation, PairUpgradeFilter, PairUpgradesModule},
+    split_route_swap::{SplitRouteSwapModule, WeightedRoute},
+    Router,
 };
 use router_setup::*;
 
 use multiversx_sc_scenario::{
-    managed_address, managed_biguint, managed_token_id, managed_token_id_wrapped, rust_biguint,
-    whitebox_legacy::BlockchainStateWrapper, whitebox_legacy::TxTokenTransfer, DebugApi,
+    managed_address, managed_biguint, managed_buffer, managed_token_id, managed_token_id_wrapped,
+    rust_biguint, whitebox_legacy::BlockchainStateWrapper, whitebox_legacy::TxTokenTransfer,
+    DebugApi,
 };
 use simple_lock::{
     locked_token::{LockedTokenAttributes, LockedTokenModule},
@@ -181,6 +194,890 @@ fn test_multi_pair_swap() {
     );
 }
 
+#[test]
+fn test_multi_pair_swap_with_options() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+    let build_swap_operations = || {
+        let mut swap_operations = MultiValueEncoded::new();
+        swap_operations.push(
+            (
+                managed_address!(&mex_pair_address),
+                managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+                managed_token_id!(WEGLD_TOKEN_ID),
+                managed_biguint!(1),
+            )
+                .into(),
+        );
+        swap_operations.push(
+            (
+                managed_address!(&usdc_pair_address),
+                managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(1),
+            )
+                .into(),
+        );
+        swap_operations
+    };
+
+    let user_address = router_setup.user_address.clone();
+    let recipient_address = router_setup
+        .blockchain_wrapper
+        .create_user_account(&rust_biguint!(0));
+    router_setup.blockchain_wrapper.set_block_timestamp(100);
+
+    let mut swap_with_options = |amount_out_min: u64, deadline: u64, expected_error: &str| {
+        router_setup
+            .blockchain_wrapper
+            .execute_esdt_transfer(
+                &user_address,
+                &router_setup.router_wrapper,
+                MEX_TOKEN_ID,
+                0,
+                &rust_biguint!(100_000),
+                |sc| {
+                    sc.multi_pair_swap_with_options(
+                        managed_biguint!(amount_out_min),
+                        deadline,
+                        Some(managed_address!(&recipient_address)),
+                        build_swap_operations(),
+                    );
+                },
+            )
+            .assert_user_error(expected_error);
+    };
+    swap_with_options(1, 99, "Deadline passed");
+    swap_with_options(82_910, 100, "Slippage exceeded");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let payments = sc.multi_pair_swap_with_options(
+                    managed_biguint!(82_909),
+                    100,
+                    Some(managed_address!(&recipient_address)),
+                    build_swap_operations(),
+                );
+                assert_eq!(payments.len(), 1);
+                assert_eq!(payments.get(0).amount, managed_biguint!(82_909));
+            },
+        )
+        .assert_ok();
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &recipient_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(82_909),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(5_000_000_000),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(4_999_900_000),
+    );
+}
+
+#[test]
+fn test_multi_pair_swap_and_call() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+
+    let rust_zero = rust_biguint!(0);
+    let receiver = router_setup.blockchain_wrapper.create_sc_account(
+        &rust_zero,
+        Some(&router_setup.owner_address),
+        payment_receiver_mock::contract_obj,
+        "payment receiver mock",
+    );
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&router_setup.owner_address, &receiver, &rust_zero, |sc| {
+            sc.init();
+        })
+        .assert_ok();
+
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+    let build_swap_operations = || {
+        let mut swap_operations = MultiValueEncoded::new();
+        swap_operations.push(
+            (
+                managed_address!(&mex_pair_address),
+                managed_buffer!(SWAP_TOKENS_FIXED_OUTPUT_FUNC_NAME),
+                managed_token_id!(WEGLD_TOKEN_ID),
+                managed_biguint!(50_000),
+            )
+                .into(),
+        );
+        swap_operations.push(
+            (
+                managed_address!(&usdc_pair_address),
+                managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(1),
+            )
+                .into(),
+        );
+        swap_operations
+    };
+
+    let user_address = router_setup.user_address.clone();
+    let receiver_address = receiver.address_ref().clone();
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let mut endpoint_args = ManagedVec::new();
+                endpoint_args.push(managed_buffer!(b"order-1"));
+                sc.multi_pair_swap_and_call(
+                    managed_address!(&user_address),
+                    managed_buffer!(b"receivePayment"),
+                    endpoint_args,
+                    build_swap_operations(),
+                );
+            },
+        )
+        .assert_user_error("Invalid SC Address");
+
+    let router_address = router_setup.router_wrapper.address_ref().clone();
+    for destination in [&mex_pair_address, &router_address] {
+        router_setup
+            .blockchain_wrapper
+            .execute_esdt_transfer(
+                &user_address,
+                &router_setup.router_wrapper,
+                MEX_TOKEN_ID,
+                0,
+                &rust_biguint!(100_000),
+                |sc| {
+                    sc.multi_pair_swap_and_call(
+                        managed_address!(destination),
+                        managed_buffer!(b"swapNoFeeAndForward"),
+                        ManagedVec::new(),
+                        build_swap_operations(),
+                    );
+                },
+            )
+            .assert_user_error("Invalid destination");
+    }
+
+    // the MEX left over by the fixed output swap goes back to the caller
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let mut endpoint_args = ManagedVec::new();
+                endpoint_args.push(managed_buffer!(b"order-1"));
+                let payments = sc.multi_pair_swap_and_call(
+                    managed_address!(&receiver_address),
+                    managed_buffer!(b"receivePayment"),
+                    endpoint_args,
+                    build_swap_operations(),
+                );
+                assert_eq!(payments.len(), 2);
+                assert_eq!(
+                    payments.get(0).token_identifier,
+                    managed_token_id!(MEX_TOKEN_ID)
+                );
+                assert_eq!(payments.get(0).amount, managed_biguint!(47_212));
+                assert_eq!(
+                    payments.get(1).token_identifier,
+                    managed_token_id!(USDC_TOKEN_ID)
+                );
+                assert_eq!(payments.get(1).amount, managed_biguint!(47_485));
+            },
+        )
+        .assert_ok();
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &receiver_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(47_485),
+    );
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&receiver, |sc| {
+            assert_eq!(sc.last_tag().get(), managed_buffer!(b"order-1"));
+            assert_eq!(sc.last_payment().get().amount, managed_biguint!(47_485));
+        })
+        .assert_ok();
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(4_999_947_212),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(5_000_000_000),
+    );
+
+    // the payments the destination transfers back are forwarded to the caller
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let mut endpoint_args = ManagedVec::new();
+                endpoint_args.push(managed_buffer!(b"order-2"));
+                endpoint_args.push(managed_biguint!(1_000).to_bytes_be_buffer());
+                let payments = sc.multi_pair_swap_and_call(
+                    managed_address!(&receiver_address),
+                    managed_buffer!(b"receivePaymentWithRefund"),
+                    endpoint_args,
+                    build_swap_operations(),
+                );
+                assert_eq!(payments.len(), 3);
+                assert_eq!(payments.get(0).amount, managed_biguint!(41_345));
+                assert_eq!(
+                    payments.get(2).token_identifier,
+                    managed_token_id!(USDC_TOKEN_ID)
+                );
+                assert_eq!(payments.get(2).amount, managed_biguint!(1_000));
+            },
+        )
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&receiver, |sc| {
+            assert_eq!(sc.last_tag().get(), managed_buffer!(b"order-2"));
+        })
+        .assert_ok();
+    // only the refund comes back, not the outputs of the two hops
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(5_000_001_000),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(5_000_000_000),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(4_999_888_557),
+    );
+    for token_id in [MEX_TOKEN_ID, WEGLD_TOKEN_ID, USDC_TOKEN_ID] {
+        router_setup.blockchain_wrapper.check_esdt_balance(
+            &router_address,
+            token_id,
+            &rust_biguint!(0),
+        );
+    }
+}
+
+#[test]
+fn test_original_caller_only_for_upgraded_pairs() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let user_address = router_setup.user_address.clone();
+
+    // pairs without a recorded version may run an older template, without the original caller argument
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            let opt_original_caller = sc.original_caller_arg(
+                &managed_address!(&mex_pair_address),
+                &managed_address!(&user_address),
+            );
+            assert!(opt_original_caller.into_option().is_none());
+        })
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &router_setup.owner_address,
+            &router_setup.router_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.pair_deploy_version(&managed_address!(&mex_pair_address))
+                    .set(1);
+            },
+        )
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            let opt_original_caller = sc.original_caller_arg(
+                &managed_address!(&mex_pair_address),
+                &managed_address!(&user_address),
+            );
+            assert_eq!(
+                opt_original_caller.into_option(),
+                Some(managed_address!(&user_address))
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_multi_pair_swap_native_egld() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+
+    let rust_zero = rust_biguint!(0);
+    let wrapper = router_setup.blockchain_wrapper.create_sc_account(
+        &rust_biguint!(1_000_000),
+        Some(&router_setup.owner_address),
+        egld_wrapper_mock::contract_obj,
+        "egld wrapper mock",
+    );
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&router_setup.owner_address, &wrapper, &rust_zero, |sc| {
+            sc.init(managed_token_id!(WEGLD_TOKEN_ID));
+        })
+        .assert_ok();
+    router_setup.blockchain_wrapper.set_esdt_balance(
+        wrapper.address_ref(),
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(1_000_000),
+    );
+
+    let user_address = router_setup.user_address.clone();
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let swap_to = |token_wanted: &[u8]| {
+        let mut swap_operations = MultiValueEncoded::new();
+        swap_operations.push(
+            (
+                managed_address!(&mex_pair_address),
+                managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+                managed_token_id!(token_wanted),
+                managed_biguint!(1),
+            )
+                .into(),
+        );
+        swap_operations
+    };
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &user_address,
+            &router_setup.router_wrapper,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.multi_pair_swap(swap_to(MEX_TOKEN_ID));
+            },
+        )
+        .assert_user_error("EGLD wrapper not set");
+
+    let wrapper_address = wrapper.address_ref().clone();
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &router_setup.owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_egld_wrapper(
+                    managed_address!(&wrapper_address),
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                );
+            },
+        )
+        .assert_ok();
+
+    // EGLD is wrapped before the first swap
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &user_address,
+            &router_setup.router_wrapper,
+            &rust_biguint!(100_000),
+            |sc| {
+                let payments = sc.multi_pair_swap(swap_to(MEX_TOKEN_ID));
+                let last_payment = payments.get(payments.len() - 1);
+                assert_eq!(
+                    last_payment.token_identifier,
+                    managed_token_id!(MEX_TOKEN_ID)
+                );
+                assert_eq!(last_payment.amount, managed_biguint!(90_669));
+            },
+        )
+        .assert_ok();
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(5_000_090_669),
+    );
+    router_setup
+        .blockchain_wrapper
+        .check_egld_balance(&user_address, &rust_biguint!(99_900_000));
+
+    // the wrapped EGLD output is unwrapped when EGLD is requested
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                let payments = sc.multi_pair_swap(swap_to(b"EGLD"));
+                let last_payment = payments.get(payments.len() - 1);
+                assert_eq!(last_payment.token_identifier, managed_token_id!(b"EGLD"));
+                assert_eq!(last_payment.amount, managed_biguint!(11_927));
+            },
+        )
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .check_egld_balance(&user_address, &rust_biguint!(99_911_927));
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(5_000_000_000),
+    );
+}
+
+#[test]
+fn test_best_route() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+
+    // a small MEX/USDC pair, better than the route through WEGLD only for small amounts
+    let direct_pair_address = router_setup.add_mex_usdc_pair(pair::contract_obj, 50_000);
+
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+
+    // routes only go through the route tokens set by the owner
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            let route = sc.get_best_route(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(100_000),
+                2,
+            );
+            assert_eq!(route.amount_out, managed_biguint!(33_299));
+            assert_eq!(route.steps.len(), 1);
+        })
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &router_setup.owner_address,
+            &router_setup.router_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_route_token(managed_token_id!(WEGLD_TOKEN_ID));
+            },
+        )
+        .assert_ok();
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &router_setup.owner_address,
+            &router_setup.router_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_route_token(managed_token_id!(WEGLD_TOKEN_ID));
+            },
+        )
+        .assert_user_error("Route token already added");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            let route = sc.get_best_route(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(100),
+                2,
+            );
+            assert_eq!(route.amount_out, managed_biguint!(99));
+            assert_eq!(route.steps.len(), 1);
+            assert_eq!(
+                route.steps.get(0).pair_address,
+                managed_address!(&direct_pair_address)
+            );
+
+            let route = sc.get_best_route(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(100_000),
+                2,
+            );
+            assert_eq!(route.amount_out, managed_biguint!(82_909));
+            assert_eq!(route.steps.len(), 2);
+            assert_eq!(
+                route.steps.get(0).pair_address,
+                managed_address!(&mex_pair_address)
+            );
+            assert_eq!(
+                route.steps.get(0).token_out,
+                managed_token_id!(WEGLD_TOKEN_ID)
+            );
+            assert_eq!(
+                route.steps.get(1).pair_address,
+                managed_address!(&usdc_pair_address)
+            );
+
+            // with a single hop, only the direct pair can be used
+            let route = sc.get_best_route(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(100_000),
+                1,
+            );
+            assert_eq!(route.amount_out, managed_biguint!(33_299));
+        })
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            let _ = sc.get_best_route(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_biguint!(100_000),
+                MAX_ROUTE_HOPS + 1,
+            );
+        })
+        .assert_user_error("Invalid number of hops");
+
+    let user_address = router_setup.user_address.clone();
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.swap_best_route(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(82_910),
+                    2,
+                );
+            },
+        )
+        .assert_user_error("Slippage exceeded");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let payment = sc.swap_best_route(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(82_909),
+                    2,
+                );
+                assert_eq!(payment.amount, managed_biguint!(82_909));
+            },
+        )
+        .assert_ok();
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(5_000_082_909),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(4_999_900_000),
+    );
+}
+
+#[test]
+fn test_split_route_swap() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+    let direct_pair_address = router_setup.add_mex_usdc_pair(pair::contract_obj, 500_000);
+
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+    let build_routes = |direct_weight: u64, hop_weight: u64| {
+        let mut direct_steps = ManagedVec::new();
+        direct_steps.push(RouteStep {
+            pair_address: managed_address!(&direct_pair_address),
+            token_out: managed_token_id!(USDC_TOKEN_ID),
+        });
+
+        let mut hop_steps = ManagedVec::new();
+        hop_steps.push(RouteStep {
+            pair_address: managed_address!(&mex_pair_address),
+            token_out: managed_token_id!(WEGLD_TOKEN_ID),
+        });
+        hop_steps.push(RouteStep {
+            pair_address: managed_address!(&usdc_pair_address),
+            token_out: managed_token_id!(USDC_TOKEN_ID),
+        });
+
+        let mut routes = MultiValueEncoded::new();
+        routes.push(WeightedRoute {
+            weight: direct_weight,
+            steps: direct_steps,
+        });
+        routes.push(WeightedRoute {
+            weight: hop_weight,
+            steps: hop_steps,
+        });
+        routes
+    };
+
+    let user_address = router_setup.user_address.clone();
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.split_route_swap(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(1),
+                    build_routes(4_000, 5_000),
+                );
+            },
+        )
+        .assert_user_error("Invalid route weights");
+
+    // weights adding up to TOTAL_ROUTE_WEIGHT only after an overflow
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.split_route_swap(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(1),
+                    build_routes(u64::MAX, 10_001),
+                );
+            },
+        )
+        .assert_user_error("Invalid route weight");
+
+    // 40% through the direct pair and 60% through WEGLD, with 90_215 in total
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.split_route_swap(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(90_216),
+                    build_routes(4_000, 6_000),
+                );
+            },
+        )
+        .assert_user_error("Slippage exceeded");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let payment = sc.split_route_swap(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(90_215),
+                    build_routes(4_000, 6_000),
+                );
+                assert_eq!(payment.token_identifier, managed_token_id!(USDC_TOKEN_ID));
+                assert_eq!(payment.amount, managed_biguint!(90_215));
+            },
+        )
+        .assert_ok();
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(5_000_090_215),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(4_999_900_000),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &direct_pair_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(540_000),
+    );
+}
+
+#[test]
+fn test_multi_pair_swap_through_multi_asset_pool() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+    let owner = router_setup.owner_address.clone();
+    let user = router_setup.user_address.clone();
+    let router_address = router_setup.router_wrapper.address_ref().clone();
+
+    let pool_wrapper = router_setup.blockchain_wrapper.create_sc_account(
+        &rust_zero,
+        Some(&owner),
+        multi_asset_pool::contract_obj,
+        MULTI_ASSET_POOL_WASM_PATH,
+    );
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &pool_wrapper, &rust_zero, |sc| {
+            let mut token_ids = MultiValueEncoded::new();
+            token_ids.push(managed_token_id!(WEGLD_TOKEN_ID));
+            token_ids.push(managed_token_id!(MEX_TOKEN_ID));
+            token_ids.push(managed_token_id!(USDC_TOKEN_ID));
+
+            sc.init(
+                managed_address!(&router_address),
+                managed_address!(&owner),
+                300,
+                token_ids,
+            );
+            sc.set_lp_token_identifier(managed_token_id!(LPPOOL_TOKEN_ID));
+            sc.state().set(State::Active);
+        })
+        .assert_ok();
+    router_setup.blockchain_wrapper.set_esdt_local_roles(
+        pool_wrapper.address_ref(),
+        LPPOOL_TOKEN_ID,
+        &[EsdtLocalRole::Mint, EsdtLocalRole::Burn],
+    );
+
+    let payments = [WEGLD_TOKEN_ID, MEX_TOKEN_ID, USDC_TOKEN_ID]
+        .iter()
+        .map(|token_id| TxTokenTransfer {
+            token_identifier: token_id.to_vec(),
+            nonce: 0,
+            value: rust_biguint!(1_000_000),
+        })
+        .collect::<Vec<_>>();
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_multi_transfer(&user, &pool_wrapper, &payments, |sc| {
+            sc.add_liquidity(managed_biguint!(1));
+        })
+        .assert_ok();
+
+    let ops = vec![(
+        pool_wrapper.address_ref().clone(),
+        SWAP_TOKENS_FIXED_INPUT_FUNC_NAME,
+        USDC_TOKEN_ID,
+        1,
+    )];
+
+    // the pool has to be registered first
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                let mut swap_operations = MultiValueEncoded::new();
+                swap_operations.push(
+                    (
+                        managed_address!(pool_wrapper.address_ref()),
+                        managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+                        managed_token_id!(USDC_TOKEN_ID),
+                        managed_biguint!(1),
+                    )
+                        .into(),
+                );
+
+                sc.multi_pair_swap(swap_operations);
+            },
+        )
+        .assert_user_error("Not a pair SC");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &router_setup.router_wrapper, &rust_zero, |sc| {
+            sc.add_multi_asset_pool(managed_address!(pool_wrapper.address_ref()));
+        })
+        .assert_ok();
+
+    router_setup.multi_pair_swap(MEX_TOKEN_ID, 10_000, &ops);
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_MEX_TOKENS - 1_010_000),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user,
+        USDC_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_USDC_TOKENS - 1_000_000 + 9_871),
+    );
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &router_setup.router_wrapper, &rust_zero, |sc| {
+            sc.remove_multi_asset_pool(managed_address!(pool_wrapper.address_ref()));
+            assert!(sc.multi_asset_pools().is_empty());
+        })
+        .assert_ok();
+}
+
 #[test]
 fn user_enable_pair_swaps_through_router_test() {
     let rust_zero = rust_biguint!(0u64);
@@ -558,3 +1455,167 @@ fn user_enable_pair_swaps_fail_test() {
         }),
     );
 }
+
+#[test]
+fn test_upgrade_pairs() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+    let mex_usdc_pair_address = router_setup.add_mex_usdc_pair(pair::contract_obj, 1_000);
+
+    let rust_zero = rust_biguint!(0);
+    let owner_address = router_setup.owner_address.clone();
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+
+    // pairs registered before the first template version are considered up to date
+    router_setup.check_pairs_not_upgraded(&[]);
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_pair_template_address(managed_address!(&mex_pair_address));
+                assert_eq!(sc.pair_template_version().get(), 1);
+            },
+        )
+        .assert_ok();
+    router_setup.check_pairs_not_upgraded(&[
+        &mex_pair_address,
+        &usdc_pair_address,
+        &mex_usdc_pair_address,
+    ]);
+
+    // each call upgrades a single pair, since the upgrade ends the transaction
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.upgrade_pairs_by_filter(PairUpgradeFilter::Token(managed_token_id!(
+                    USDC_TOKEN_ID
+                )));
+            },
+        )
+        .assert_ok();
+    router_setup.check_pairs_not_upgraded(&[&mex_pair_address, &mex_usdc_pair_address]);
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.upgrade_all_pairs();
+            },
+        )
+        .assert_user_error("Filtered upgrade in progress");
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.upgrade_pairs_by_filter(PairUpgradeFilter::Version(0));
+            },
+        )
+        .assert_user_error("Upgrade with another filter in progress");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.upgrade_pairs_by_filter(PairUpgradeFilter::Token(managed_token_id!(
+                    USDC_TOKEN_ID
+                )));
+            },
+        )
+        .assert_ok();
+    router_setup.check_pairs_not_upgraded(&[&mex_pair_address]);
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                let run_result = sc.upgrade_pairs_by_filter(PairUpgradeFilter::Token(
+                    managed_token_id!(USDC_TOKEN_ID),
+                ));
+                assert_eq!(run_result, OperationCompletionStatus::Completed);
+            },
+        )
+        .assert_ok();
+
+    // resuming after a pair that was removed in the meantime starts over
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.save_progress(&OngoingUpgradeOperation::<DebugApi>::UpgradeAll {
+                    last_pair_tokens: Some(PairTokens {
+                        first_token_id: managed_token_id!(MEX_TOKEN_ID),
+                        second_token_id: managed_token_id!(b"REMOVED-abcdef"),
+                    }),
+                });
+                sc.upgrade_all_pairs();
+            },
+        )
+        .assert_ok();
+    router_setup.check_pairs_not_upgraded(&[]);
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                let run_result = sc.upgrade_all_pairs();
+                assert_eq!(run_result, OperationCompletionStatus::Completed);
+                assert_eq!(sc.get_pair_version(&managed_address!(&mex_pair_address)), 1);
+            },
+        )
+        .assert_ok();
+
+    // the version is only recorded by the upgraded pair, so a failed upgrade does not change it
+    let receiver = router_setup.blockchain_wrapper.create_sc_account(
+        &rust_zero,
+        Some(&owner_address),
+        payment_receiver_mock::contract_obj,
+        "payment receiver mock",
+    );
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &owner_address,
+            &router_setup.router_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_pair_template_address(managed_address!(receiver.address_ref()));
+                sc.upgrade_pair_endpoint(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    managed_token_id!(MEX_TOKEN_ID),
+                );
+            },
+        )
+        .assert_ok();
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            assert_eq!(sc.get_pair_version(&managed_address!(&mex_pair_address)), 1);
+        })
+        .assert_ok();
+}
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           29
+// Endpoints:                           52
 // Async Callback:                       1
-// Total number of exported functions:  32
+// Total number of exported functions:  55
 
 #![no_std]
 
@@ -22,28 +22,51 @@ multiversx_sc_wasm_adapter::endpoints! {
         upgrade => upgrade
         pause => pause
         resume => resume
+        setPairWithdrawOnly => set_pair_withdraw_only
         createPair => create_pair_endpoint
         upgradePair => upgrade_pair_endpoint
         issueLpToken => issue_lp_token
         setLocalRoles => set_local_roles
         removePair => remove_pair
+        addMultiAssetPool => add_multi_asset_pool
+        removeMultiAssetPool => remove_multi_asset_pool
         setFeeOn => set_fee_on
         setFeeOff => set_fee_off
+        setPairEnergyFactoryAddress => set_pair_energy_factory_address
+        setPairSwapHook => set_pair_swap_hook
+        removePairSwapHook => remove_pair_swap_hook
         setPairCreationEnabled => set_pair_creation_enabled
         getPairCreationEnabled => pair_creation_enabled
         getState => state
         getOwner => owner
         setTemporaryOwnerPeriod => set_temporary_owner_period
         setPairTemplateAddress => set_pair_template_address
+        setEgldWrapper => set_egld_wrapper
         getPairTemplateAddress => pair_template_address
+        getPairTemplateVersion => pair_template_version
+        getPairVersion => get_pair_version
         getTemporaryOwnerPeriod => temporary_owner_period
         getCommonTokensForUserPairs => common_tokens_for_user_pairs
+        getEgldWrapperAddress => egld_wrapper_address
+        getWrappedEgldTokenId => wrapped_egld_token_id
+        getAllMultiAssetPools => multi_asset_pools
         getAllPairsManagedAddresses => get_all_pairs_addresses
         getAllPairTokens => get_all_token_pairs
         getAllPairContractMetadata => get_all_pair_contract_metadata
         getPair => get_pair
         clearPairTemporaryOwnerStorage => clear_pair_temporary_owner_storage
         multiPairSwap => multi_pair_swap
+        multiPairSwapWithOptions => multi_pair_swap_with_options
+        multiPairSwapAndCall => multi_pair_swap_and_call
+        addRouteToken => add_route_token
+        removeRouteToken => remove_route_token
+        getBestRoute => get_best_route
+        swapBestRoute => swap_best_route
+        getRouteTokens => route_tokens
+        splitRouteSwap => split_route_swap
+        upgradeAllPairs => upgrade_all_pairs
+        upgradePairsByFilter => upgrade_pairs_by_filter
+        getPairsNotUpgraded => get_pairs_not_upgraded
         configEnableByUserParameters => config_enable_by_user_parameters
         addCommonTokensForUserPairs => add_common_tokens_for_user_pairs
         removeCommonTokensForUserPairs => remove_common_tokens_for_user_pairs
@@ -0,0 +1,56 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+use pair::swap_hooks::SwapHookPayload;
+
+/// Counts the swap hook calls and can veto swaps or override their fee
+#[multiversx_sc::contract]
+pub trait SwapHookMock {
+    #[init]
+    fn init(&self) {}
+
+    #[endpoint(setVeto)]
+    fn set_veto(&self, veto: bool) {
+        self.veto().set(veto);
+    }
+
+    #[endpoint(setFeeOverride)]
+    fn set_fee_override(&self, fee_override: Option<u64>) {
+        self.fee_override().set(fee_override);
+    }
+
+    #[endpoint(beforeSwap)]
+    fn before_swap(&self, payload: SwapHookPayload<Self::Api>) -> Option<u64> {
+        require!(!self.veto().get(), "Swap vetoed");
+
+        self.before_swap_count().update(|count| *count += 1);
+        self.last_payload().set(payload);
+
+        self.fee_override().get()
+    }
+
+    #[endpoint(afterSwap)]
+    fn after_swap(&self, payload: SwapHookPayload<Self::Api>) {
+        self.after_swap_count().update(|count| *count += 1);
+        self.last_payload().set(payload);
+    }
+
+    #[storage_mapper("veto")]
+    fn veto(&self) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("feeOverride")]
+    fn fee_override(&self) -> SingleValueMapper<Option<u64>>;
+
+    #[view(getBeforeSwapCount)]
+    #[storage_mapper("beforeSwapCount")]
+    fn before_swap_count(&self) -> SingleValueMapper<u64>;
+
+    #[view(getAfterSwapCount)]
+    #[storage_mapper("afterSwapCount")]
+    fn after_swap_count(&self) -> SingleValueMapper<u64>;
+
+    #[view(getLastPayload)]
+    #[storage_mapper("lastPayload")]
+    fn last_payload(&self) -> SingleValueMapper<SwapHookPayload<Self::Api>>;
+}
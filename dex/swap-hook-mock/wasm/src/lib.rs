@@ -0,0 +1,31 @@
+// Code generated by the multiversx-sc build system. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+// Init:                                 1
+// Endpoints:                            7
+// Async Callback (empty):               1
+// Total number of exported functions:   9
+
+#![no_std]
+
+multiversx_sc_wasm_adapter::allocator!();
+multiversx_sc_wasm_adapter::panic_handler!();
+
+multiversx_sc_wasm_adapter::endpoints! {
+    swap_hook_mock
+    (
+        init => init
+        setVeto => set_veto
+        setFeeOverride => set_fee_override
+        beforeSwap => before_swap
+        afterSwap => after_swap
+        getBeforeSwapCount => before_swap_count
+        getAfterSwapCount => after_swap_count
+        getLastPayload => last_payload
+    )
+}
+
+multiversx_sc_wasm_adapter::async_callback_empty! {}
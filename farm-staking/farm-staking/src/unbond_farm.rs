@@ -32,7 +32,10 @@ pub trait UnbondFarmModule:
     #[endpoint(unbondFarm)]
     fn unbond_farm(&self) -> EsdtTokenPayment {
         let storage_cache = StorageCache::new(self);
-        self.validate_contract_state(storage_cache.contract_state, &storage_cache.farm_token_id);
+        self.validate_contract_state_for_exit(
+            storage_cache.contract_state,
+            &storage_cache.farm_token_id,
+        );
 
         let farm_token_mapper = self.farm_token();
         let payment = self.call_value().single_esdt();
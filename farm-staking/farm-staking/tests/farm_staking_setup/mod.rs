@@ -761,6 +761,19 @@ where
             .assert_ok();
     }
 
+    pub fn set_withdraw_only(&mut self) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.farm_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_withdraw_only();
+                },
+            )
+            .assert_ok();
+    }
+
     pub fn set_boosted_yields_rewards_percentage(&mut self, percentage: u64) {
         self.b_mock
             .execute_tx(
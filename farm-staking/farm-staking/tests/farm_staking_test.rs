@@ -314,6 +314,63 @@ fn test_unbond() {
     );
 }
 
+#[test]
+fn test_unstake_and_unbond_withdraw_only() {
+    DebugApi::dummy();
+    let mut farm_setup = FarmStakingSetup::new(
+        farm_staking::contract_obj,
+        energy_factory::contract_obj,
+        permissions_hub::contract_obj,
+    );
+
+    let user_address = farm_setup.user_address.clone();
+
+    let farm_in_amount = 100_000_000;
+    let expected_farm_token_nonce = 1;
+    farm_setup.stake_farm(
+        &user_address,
+        farm_in_amount,
+        &[],
+        expected_farm_token_nonce,
+        0,
+        0,
+    );
+
+    let current_block = 10;
+    let current_epoch = 5;
+    farm_setup.set_block_epoch(current_epoch);
+    farm_setup.set_block_nonce(current_block);
+
+    farm_setup.set_withdraw_only();
+
+    let expected_rewards = 40;
+    let expected_ride_token_balance =
+        rust_biguint!(USER_TOTAL_RIDE_TOKENS) - farm_in_amount + expected_rewards;
+    farm_setup.unstake_farm(
+        &user_address,
+        farm_in_amount,
+        expected_farm_token_nonce,
+        expected_rewards,
+        &expected_ride_token_balance,
+        &expected_ride_token_balance,
+        expected_farm_token_nonce + 1,
+        farm_in_amount,
+        &UnbondSftAttributes {
+            unlock_epoch: current_epoch + MIN_UNBOND_EPOCHS,
+        },
+    );
+    farm_setup.check_farm_token_supply(0);
+
+    farm_setup.set_block_epoch(current_epoch + MIN_UNBOND_EPOCHS);
+
+    farm_setup.unbond_farm(
+        expected_farm_token_nonce + 1,
+        farm_in_amount,
+        farm_in_amount,
+        USER_TOTAL_RIDE_TOKENS + expected_rewards,
+    );
+}
+
 #[test]
 fn test_withdraw_rewards() {
     DebugApi::dummy();
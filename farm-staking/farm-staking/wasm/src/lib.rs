@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           72
+// Endpoints:                           73
 // Async Callback:                       1
-// Total number of exported functions:  75
+// Total number of exported functions:  76
 
 #![no_std]
 
@@ -54,6 +54,7 @@ multiversx_sc_wasm_adapter::endpoints! {
         removeFromPauseWhitelist => remove_from_pause_whitelist
         pause => pause
         resume => resume
+        setWithdrawOnly => set_withdraw_only
         getState => state
         addAdmin => add_admin_endpoint
         removeAdmin => remove_admin_endpoint